@@ -0,0 +1,216 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::context::CliContext;
+use clap::Args;
+use fusionamm_core::MAX_FEE_RATE;
+use fusionamm_sdk::{
+    collect_protocol_fees_instructions, create_fusion_pool_instructions, delete_token_badge_instructions, initialize_config_instructions,
+    initialize_token_badge_instructions, reset_pool_price_instructions, set_collect_protocol_fees_authority_instructions,
+    set_default_protocol_fee_rate_instructions, set_fee_authority_instructions, set_fee_rate_instructions, set_protocol_fee_rate_instructions,
+    set_token_badge_authority_instructions,
+};
+use serde_json::{json, Value};
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct InitializeConfigArgs {
+    /// Authority who can collect the protocol fees. Also used as the default for the other authorities.
+    default_authority: Pubkey,
+
+    /// Portion of the fee rate taken by the protocol, in basis points.
+    default_protocol_fee_rate: u16,
+
+    /// Authority who can change the fee rates.
+    #[arg(long)]
+    fee_authority: Option<Pubkey>,
+
+    /// Authority who can create token badges.
+    #[arg(long)]
+    token_badge_authority: Option<Pubkey>,
+}
+
+#[derive(Args)]
+pub struct InitializePoolArgs {
+    /// Token A mint address.
+    token_mint_a: Pubkey,
+
+    /// Token B mint address.
+    token_mint_b: Pubkey,
+
+    /// Tick spacing.
+    tick_spacing: u16,
+
+    /// Fee rate, in hundredths of a basis point.
+    fee_rate: u16,
+
+    /// Initial price.
+    initial_price: f64,
+}
+
+#[derive(Args)]
+pub struct TokenBadgeArgs {
+    /// Token mint address.
+    mint: Pubkey,
+}
+
+#[derive(Args)]
+pub struct ResetPoolPriceArgs {
+    /// Fusion pool address.
+    pool: Pubkey,
+
+    /// New pool price.
+    price: f64,
+}
+
+#[derive(Args)]
+pub struct SetFeeRateArgs {
+    /// Fusion pool address.
+    pool: Pubkey,
+
+    /// Fee rate, in hundredths of a basis point. The maximum value 60000 equals to 6%.
+    fee_rate: u16,
+}
+
+#[derive(Args)]
+pub struct SetProtocolFeeRateArgs {
+    /// Fusion pool address.
+    pool: Pubkey,
+
+    /// Protocol fee rate, in basis points. The maximum value 2500 equals to 25%.
+    protocol_fee_rate: u16,
+}
+
+#[derive(Args)]
+pub struct SetDefaultProtocolFeeRateArgs {
+    /// Protocol fee rate of new pools, in basis points. The maximum value 2500 equals to 25%.
+    default_protocol_fee_rate: u16,
+}
+
+#[derive(Args)]
+pub struct SetAuthorityArgs {
+    /// New authority address.
+    new_authority: Pubkey,
+}
+
+#[derive(Args)]
+pub struct CollectProtocolFeesArgs {
+    /// Fusion pool address.
+    pool: Pubkey,
+}
+
+async fn send_or_skip(ctx: &CliContext, instructions: Vec<Instruction>) -> Result<(), Box<dyn Error>> {
+    if instructions.is_empty() {
+        ctx.log("Nothing to update!");
+        return ctx.output(json!({ "transaction": Value::Null }));
+    }
+    let transaction = ctx.send_transaction(instructions, vec![]).await?;
+    ctx.output(json!({ "transaction": transaction }))
+}
+
+pub async fn initialize_config(ctx: &CliContext, args: InitializeConfigArgs) -> Result<(), Box<dyn Error>> {
+    let instructions = initialize_config_instructions(
+        &ctx.rpc,
+        args.fee_authority.unwrap_or(args.default_authority),
+        args.default_authority,
+        args.token_badge_authority.unwrap_or(args.default_authority),
+        args.default_protocol_fee_rate,
+        Some(ctx.signer_pubkey()?),
+    )
+    .await?;
+    send_or_skip(ctx, instructions).await
+}
+
+pub async fn initialize_pool(ctx: &CliContext, args: InitializePoolArgs) -> Result<(), Box<dyn Error>> {
+    if args.fee_rate > MAX_FEE_RATE {
+        return Err(format!("Fee rate {} exceeds the maximum of {}", args.fee_rate, MAX_FEE_RATE).into());
+    }
+    if args.initial_price <= 0.0 {
+        return Err("The initial price must be greater than zero".into());
+    }
+
+    let result = create_fusion_pool_instructions(
+        &ctx.rpc,
+        args.token_mint_a,
+        args.token_mint_b,
+        args.tick_spacing,
+        args.fee_rate,
+        Some(args.initial_price),
+        Some(ctx.signer_pubkey()?),
+    )
+    .await?;
+    ctx.log(format!("Creating a fusion pool at address {}", result.pool_address));
+    let transaction = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+
+    ctx.output(json!({
+        "pool": result.pool_address.to_string(),
+        "initialization_cost": result.initialization_cost,
+        "transaction": transaction,
+    }))
+}
+
+pub async fn initialize_token_badge(ctx: &CliContext, args: TokenBadgeArgs) -> Result<(), Box<dyn Error>> {
+    let instructions = initialize_token_badge_instructions(&ctx.rpc, args.mint, Some(ctx.signer_pubkey()?)).await?;
+    send_or_skip(ctx, instructions).await
+}
+
+pub async fn delete_token_badge(ctx: &CliContext, args: TokenBadgeArgs) -> Result<(), Box<dyn Error>> {
+    let instructions = delete_token_badge_instructions(&ctx.rpc, args.mint, Some(ctx.signer_pubkey()?)).await?;
+    send_or_skip(ctx, instructions).await
+}
+
+pub async fn reset_pool_price(ctx: &CliContext, args: ResetPoolPriceArgs) -> Result<(), Box<dyn Error>> {
+    let instructions = reset_pool_price_instructions(&ctx.rpc, args.pool, args.price, Some(ctx.signer_pubkey()?)).await?;
+    send_or_skip(ctx, instructions).await
+}
+
+pub async fn set_fee_rate(ctx: &CliContext, args: SetFeeRateArgs) -> Result<(), Box<dyn Error>> {
+    let instructions = set_fee_rate_instructions(&ctx.rpc, args.pool, args.fee_rate, Some(ctx.signer_pubkey()?)).await?;
+    send_or_skip(ctx, instructions).await
+}
+
+pub async fn set_protocol_fee_rate(ctx: &CliContext, args: SetProtocolFeeRateArgs) -> Result<(), Box<dyn Error>> {
+    let instructions = set_protocol_fee_rate_instructions(&ctx.rpc, args.pool, args.protocol_fee_rate, Some(ctx.signer_pubkey()?)).await?;
+    send_or_skip(ctx, instructions).await
+}
+
+pub async fn set_default_protocol_fee_rate(ctx: &CliContext, args: SetDefaultProtocolFeeRateArgs) -> Result<(), Box<dyn Error>> {
+    let instructions = set_default_protocol_fee_rate_instructions(&ctx.rpc, args.default_protocol_fee_rate, Some(ctx.signer_pubkey()?)).await?;
+    send_or_skip(ctx, instructions).await
+}
+
+pub async fn set_fee_authority(ctx: &CliContext, args: SetAuthorityArgs) -> Result<(), Box<dyn Error>> {
+    let instructions = set_fee_authority_instructions(&ctx.rpc, args.new_authority, Some(ctx.signer_pubkey()?)).await?;
+    send_or_skip(ctx, instructions).await
+}
+
+pub async fn set_collect_protocol_fees_authority(ctx: &CliContext, args: SetAuthorityArgs) -> Result<(), Box<dyn Error>> {
+    let instructions = set_collect_protocol_fees_authority_instructions(&ctx.rpc, args.new_authority, Some(ctx.signer_pubkey()?)).await?;
+    send_or_skip(ctx, instructions).await
+}
+
+pub async fn set_token_badge_authority(ctx: &CliContext, args: SetAuthorityArgs) -> Result<(), Box<dyn Error>> {
+    let instructions = set_token_badge_authority_instructions(&ctx.rpc, args.new_authority, Some(ctx.signer_pubkey()?)).await?;
+    send_or_skip(ctx, instructions).await
+}
+
+pub async fn collect_protocol_fees(ctx: &CliContext, args: CollectProtocolFeesArgs) -> Result<(), Box<dyn Error>> {
+    let result = collect_protocol_fees_instructions(&ctx.rpc, args.pool, Some(ctx.signer_pubkey()?)).await?;
+    ctx.log(format!("Protocol fees owed: {} A, {} B", result.protocol_fee_owed_a, result.protocol_fee_owed_b));
+    let transaction = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+
+    ctx.output(json!({
+        "pool": args.pool.to_string(),
+        "protocol_fee_owed_a": result.protocol_fee_owed_a,
+        "protocol_fee_owed_b": result.protocol_fee_owed_b,
+        "amount_a": result.amount_a,
+        "amount_b": result.amount_b,
+        "transaction": transaction,
+    }))
+}
@@ -0,0 +1,240 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::commands::fetch_pool_with_decimals;
+use crate::context::CliContext;
+use clap::Args;
+use fusionamm_client::{
+    fetch_all_fusion_pool_with_filter, fetch_all_limit_order_with_filter, get_fusion_pools_config_address, get_tick_array_address, FusionPoolsConfig,
+    LimitOrder, LimitOrderFilter, Position, TickArray,
+};
+use fusionamm_core::{
+    decrease_limit_order_quote, get_tick_array_start_tick_index, get_tick_index_in_array, sqrt_price_to_price, tick_index_to_price, TickFacade,
+    TICK_ARRAY_SIZE,
+};
+use fusionamm_sdk::{fetch_positions_for_owner, fetch_positions_in_fusion_pool, PositionOrBundle};
+use serde_json::{json, Value};
+use solana_pubkey::Pubkey;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct FetchPoolArgs {
+    /// Fusion pool address. Lists all pools if not provided.
+    pool: Option<Pubkey>,
+}
+
+#[derive(Args)]
+pub struct FetchPositionArgs {
+    /// Position address.
+    position: Option<Pubkey>,
+
+    /// List the positions of a fusion pool.
+    #[arg(long, conflicts_with = "position")]
+    pool: Option<Pubkey>,
+
+    /// List the positions of an owner.
+    #[arg(long, conflicts_with_all = ["position", "pool"])]
+    owner: Option<Pubkey>,
+}
+
+#[derive(Args)]
+pub struct FetchLimitOrderArgs {
+    /// Limit order address.
+    limit_order: Option<Pubkey>,
+
+    /// List the limit orders of a fusion pool.
+    #[arg(long, conflicts_with = "limit_order")]
+    pool: Option<Pubkey>,
+}
+
+#[derive(Args)]
+pub struct FetchTickArrayArgs {
+    /// Tick array address.
+    tick_array: Option<Pubkey>,
+
+    /// Fusion pool address. Used with --tick-index instead of the tick array address.
+    #[arg(long, conflicts_with = "tick_array", requires = "tick_index")]
+    pool: Option<Pubkey>,
+
+    /// A tick index inside the tick array.
+    #[arg(long, allow_hyphen_values = true, requires = "pool")]
+    tick_index: Option<i32>,
+}
+
+pub async fn fetch_config(ctx: &CliContext) -> Result<(), Box<dyn Error>> {
+    let config_address = get_fusion_pools_config_address()?.0;
+    ctx.log(format!("Fetching the config at address {}...", config_address));
+    let config_info = ctx.rpc.get_account(&config_address).await?;
+    let config = FusionPoolsConfig::from_bytes(&config_info.data)?;
+
+    ctx.output(json!({
+        "address": config_address.to_string(),
+        "config": serde_json::to_value(&config)?,
+    }))
+}
+
+pub async fn fetch_pool(ctx: &CliContext, args: FetchPoolArgs) -> Result<(), Box<dyn Error>> {
+    let Some(pool_address) = args.pool else {
+        ctx.log("Fetching fusion pools...");
+        let pools = fetch_all_fusion_pool_with_filter(&ctx.rpc, vec![]).await?;
+        return ctx.output(Value::Array(pools.iter().map(|pool| json!(pool.address.to_string())).collect()));
+    };
+
+    ctx.log(format!("Fetching fusion pool at address {}...", pool_address));
+    let pool = fetch_pool_with_decimals(ctx, pool_address).await?;
+
+    ctx.output(json!({
+        "address": pool_address.to_string(),
+        "pool": serde_json::to_value(&pool.pool)?,
+        "price": sqrt_price_to_price(pool.pool.sqrt_price, pool.decimals_a, pool.decimals_b),
+        "ma_price": sqrt_price_to_price(pool.pool.ma_sqrt_price, pool.decimals_a, pool.decimals_b),
+    }))
+}
+
+pub async fn fetch_position(ctx: &CliContext, args: FetchPositionArgs) -> Result<(), Box<dyn Error>> {
+    if let Some(position_address) = args.position {
+        ctx.log(format!("Fetching position at address {}...", position_address));
+        let position_info = ctx.rpc.get_account(&position_address).await?;
+        let position = Position::from_bytes(&position_info.data)?;
+        let pool = fetch_pool_with_decimals(ctx, position.fusion_pool).await?;
+
+        return ctx.output(json!({
+            "address": position_address.to_string(),
+            "position": serde_json::to_value(&position)?,
+            "lower_price": tick_index_to_price(position.tick_lower_index, pool.decimals_a, pool.decimals_b),
+            "upper_price": tick_index_to_price(position.tick_upper_index, pool.decimals_a, pool.decimals_b),
+            "pool_price": sqrt_price_to_price(pool.pool.sqrt_price, pool.decimals_a, pool.decimals_b),
+        }));
+    }
+
+    if let Some(pool_address) = args.pool {
+        ctx.log(format!("Fetching the positions of fusion pool {}...", pool_address));
+        let positions = fetch_positions_in_fusion_pool(&ctx.rpc, pool_address).await?;
+        let positions = positions
+            .iter()
+            .map(|position| Ok(json!({ "address": position.address.to_string(), "position": serde_json::to_value(&position.data)? })))
+            .collect::<Result<Vec<Value>, serde_json::Error>>()?;
+        return ctx.output(Value::Array(positions));
+    }
+
+    let owner = match args.owner {
+        Some(owner) => owner,
+        None => ctx.signer_pubkey()?,
+    };
+    ctx.log(format!("Fetching the positions of owner {}...", owner));
+    let positions = fetch_positions_for_owner(&ctx.rpc, owner).await?;
+    let mut items: Vec<Value> = Vec::new();
+    for position in positions {
+        match position {
+            PositionOrBundle::Position(position) => items.push(json!({
+                "address": position.address.to_string(),
+                "position": serde_json::to_value(&position.data)?,
+                "locked": position.is_locked(),
+            })),
+            PositionOrBundle::PositionBundle(bundle) => {
+                for position in bundle.positions {
+                    items.push(json!({
+                        "address": position.address.to_string(),
+                        "position_bundle": bundle.address.to_string(),
+                        "position": serde_json::to_value(&position.data)?,
+                    }));
+                }
+            }
+        }
+    }
+    ctx.output(Value::Array(items))
+}
+
+async fn limit_order_to_json(ctx: &CliContext, address: Pubkey, limit_order: &LimitOrder) -> Result<Value, Box<dyn Error>> {
+    let pool = fetch_pool_with_decimals(ctx, limit_order.fusion_pool).await?;
+    let tick_spacing = pool.pool.tick_spacing;
+
+    let tick_array_start_index = get_tick_array_start_tick_index(limit_order.tick_index, tick_spacing);
+    let tick_array_address = get_tick_array_address(&limit_order.fusion_pool, tick_array_start_index)?.0;
+    let tick_array_info = ctx.rpc.get_account(&tick_array_address).await?;
+    let tick_array = TickArray::from_bytes(&tick_array_info.data)?;
+    let tick = tick_array.ticks[get_tick_index_in_array(limit_order.tick_index, tick_array_start_index, tick_spacing)? as usize].clone();
+
+    let quote = decrease_limit_order_quote(pool.pool.clone().into(), limit_order.clone().into(), tick.into(), limit_order.amount, None, None)?;
+    let remaining_input = if limit_order.a_to_b { quote.amount_out_a } else { quote.amount_out_b };
+    let fill = if limit_order.amount > 0 {
+        limit_order.amount.saturating_sub(remaining_input) as f64 / limit_order.amount as f64
+    } else {
+        0.0
+    };
+
+    Ok(json!({
+        "address": address.to_string(),
+        "limit_order": serde_json::to_value(limit_order)?,
+        "price": tick_index_to_price(limit_order.tick_index, pool.decimals_a, pool.decimals_b),
+        "fill": fill,
+        "amount_out_a": quote.amount_out_a,
+        "amount_out_b": quote.amount_out_b,
+    }))
+}
+
+pub async fn fetch_limit_order(ctx: &CliContext, args: FetchLimitOrderArgs) -> Result<(), Box<dyn Error>> {
+    if let Some(limit_order_address) = args.limit_order {
+        ctx.log(format!("Fetching limit order at address {}...", limit_order_address));
+        let limit_order_info = ctx.rpc.get_account(&limit_order_address).await?;
+        let limit_order = LimitOrder::from_bytes(&limit_order_info.data)?;
+        return ctx.output(limit_order_to_json(ctx, limit_order_address, &limit_order).await?);
+    }
+
+    let pool_address = args.pool.ok_or("Either the limit order address or --pool must be provided")?;
+    ctx.log(format!("Fetching the limit orders of fusion pool {}...", pool_address));
+    let limit_orders = fetch_all_limit_order_with_filter(&ctx.rpc, vec![LimitOrderFilter::FusionPool(pool_address)]).await?;
+    let mut items: Vec<Value> = Vec::new();
+    for limit_order in limit_orders {
+        items.push(limit_order_to_json(ctx, limit_order.address, &limit_order.data).await?);
+    }
+    ctx.output(Value::Array(items))
+}
+
+pub async fn fetch_tick_array(ctx: &CliContext, args: FetchTickArrayArgs) -> Result<(), Box<dyn Error>> {
+    let tick_array_address = match (args.tick_array, args.pool, args.tick_index) {
+        (Some(address), _, _) => address,
+        (None, Some(pool_address), Some(tick_index)) => {
+            let pool = fetch_pool_with_decimals(ctx, pool_address).await?;
+            let start_tick_index = get_tick_array_start_tick_index(tick_index, pool.pool.tick_spacing);
+            get_tick_array_address(&pool_address, start_tick_index)?.0
+        }
+        _ => return Err("Either the tick array address or --pool and --tick-index must be provided".into()),
+    };
+
+    ctx.log(format!("Fetching tick array at address {}...", tick_array_address));
+    let tick_array_info = ctx.rpc.get_account(&tick_array_address).await?;
+    let tick_array = TickArray::from_bytes(&tick_array_info.data)?;
+    let pool = fetch_pool_with_decimals(ctx, tick_array.fusion_pool).await?;
+    let tick_spacing = pool.pool.tick_spacing as i32;
+
+    let mut ticks: Vec<Value> = Vec::new();
+    for (i, tick) in tick_array.ticks.iter().enumerate().take(TICK_ARRAY_SIZE) {
+        let tick: TickFacade = tick.clone().into();
+        if !tick.initialized {
+            continue;
+        }
+        let tick_index = tick_array.start_tick_index + i as i32 * tick_spacing;
+        ticks.push(json!({
+            "tick_index": tick_index,
+            "price": tick_index_to_price(tick_index, pool.decimals_a, pool.decimals_b),
+            "liquidity_net": tick.liquidity_net.to_string(),
+            "liquidity_gross": tick.liquidity_gross.to_string(),
+            "open_orders_input": tick.open_orders_input,
+            "part_filled_orders_input": tick.part_filled_orders_input,
+            "fulfilled_a_to_b_orders_input": tick.fulfilled_a_to_b_orders_input,
+            "fulfilled_b_to_a_orders_input": tick.fulfilled_b_to_a_orders_input,
+        }));
+    }
+
+    ctx.output(json!({
+        "address": tick_array_address.to_string(),
+        "fusion_pool": tick_array.fusion_pool.to_string(),
+        "start_tick_index": tick_array.start_tick_index,
+        "initialized_ticks": ticks,
+    }))
+}
@@ -0,0 +1,105 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::commands::parse_direction;
+use crate::context::CliContext;
+use clap::Args;
+use fusionamm_sdk::{
+    close_limit_order_instructions, decrease_limit_order_instructions, increase_limit_order_instructions, open_limit_order_instructions,
+    PriceOrTickIndex,
+};
+use serde_json::json;
+use solana_pubkey::Pubkey;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct IncreaseLimitOrderArgs {
+    /// Fusion pool address.
+    pool: Pubkey,
+
+    /// Limit order mint address. Required for an existing limit order.
+    #[arg(long, conflicts_with_all = ["price", "a_to_b", "b_to_a"])]
+    limit_order_mint: Option<Pubkey>,
+
+    /// Provided amount of the input token.
+    #[arg(long)]
+    amount: u64,
+
+    /// Limit order price. Only required for a new order.
+    #[arg(long)]
+    price: Option<f64>,
+
+    /// Set the limit order A to B swap direction. Only required for a new order.
+    #[arg(long)]
+    a_to_b: bool,
+
+    /// Set the limit order B to A swap direction. Only required for a new order.
+    #[arg(long)]
+    b_to_a: bool,
+}
+
+#[derive(Args)]
+pub struct DecreaseLimitOrderArgs {
+    /// Limit order mint address.
+    limit_order_mint: Pubkey,
+
+    /// The amount by which the limit order is reduced. The limit order is fully closed if not provided.
+    amount: Option<u64>,
+}
+
+pub async fn increase_limit_order(ctx: &CliContext, args: IncreaseLimitOrderArgs) -> Result<(), Box<dyn Error>> {
+    if let Some(limit_order_mint) = args.limit_order_mint {
+        ctx.log(format!("Increasing the limit order with mint {}...", limit_order_mint));
+        let result = increase_limit_order_instructions(&ctx.rpc, limit_order_mint, args.amount, Some(ctx.signer_pubkey()?)).await?;
+        let transaction = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+
+        return ctx.output(json!({
+            "limit_order_mint": limit_order_mint.to_string(),
+            "quote_a": result.quote_a,
+            "quote_b": result.quote_b,
+            "transaction": transaction,
+        }));
+    }
+
+    let price = args.price.ok_or("--price must be specified to open a new limit order")?;
+    let a_to_b = parse_direction(args.a_to_b, args.b_to_a)?;
+
+    let result =
+        open_limit_order_instructions(&ctx.rpc, args.pool, args.amount, PriceOrTickIndex::Price(price), a_to_b, Some(ctx.signer_pubkey()?)).await?;
+    ctx.log(format!("Opening a new limit order with mint address {}", result.limit_order_mint));
+    let transaction = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+
+    ctx.output(json!({
+        "limit_order_mint": result.limit_order_mint.to_string(),
+        "quote_a": result.quote_a,
+        "quote_b": result.quote_b,
+        "transaction": transaction,
+    }))
+}
+
+pub async fn decrease_limit_order(ctx: &CliContext, args: DecreaseLimitOrderArgs) -> Result<(), Box<dyn Error>> {
+    let result = match args.amount {
+        Some(amount) => {
+            ctx.log(format!("Decreasing the limit order with mint {}...", args.limit_order_mint));
+            decrease_limit_order_instructions(&ctx.rpc, args.limit_order_mint, amount, Some(ctx.signer_pubkey()?)).await?
+        }
+        None => {
+            ctx.log(format!("Closing the limit order with mint {}...", args.limit_order_mint));
+            close_limit_order_instructions(&ctx.rpc, args.limit_order_mint, Some(ctx.signer_pubkey()?)).await?
+        }
+    };
+    let transaction = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+
+    ctx.output(json!({
+        "limit_order_mint": args.limit_order_mint.to_string(),
+        "amount_out_a": result.quote.amount_out_a,
+        "amount_out_b": result.quote.amount_out_b,
+        "reward_a": result.quote.reward_a,
+        "reward_b": result.quote.reward_b,
+        "transaction": transaction,
+    }))
+}
@@ -0,0 +1,142 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+mod admin;
+mod fetch;
+mod limit_order;
+mod position;
+mod swap;
+
+use crate::context::CliContext;
+use clap::Subcommand;
+use fusionamm_client::FusionPool;
+use solana_pubkey::Pubkey;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Mint;
+use std::error::Error;
+
+#[derive(Subcommand)]
+#[command(rename_all = "snake_case")]
+pub enum Command {
+    /// Fetch the FusionPoolsConfig account.
+    FetchConfig,
+    /// Fetch a fusion pool or the list of pools.
+    FetchPool(fetch::FetchPoolArgs),
+    /// Fetch a position or the list of positions of a pool or an owner.
+    FetchPosition(fetch::FetchPositionArgs),
+    /// Fetch a limit order or the list of limit orders of a pool.
+    FetchLimitOrder(fetch::FetchLimitOrderArgs),
+    /// Fetch a tick array by address or by pool and tick index.
+    FetchTickArray(fetch::FetchTickArrayArgs),
+    /// Quote a swap without sending it.
+    SwapQuote(swap::SwapArgs),
+    /// Execute a swap.
+    Swap(swap::SwapArgs),
+    /// Add liquidity to a position. Opens a new position if a position mint is not provided.
+    IncreaseLiquidity(position::IncreaseLiquidityArgs),
+    /// Remove liquidity from a position. Fully closes the position if amounts or liquidity are not provided.
+    DecreaseLiquidity(position::DecreaseLiquidityArgs),
+    /// Collect the fees of a position.
+    Harvest(position::HarvestArgs),
+    /// Add to a limit order. Opens a new limit order if a limit order mint is not provided.
+    IncreaseLimitOrder(limit_order::IncreaseLimitOrderArgs),
+    /// Decrease a limit order. Fully closes the limit order if the amount is not provided.
+    DecreaseLimitOrder(limit_order::DecreaseLimitOrderArgs),
+    /// Create the FusionPoolsConfig account.
+    InitializeConfig(admin::InitializeConfigArgs),
+    /// Create a fusion pool.
+    InitializePool(admin::InitializePoolArgs),
+    /// Create a token badge.
+    InitializeTokenBadge(admin::TokenBadgeArgs),
+    /// Delete a token badge.
+    DeleteTokenBadge(admin::TokenBadgeArgs),
+    /// Reset the price of an empty fusion pool.
+    ResetPoolPrice(admin::ResetPoolPriceArgs),
+    /// Set the fee rate of a pool.
+    SetFeeRate(admin::SetFeeRateArgs),
+    /// Set the protocol fee rate of a pool.
+    SetProtocolFeeRate(admin::SetProtocolFeeRateArgs),
+    /// Set the protocol fee rate of new pools.
+    SetDefaultProtocolFeeRate(admin::SetDefaultProtocolFeeRateArgs),
+    /// Transfer the fee authority.
+    SetFeeAuthority(admin::SetAuthorityArgs),
+    /// Transfer the collect protocol fees authority.
+    SetCollectProtocolFeesAuthority(admin::SetAuthorityArgs),
+    /// Transfer the token badge authority.
+    SetTokenBadgeAuthority(admin::SetAuthorityArgs),
+    /// Collect the protocol fees of a pool.
+    CollectProtocolFees(admin::CollectProtocolFeesArgs),
+}
+
+impl Command {
+    pub async fn run(self, ctx: &CliContext) -> Result<(), Box<dyn Error>> {
+        match self {
+            Command::FetchConfig => fetch::fetch_config(ctx).await,
+            Command::FetchPool(args) => fetch::fetch_pool(ctx, args).await,
+            Command::FetchPosition(args) => fetch::fetch_position(ctx, args).await,
+            Command::FetchLimitOrder(args) => fetch::fetch_limit_order(ctx, args).await,
+            Command::FetchTickArray(args) => fetch::fetch_tick_array(ctx, args).await,
+            Command::SwapQuote(args) => swap::swap(ctx, args, false).await,
+            Command::Swap(args) => swap::swap(ctx, args, true).await,
+            Command::IncreaseLiquidity(args) => position::increase_liquidity(ctx, args).await,
+            Command::DecreaseLiquidity(args) => position::decrease_liquidity(ctx, args).await,
+            Command::Harvest(args) => position::harvest(ctx, args).await,
+            Command::IncreaseLimitOrder(args) => limit_order::increase_limit_order(ctx, args).await,
+            Command::DecreaseLimitOrder(args) => limit_order::decrease_limit_order(ctx, args).await,
+            Command::InitializeConfig(args) => admin::initialize_config(ctx, args).await,
+            Command::InitializePool(args) => admin::initialize_pool(ctx, args).await,
+            Command::InitializeTokenBadge(args) => admin::initialize_token_badge(ctx, args).await,
+            Command::DeleteTokenBadge(args) => admin::delete_token_badge(ctx, args).await,
+            Command::ResetPoolPrice(args) => admin::reset_pool_price(ctx, args).await,
+            Command::SetFeeRate(args) => admin::set_fee_rate(ctx, args).await,
+            Command::SetProtocolFeeRate(args) => admin::set_protocol_fee_rate(ctx, args).await,
+            Command::SetDefaultProtocolFeeRate(args) => admin::set_default_protocol_fee_rate(ctx, args).await,
+            Command::SetFeeAuthority(args) => admin::set_fee_authority(ctx, args).await,
+            Command::SetCollectProtocolFeesAuthority(args) => admin::set_collect_protocol_fees_authority(ctx, args).await,
+            Command::SetTokenBadgeAuthority(args) => admin::set_token_badge_authority(ctx, args).await,
+            Command::CollectProtocolFees(args) => admin::collect_protocol_fees(ctx, args).await,
+        }
+    }
+}
+
+/// A fusion pool together with the decimals of its token mints.
+pub(crate) struct PoolWithDecimals {
+    pub pool: FusionPool,
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+}
+
+pub(crate) async fn fetch_pool_with_decimals(ctx: &CliContext, pool_address: Pubkey) -> Result<PoolWithDecimals, Box<dyn Error>> {
+    let pool_info = ctx
+        .rpc
+        .get_account(&pool_address)
+        .await
+        .map_err(|_| format!("Fusion pool is not found at address {}", pool_address))?;
+    let pool = FusionPool::from_bytes(&pool_info.data)?;
+
+    let mint_infos = ctx.rpc.get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b]).await?;
+    let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
+    let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
+    let decimals_a = StateWithExtensions::<Mint>::unpack(&mint_a_info.data)?.base.decimals;
+    let decimals_b = StateWithExtensions::<Mint>::unpack(&mint_b_info.data)?.base.decimals;
+
+    Ok(PoolWithDecimals {
+        pool,
+        decimals_a,
+        decimals_b,
+    })
+}
+
+/// Parses the mutually exclusive `--a-to-b` and `--b-to-a` flags.
+pub(crate) fn parse_direction(a_to_b: bool, b_to_a: bool) -> Result<bool, Box<dyn Error>> {
+    match (a_to_b, b_to_a) {
+        (true, false) => Ok(true),
+        (false, true) => Ok(false),
+        (true, true) => Err("--a-to-b and --b-to-a can't be used together".into()),
+        (false, false) => Err("Swap direction flag must be set".into()),
+    }
+}
@@ -0,0 +1,193 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::context::CliContext;
+use clap::{ArgGroup, Args};
+use fusionamm_core::{DecreaseLiquidityQuote, IncreaseLiquidityQuote};
+use fusionamm_sdk::{
+    close_position_instructions, decrease_liquidity_instructions, harvest_position_instructions, increase_liquidity_instructions,
+    open_position_instructions, DecreaseLiquidityParam, IncreaseLiquidityParam, PriceOrTickIndex,
+};
+use serde_json::{json, Value};
+use solana_pubkey::Pubkey;
+use std::error::Error;
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("increase_amount").required(true).args(["amount_a", "amount_b", "liquidity"])))]
+pub struct IncreaseLiquidityArgs {
+    /// Fusion pool address.
+    pool: Pubkey,
+
+    /// Position mint address. Required for an existing position.
+    #[arg(long, conflicts_with_all = ["lower_price", "upper_price"])]
+    position_mint: Option<Pubkey>,
+
+    /// Lower price. Only required for a new position.
+    #[arg(long, requires = "upper_price")]
+    lower_price: Option<f64>,
+
+    /// Upper price. Only required for a new position.
+    #[arg(long, requires = "lower_price")]
+    upper_price: Option<f64>,
+
+    /// Provided amount of token A.
+    #[arg(long)]
+    amount_a: Option<u64>,
+
+    /// Provided amount of token B.
+    #[arg(long)]
+    amount_b: Option<u64>,
+
+    /// Provided liquidity.
+    #[arg(long)]
+    liquidity: Option<u128>,
+
+    /// Slippage tolerance in basis points.
+    #[arg(long)]
+    slippage_tolerance_bps: Option<u16>,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("decrease_amount").args(["amount_a", "amount_b", "liquidity"])))]
+pub struct DecreaseLiquidityArgs {
+    /// Position mint address.
+    position_mint: Pubkey,
+
+    /// Withdrawn amount of token A.
+    #[arg(long)]
+    amount_a: Option<u64>,
+
+    /// Withdrawn amount of token B.
+    #[arg(long)]
+    amount_b: Option<u64>,
+
+    /// Withdrawn liquidity.
+    #[arg(long)]
+    liquidity: Option<u128>,
+
+    /// Slippage tolerance in basis points.
+    #[arg(long)]
+    slippage_tolerance_bps: Option<u16>,
+}
+
+#[derive(Args)]
+pub struct HarvestArgs {
+    /// Position mint address.
+    position_mint: Pubkey,
+}
+
+fn increase_quote_to_json(quote: &IncreaseLiquidityQuote) -> Value {
+    json!({
+        "liquidity_delta": quote.liquidity_delta.to_string(),
+        "token_est_a": quote.token_est_a,
+        "token_est_b": quote.token_est_b,
+        "token_max_a": quote.token_max_a,
+        "token_max_b": quote.token_max_b,
+    })
+}
+
+fn decrease_quote_to_json(quote: &DecreaseLiquidityQuote) -> Value {
+    json!({
+        "liquidity_delta": quote.liquidity_delta.to_string(),
+        "token_est_a": quote.token_est_a,
+        "token_est_b": quote.token_est_b,
+        "token_min_a": quote.token_min_a,
+        "token_min_b": quote.token_min_b,
+    })
+}
+
+pub async fn increase_liquidity(ctx: &CliContext, args: IncreaseLiquidityArgs) -> Result<(), Box<dyn Error>> {
+    let param = match (args.amount_a, args.amount_b, args.liquidity) {
+        (Some(amount), _, _) => IncreaseLiquidityParam::TokenA(amount),
+        (_, Some(amount), _) => IncreaseLiquidityParam::TokenB(amount),
+        (_, _, Some(liquidity)) => IncreaseLiquidityParam::Liquidity(liquidity),
+        _ => return Err("Exactly one of the following parameters must be provided: liquidity, amount-a, or amount-b".into()),
+    };
+
+    if let Some(position_mint) = args.position_mint {
+        ctx.log(format!("Increasing liquidity of the position with mint {}...", position_mint));
+        let result = increase_liquidity_instructions(&ctx.rpc, position_mint, param, args.slippage_tolerance_bps, Some(ctx.signer_pubkey()?)).await?;
+        let transaction = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+
+        return ctx.output(json!({
+            "position_mint": position_mint.to_string(),
+            "quote": increase_quote_to_json(&result.quote),
+            "transaction": transaction,
+        }));
+    }
+
+    let (Some(lower_price), Some(upper_price)) = (args.lower_price, args.upper_price) else {
+        return Err("--lower-price and --upper-price must be specified to open a new position".into());
+    };
+
+    let result = open_position_instructions(
+        &ctx.rpc,
+        args.pool,
+        PriceOrTickIndex::Price(lower_price),
+        PriceOrTickIndex::Price(upper_price),
+        param,
+        args.slippage_tolerance_bps,
+        Some(ctx.signer_pubkey()?),
+    )
+    .await?;
+    ctx.log(format!("Opening a new position with mint address {}", result.position_mint));
+    let transaction = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+
+    ctx.output(json!({
+        "position_mint": result.position_mint.to_string(),
+        "quote": increase_quote_to_json(&result.quote),
+        "initialization_cost": result.initialization_cost,
+        "transaction": transaction,
+    }))
+}
+
+pub async fn decrease_liquidity(ctx: &CliContext, args: DecreaseLiquidityArgs) -> Result<(), Box<dyn Error>> {
+    let param = match (args.amount_a, args.amount_b, args.liquidity) {
+        (Some(amount), _, _) => Some(DecreaseLiquidityParam::TokenA(amount)),
+        (_, Some(amount), _) => Some(DecreaseLiquidityParam::TokenB(amount)),
+        (_, _, Some(liquidity)) => Some(DecreaseLiquidityParam::Liquidity(liquidity)),
+        _ => None,
+    };
+
+    let Some(param) = param else {
+        ctx.log(format!("Closing the position with mint {}...", args.position_mint));
+        let result = close_position_instructions(&ctx.rpc, args.position_mint, args.slippage_tolerance_bps, Some(ctx.signer_pubkey()?)).await?;
+        let transaction = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+
+        return ctx.output(json!({
+            "position_mint": args.position_mint.to_string(),
+            "quote": decrease_quote_to_json(&result.quote),
+            "fee_owed_a": result.fees_quote.fee_owed_a,
+            "fee_owed_b": result.fees_quote.fee_owed_b,
+            "transaction": transaction,
+        }));
+    };
+
+    ctx.log(format!("Decreasing liquidity of the position with mint {}...", args.position_mint));
+    let result =
+        decrease_liquidity_instructions(&ctx.rpc, args.position_mint, param, args.slippage_tolerance_bps, Some(ctx.signer_pubkey()?)).await?;
+    let transaction = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+
+    ctx.output(json!({
+        "position_mint": args.position_mint.to_string(),
+        "quote": decrease_quote_to_json(&result.quote),
+        "transaction": transaction,
+    }))
+}
+
+pub async fn harvest(ctx: &CliContext, args: HarvestArgs) -> Result<(), Box<dyn Error>> {
+    ctx.log(format!("Harvesting the position with mint {}...", args.position_mint));
+    let result = harvest_position_instructions(&ctx.rpc, args.position_mint, Some(ctx.signer_pubkey()?)).await?;
+    let transaction = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+
+    ctx.output(json!({
+        "position_mint": args.position_mint.to_string(),
+        "fee_owed_a": result.fees_quote.fee_owed_a,
+        "fee_owed_b": result.fees_quote.fee_owed_b,
+        "transaction": transaction,
+    }))
+}
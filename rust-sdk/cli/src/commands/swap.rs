@@ -0,0 +1,114 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::commands::{fetch_pool_with_decimals, parse_direction};
+use crate::context::CliContext;
+use clap::Args;
+use fusionamm_core::sqrt_price_to_price;
+use fusionamm_sdk::{swap_instructions, SwapQuote, SwapType};
+use serde_json::{json, Value};
+use solana_pubkey::Pubkey;
+use std::error::Error;
+
+/// Token owner used to build a quote-only swap when no keypair is available. Must differ from the default pubkey.
+const QUOTE_SIGNER: Pubkey = Pubkey::new_from_array([1; 32]);
+
+#[derive(Args)]
+pub struct SwapArgs {
+    /// Fusion pool address.
+    pool: Pubkey,
+
+    /// Input amount of the swap.
+    #[arg(long, conflicts_with = "amount_out", required_unless_present = "amount_out")]
+    amount_in: Option<u64>,
+
+    /// Output amount of the swap.
+    #[arg(long)]
+    amount_out: Option<u64>,
+
+    /// Swap A to B.
+    #[arg(long)]
+    a_to_b: bool,
+
+    /// Swap B to A.
+    #[arg(long)]
+    b_to_a: bool,
+
+    /// Slippage tolerance in basis points.
+    #[arg(long)]
+    slippage_tolerance_bps: Option<u16>,
+}
+
+fn swap_quote_to_json(quote: &SwapQuote, decimals_a: u8, decimals_b: u8) -> Value {
+    match quote {
+        SwapQuote::ExactIn(quote) => json!({
+            "token_in": quote.token_in,
+            "token_est_out": quote.token_est_out,
+            "token_min_out": quote.token_min_out,
+            "trade_fee": quote.trade_fee,
+            "price_impact_bps": quote.price_impact_bps,
+            "execution_price": sqrt_price_to_price(quote.execution_sqrt_price, decimals_a, decimals_b),
+            "next_price": sqrt_price_to_price(quote.next_sqrt_price, decimals_a, decimals_b),
+            "liquidity_amount_in": quote.liquidity_amount_in,
+            "limit_order_amount_in": quote.limit_order_amount_in,
+            "ticks_crossed": quote.ticks_crossed,
+        }),
+        SwapQuote::ExactOut(quote) => json!({
+            "token_out": quote.token_out,
+            "token_est_in": quote.token_est_in,
+            "token_max_in": quote.token_max_in,
+            "trade_fee": quote.trade_fee,
+            "price_impact_bps": quote.price_impact_bps,
+            "execution_price": sqrt_price_to_price(quote.execution_sqrt_price, decimals_a, decimals_b),
+            "next_price": sqrt_price_to_price(quote.next_sqrt_price, decimals_a, decimals_b),
+            "liquidity_amount_in": quote.liquidity_amount_in,
+            "limit_order_amount_in": quote.limit_order_amount_in,
+            "ticks_crossed": quote.ticks_crossed,
+        }),
+    }
+}
+
+pub async fn swap(ctx: &CliContext, args: SwapArgs, send: bool) -> Result<(), Box<dyn Error>> {
+    let a_to_b = parse_direction(args.a_to_b, args.b_to_a)?;
+
+    ctx.log("Fetching accounts...");
+    let pool = fetch_pool_with_decimals(ctx, args.pool).await?;
+    let price = sqrt_price_to_price(pool.pool.sqrt_price, pool.decimals_a, pool.decimals_b);
+    ctx.log(format!("Current pool price: {}", price));
+
+    let (input_mint, output_mint) = if a_to_b {
+        (pool.pool.token_mint_a, pool.pool.token_mint_b)
+    } else {
+        (pool.pool.token_mint_b, pool.pool.token_mint_a)
+    };
+    let (amount, specified_mint, swap_type) = match (args.amount_in, args.amount_out) {
+        (Some(amount), _) => (amount, input_mint, SwapType::ExactIn),
+        (None, Some(amount)) => (amount, output_mint, SwapType::ExactOut),
+        (None, None) => return Err("--amount-in or --amount-out must be set".into()),
+    };
+
+    // A quote doesn't sign anything, so it falls back to a placeholder owner when no keypair is available.
+    let signer = if send {
+        ctx.signer_pubkey()?
+    } else {
+        ctx.signer_pubkey().unwrap_or(QUOTE_SIGNER)
+    };
+    let result = swap_instructions(&ctx.rpc, args.pool, amount, specified_mint, swap_type, args.slippage_tolerance_bps, Some(signer)).await?;
+
+    let mut output = json!({
+        "pool": args.pool.to_string(),
+        "a_to_b": a_to_b,
+        "price": price,
+        "quote": swap_quote_to_json(&result.quote, pool.decimals_a, pool.decimals_b),
+    });
+
+    if send {
+        output["transaction"] = ctx.send_transaction(result.instructions, result.additional_signers).await?;
+    }
+
+    ctx.output(output)
+}
@@ -0,0 +1,122 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use fusionamm_sdk::set_funder;
+use fusionamm_tx_sender::{send_smart_transaction, SmartTxConfig};
+use serde_json::{json, Value};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_instruction::Instruction;
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+
+/// Shared state of a command: the RPC client, the signer, the transaction options and the output format.
+pub struct CliContext {
+    pub rpc: RpcClient,
+    pub keypair_path: Option<PathBuf>,
+    pub tx_config: SmartTxConfig,
+    pub json: bool,
+    signer: OnceLock<Arc<Keypair>>,
+}
+
+impl CliContext {
+    pub fn new(rpc: RpcClient, keypair_path: Option<PathBuf>, tx_config: SmartTxConfig, json: bool) -> Self {
+        Self {
+            rpc,
+            keypair_path,
+            tx_config,
+            json,
+            signer: OnceLock::new(),
+        }
+    }
+
+    /// Returns the signer, loading it from the keypair file on first use.
+    /// Read-only commands never call this, so they work without a keypair.
+    pub fn signer(&self) -> Result<Arc<Keypair>, Box<dyn Error>> {
+        if let Some(signer) = self.signer.get() {
+            return Ok(signer.clone());
+        }
+
+        let signer = load_keypair(self.keypair_path.clone())?;
+        set_funder(signer.pubkey())?;
+        Ok(self.signer.get_or_init(|| Arc::new(signer)).clone())
+    }
+
+    pub fn signer_pubkey(&self) -> Result<Pubkey, Box<dyn Error>> {
+        Ok(self.signer()?.pubkey())
+    }
+
+    /// Prints a progress message. Messages are suppressed in JSON mode so that stdout only contains the result.
+    pub fn log(&self, message: impl AsRef<str>) {
+        if !self.json {
+            println!("{}", message.as_ref());
+        }
+    }
+
+    /// Prints the result of a command, either as JSON or as `key: value` lines.
+    pub fn output(&self, value: Value) -> Result<(), Box<dyn Error>> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&value)?);
+            return Ok(());
+        }
+
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    match value {
+                        Value::String(s) => println!("{}: {}", key, s),
+                        Value::Object(_) | Value::Array(_) => println!("{}: {}", key, serde_json::to_string_pretty(&value)?),
+                        _ => println!("{}: {}", key, value),
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    println!("{}", serde_json::to_string(&item)?);
+                }
+            }
+            _ => println!("{}", value),
+        }
+        Ok(())
+    }
+
+    /// Signs and sends the instructions with `send_smart_transaction`. Returns the transaction details as JSON.
+    pub async fn send_transaction(&self, instructions: Vec<Instruction>, additional_signers: Vec<Keypair>) -> Result<Value, Box<dyn Error>> {
+        if instructions.is_empty() {
+            return Err("Nothing to send".into());
+        }
+
+        let signer = self.signer()?;
+        let payer = signer.pubkey();
+        let mut signers = vec![signer];
+        signers.extend(additional_signers.into_iter().map(Arc::new));
+
+        self.log("Sending a transaction...");
+        let result = send_smart_transaction(&self.rpc, signers, &payer, instructions, vec![], self.tx_config.clone()).await?;
+        self.log(format!("Transaction landed: {} ({})", result.signature, result.elapsed_time));
+
+        Ok(json!({
+            "signature": result.signature.to_string(),
+            "priority_fee": result.priority_fee,
+            "jito_bundle_id": result.jito_bundle_id,
+        }))
+    }
+}
+
+fn load_keypair(path: Option<PathBuf>) -> Result<Keypair, Box<dyn Error>> {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let home = std::env::var("HOME").map_err(|_| "Failed to locate the home directory")?;
+            PathBuf::from(home).join(".config").join("solana").join("id.json")
+        }
+    };
+    read_keypair_file(&path).map_err(|e| format!("Failed to load keypair from {}: {}", path.display(), e).into())
+}
@@ -0,0 +1,138 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+mod commands;
+mod context;
+
+use clap::{Parser, ValueEnum};
+use commands::Command;
+use context::CliContext;
+use fusionamm_tx_sender::{PriorityFeeLevel, SmartTxConfig, SmartTxJitoConfig, SmartTxPriorityFeeConfig};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "fusionamm", version, about = "FusionAMM command-line interface")]
+struct Cli {
+    /// Solana RPC url.
+    #[arg(long, env = "RPC_URL", default_value = "https://api.mainnet-beta.solana.com", global = true)]
+    rpc_url: String,
+
+    /// Path to the signer keypair file. Defaults to ~/.config/solana/id.json.
+    #[arg(long, env = "KEYPAIR", global = true)]
+    keypair: Option<PathBuf>,
+
+    /// Priority fee level estimated from the recent prioritization fees.
+    #[arg(long, value_enum, default_value_t = PriorityFee::Medium, global = true)]
+    priority_fee: PriorityFee,
+
+    /// Fixed priority fee in micro lamports per compute unit. Overrides --priority-fee.
+    #[arg(long, global = true)]
+    priority_fee_micro_lamports: Option<u64>,
+
+    /// Maximum priority fee in micro lamports per compute unit.
+    #[arg(long, global = true)]
+    max_priority_fee: Option<u64>,
+
+    /// Send the transaction as a Jito bundle with the given tip in lamports.
+    #[arg(long, global = true)]
+    jito_tips: Option<u64>,
+
+    /// Jito API uuid.
+    #[arg(long, env = "JITO_UUID", default_value = "", global = true)]
+    jito_uuid: String,
+
+    /// Jito block engine region.
+    #[arg(long, global = true)]
+    jito_region: Option<String>,
+
+    /// Only simulate the transactions, don't send them.
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Print the result as JSON.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PriorityFee {
+    None,
+    VeryLow,
+    Low,
+    Medium,
+    High,
+    VeryHigh,
+    Ultimate,
+}
+
+impl From<PriorityFee> for PriorityFeeLevel {
+    fn from(value: PriorityFee) -> Self {
+        match value {
+            PriorityFee::None => PriorityFeeLevel::None,
+            PriorityFee::VeryLow => PriorityFeeLevel::VeryLow,
+            PriorityFee::Low => PriorityFeeLevel::Low,
+            PriorityFee::Medium => PriorityFeeLevel::Medium,
+            PriorityFee::High => PriorityFeeLevel::High,
+            PriorityFee::VeryHigh => PriorityFeeLevel::VeryHigh,
+            PriorityFee::Ultimate => PriorityFeeLevel::Ultimate,
+        }
+    }
+}
+
+impl Cli {
+    fn tx_config(&self) -> SmartTxConfig {
+        let fee_level = match self.priority_fee_micro_lamports {
+            Some(fee) => PriorityFeeLevel::Custom(fee),
+            None => self.priority_fee.into(),
+        };
+
+        SmartTxConfig {
+            priority_fee: Some(SmartTxPriorityFeeConfig {
+                fee_level,
+                fee_min: None,
+                fee_max: self.max_priority_fee,
+            }),
+            jito: self.jito_tips.map(|tips| SmartTxJitoConfig {
+                uuid: self.jito_uuid.clone(),
+                tips,
+                region: self.jito_region.clone(),
+            }),
+            dry_run: self.dry_run,
+            ..Default::default()
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let ctx = CliContext::new(RpcClient::new(cli.rpc_url.clone()), cli.keypair.clone(), cli.tx_config(), cli.json);
+    cli.command.run(&ctx).await
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+    let json = cli.json;
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if json {
+                println!("{}", serde_json::json!({ "error": err.to_string() }));
+            } else {
+                eprintln!("Error: {}", err);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -29,6 +29,8 @@ impl From<FusionPool> for FusionPoolFacade {
             orders_filled_amount_b: val.orders_filled_amount_b,
             olp_fee_owed_a: val.olp_fee_owed_a,
             olp_fee_owed_b: val.olp_fee_owed_b,
+            ma_sqrt_price: val.ma_sqrt_price,
+            last_swap_timestamp: val.last_swap_timestamp,
         }
     }
 }
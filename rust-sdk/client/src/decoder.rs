@@ -0,0 +1,527 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::generated::instructions::*;
+use crate::generated::programs::FUSIONAMM_ID;
+use borsh::BorshDeserialize;
+use solana_instruction::Instruction;
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_message::v0::LoadedAddresses;
+use solana_message::{AddressLookupTableAccount, VersionedMessage};
+use solana_pubkey::Pubkey;
+use std::error::Error;
+use std::slice::Iter;
+
+macro_rules! fusionamm_instructions {
+    ($($name:ident($discriminator:ident) { $($account:ident),* $(,)? } $(, args: $args:ident)?;)*) => {
+        /// A FusionAMM instruction decoded into its named accounts and arguments.
+        #[derive(Debug)]
+        pub enum FusionAmmInstruction {
+            $(
+                $name {
+                    accounts: $name,
+                    $(args: $args,)?
+                },
+            )*
+            /// An instruction with a discriminator this version of the client doesn't know, e.g. one added by a program upgrade.
+            Unknown {
+                discriminator: [u8; 8],
+                accounts: Vec<Pubkey>,
+                data: Vec<u8>,
+            },
+        }
+
+        impl FusionAmmInstruction {
+            /// Returns the name of the instruction, e.g. `"Swap"`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$name { .. } => stringify!($name),)*
+                    Self::Unknown { .. } => "Unknown",
+                }
+            }
+        }
+
+        fn decode_fusionamm_instruction<'a>(accounts: &'a [Pubkey], data: &[u8]) -> Result<(FusionAmmInstruction, &'a [Pubkey]), Box<dyn Error>> {
+            let (discriminator, mut args_data) = data.split_at_checked(8).ok_or("Instruction data is too short")?;
+            let mut account_keys = accounts.iter();
+            $(
+                if discriminator == $discriminator {
+                    let instruction = FusionAmmInstruction::$name {
+                        accounts: $name {
+                            $($account: next_account(&mut account_keys, stringify!($account))?,)*
+                        },
+                        $(args: $args::deserialize(&mut args_data)?,)?
+                    };
+                    return Ok((instruction, account_keys.as_slice()));
+                }
+            )*
+            let instruction = FusionAmmInstruction::Unknown {
+                discriminator: discriminator.try_into()?,
+                accounts: accounts.to_vec(),
+                data: args_data.to_vec(),
+            };
+            Ok((instruction, &[]))
+        }
+    };
+}
+
+// The accounts of each instruction are listed in the order of the program IDL.
+fusionamm_instructions! {
+    CloseBundledPosition(CLOSE_BUNDLED_POSITION_DISCRIMINATOR) {
+        bundled_position, position_bundle, position_bundle_token_account, position_bundle_authority, receiver,
+    }, args: CloseBundledPositionInstructionArgs;
+    CloseLimitOrder(CLOSE_LIMIT_ORDER_DISCRIMINATOR) {
+        limit_order_authority, receiver, limit_order, limit_order_mint, limit_order_token_account, token2022_program,
+    };
+    ClosePosition(CLOSE_POSITION_DISCRIMINATOR) {
+        position_authority, receiver, position, position_mint, position_token_account, token2022_program,
+    };
+    CollectFees(COLLECT_FEES_DISCRIMINATOR) {
+        fusion_pool, position_authority, position, position_token_account, token_mint_a, token_mint_b, token_owner_account_a,
+        token_owner_account_b, token_vault_a, token_vault_b, token_program_a, token_program_b, memo_program,
+    }, args: CollectFeesInstructionArgs;
+    CollectProtocolFees(COLLECT_PROTOCOL_FEES_DISCRIMINATOR) {
+        fusion_pools_config, fusion_pool, collect_protocol_fees_authority, token_mint_a, token_mint_b, token_vault_a, token_vault_b,
+        token_destination_a, token_destination_b, token_program_a, token_program_b, memo_program,
+    }, args: CollectProtocolFeesInstructionArgs;
+    DecreaseLimitOrder(DECREASE_LIMIT_ORDER_DISCRIMINATOR) {
+        limit_order_authority, fusion_pool, limit_order, limit_order_token_account, token_mint_a, token_mint_b, token_owner_account_a,
+        token_owner_account_b, token_vault_a, token_vault_b, tick_array, token_program_a, token_program_b, memo_program,
+    }, args: DecreaseLimitOrderInstructionArgs;
+    DecreaseLiquidity(DECREASE_LIQUIDITY_DISCRIMINATOR) {
+        fusion_pool, token_program_a, token_program_b, memo_program, position_authority, position, position_token_account, token_mint_a,
+        token_mint_b, token_owner_account_a, token_owner_account_b, token_vault_a, token_vault_b, tick_array_lower, tick_array_upper,
+    }, args: DecreaseLiquidityInstructionArgs;
+    DeletePositionBundle(DELETE_POSITION_BUNDLE_DISCRIMINATOR) {
+        position_bundle, position_bundle_mint, position_bundle_token_account, position_bundle_owner, receiver, token_program,
+    };
+    DeleteTokenBadge(DELETE_TOKEN_BADGE_DISCRIMINATOR) {
+        fusion_pools_config, token_badge_authority, token_mint, token_badge, receiver,
+    };
+    IdlInclude(IDL_INCLUDE_DISCRIMINATOR) {
+        tick_array, system_program,
+    };
+    IncreaseLimitOrder(INCREASE_LIMIT_ORDER_DISCRIMINATOR) {
+        limit_order_authority, fusion_pool, limit_order, limit_order_token_account, token_mint, token_owner_account, token_vault,
+        tick_array, token_program, memo_program,
+    }, args: IncreaseLimitOrderInstructionArgs;
+    IncreaseLiquidity(INCREASE_LIQUIDITY_DISCRIMINATOR) {
+        fusion_pool, token_program_a, token_program_b, memo_program, position_authority, position, position_token_account, token_mint_a,
+        token_mint_b, token_owner_account_a, token_owner_account_b, token_vault_a, token_vault_b, tick_array_lower, tick_array_upper,
+    }, args: IncreaseLiquidityInstructionArgs;
+    InitializeConfig(INITIALIZE_CONFIG_DISCRIMINATOR) {
+        fusion_pools_config, funder, system_program,
+    }, args: InitializeConfigInstructionArgs;
+    InitializePool(INITIALIZE_POOL_DISCRIMINATOR) {
+        fusion_pools_config, token_mint_a, token_mint_b, token_badge_a, token_badge_b, funder, fusion_pool, token_vault_a, token_vault_b,
+        token_program_a, token_program_b, system_program, rent,
+    }, args: InitializePoolInstructionArgs;
+    InitializePositionBundle(INITIALIZE_POSITION_BUNDLE_DISCRIMINATOR) {
+        position_bundle, position_bundle_mint, position_bundle_token_account, position_bundle_owner, funder, token_program, system_program,
+        rent, associated_token_program,
+    };
+    InitializePositionBundleWithMetadata(INITIALIZE_POSITION_BUNDLE_WITH_METADATA_DISCRIMINATOR) {
+        position_bundle, position_bundle_mint, position_bundle_metadata, position_bundle_token_account, position_bundle_owner, funder,
+        metadata_update_auth, token_program, system_program, rent, associated_token_program, metadata_program,
+    };
+    InitializeTickArray(INITIALIZE_TICK_ARRAY_DISCRIMINATOR) {
+        fusion_pool, funder, tick_array, system_program,
+    }, args: InitializeTickArrayInstructionArgs;
+    InitializeTokenBadge(INITIALIZE_TOKEN_BADGE_DISCRIMINATOR) {
+        fusion_pools_config, token_badge_authority, token_mint, token_badge, funder, system_program,
+    };
+    LockPosition(LOCK_POSITION_DISCRIMINATOR) {
+        funder, position_authority, position, position_mint, position_token_account, position_lock, fusion_pool, token2022_program,
+        system_program,
+    }, args: LockPositionInstructionArgs;
+    OpenBundledPosition(OPEN_BUNDLED_POSITION_DISCRIMINATOR) {
+        bundled_position, position_bundle, position_bundle_token_account, position_bundle_authority, fusion_pool, funder, system_program,
+        rent,
+    }, args: OpenBundledPositionInstructionArgs;
+    OpenLimitOrder(OPEN_LIMIT_ORDER_DISCRIMINATOR) {
+        funder, owner, limit_order, limit_order_mint, limit_order_token_account, fusion_pool, token2022_program, system_program,
+        associated_token_program, metadata_update_auth,
+    }, args: OpenLimitOrderInstructionArgs;
+    OpenPosition(OPEN_POSITION_DISCRIMINATOR) {
+        funder, owner, position, position_mint, position_token_account, fusion_pool, token2022_program, system_program,
+        associated_token_program, metadata_update_auth,
+    }, args: OpenPositionInstructionArgs;
+    ResetPoolPrice(RESET_POOL_PRICE_DISCRIMINATOR) {
+        fee_authority, fusion_pools_config, token_vault_a, token_vault_b, fusion_pool,
+    }, args: ResetPoolPriceInstructionArgs;
+    SetCollectProtocolFeesAuthority(SET_COLLECT_PROTOCOL_FEES_AUTHORITY_DISCRIMINATOR) {
+        fusion_pools_config, collect_protocol_fees_authority, new_collect_protocol_fees_authority,
+    };
+    SetDefaultProtocolFeeRate(SET_DEFAULT_PROTOCOL_FEE_RATE_DISCRIMINATOR) {
+        fusion_pools_config, fee_authority,
+    }, args: SetDefaultProtocolFeeRateInstructionArgs;
+    SetFeeAuthority(SET_FEE_AUTHORITY_DISCRIMINATOR) {
+        fusion_pools_config, fee_authority, new_fee_authority,
+    };
+    SetFeeRate(SET_FEE_RATE_DISCRIMINATOR) {
+        fusion_pools_config, fusion_pool, fee_authority,
+    }, args: SetFeeRateInstructionArgs;
+    SetPositionRange(SET_POSITION_RANGE_DISCRIMINATOR) {
+        position_authority, position, position_token_account, fusion_pool,
+    }, args: SetPositionRangeInstructionArgs;
+    SetProtocolFeeRate(SET_PROTOCOL_FEE_RATE_DISCRIMINATOR) {
+        fusion_pools_config, fusion_pool, fee_authority,
+    }, args: SetProtocolFeeRateInstructionArgs;
+    SetTokenBadgeAuthority(SET_TOKEN_BADGE_AUTHORITY_DISCRIMINATOR) {
+        fusion_pools_config, fee_authority, new_token_badge_authority,
+    };
+    Swap(SWAP_DISCRIMINATOR) {
+        token_program_a, token_program_b, memo_program, token_authority, fusion_pool, token_mint_a, token_mint_b, token_owner_account_a,
+        token_owner_account_b, token_vault_a, token_vault_b, tick_array0, tick_array1, tick_array2,
+    }, args: SwapInstructionArgs;
+    TwoHopSwap(TWO_HOP_SWAP_DISCRIMINATOR) {
+        fusion_pool_one, fusion_pool_two, token_mint_input, token_mint_intermediate, token_mint_output, token_program_input,
+        token_program_intermediate, token_program_output, token_owner_account_input, token_vault_one_input, token_vault_one_intermediate,
+        token_vault_two_intermediate, token_vault_two_output, token_owner_account_output, token_authority, tick_array_one0, tick_array_one1,
+        tick_array_one2, tick_array_two0, tick_array_two1, tick_array_two2, memo_program,
+    }, args: TwoHopSwapInstructionArgs;
+    UpdateFees(UPDATE_FEES_DISCRIMINATOR) {
+        fusion_pool, position, tick_array_lower, tick_array_upper,
+    };
+}
+
+impl FusionAmmInstruction {
+    /// Decodes an instruction of the FusionAMM program from its account keys and data.
+    /// Accounts beyond the named accounts of the instruction are ignored.
+    /// An instruction with an unknown discriminator is returned as `FusionAmmInstruction::Unknown`.
+    pub fn decode(accounts: &[Pubkey], data: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Ok(decode_fusionamm_instruction(accounts, data)?.0)
+    }
+}
+
+impl TryFrom<&Instruction> for FusionAmmInstruction {
+    type Error = Box<dyn Error>;
+
+    fn try_from(instruction: &Instruction) -> Result<Self, Self::Error> {
+        if instruction.program_id != FUSIONAMM_ID {
+            return Err(format!("Instruction of program {} is not a FusionAMM instruction", instruction.program_id).into());
+        }
+        let accounts: Vec<Pubkey> = instruction.accounts.iter().map(|account| account.pubkey).collect();
+        Self::decode(&accounts, &instruction.data)
+    }
+}
+
+/// The inner instructions invoked by a top-level instruction of a transaction, as reported by the transaction status.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CompiledInnerInstructions {
+    /// The index of the top-level instruction.
+    pub index: u8,
+
+    /// The inner instructions in the order of execution.
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+/// A FusionAMM instruction found in a transaction.
+#[derive(Debug)]
+pub struct DecodedInstruction {
+    /// The index of the top-level instruction in the transaction.
+    pub instruction_index: usize,
+
+    /// The index of the instruction within the inner instructions of the top-level instruction, or `None` for a top-level instruction.
+    pub inner_instruction_index: Option<usize>,
+
+    /// The decoded instruction.
+    pub instruction: FusionAmmInstruction,
+
+    /// The accounts passed after the named accounts, e.g. supplemental tick arrays or transfer hook accounts.
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+/// Decodes all FusionAMM instructions of a transaction, including the ones invoked through CPI.
+/// Instructions with an unknown discriminator are returned as `FusionAmmInstruction::Unknown`, so that
+/// a transaction with instructions of a newer program version can still be decoded.
+///
+/// # Arguments
+///
+/// * `message` - The message of the transaction.
+/// * `loaded_addresses` - The addresses loaded from address lookup tables, e.g. from the transaction status or from `resolve_loaded_addresses`.
+/// * `inner_instructions` - The inner instructions of the transaction. Pass an empty slice to decode top-level instructions only.
+///
+/// # Returns
+///
+/// A `Result` containing the decoded instructions in the order of execution.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - An instruction references an account that is not part of the transaction.
+/// - A FusionAMM instruction has malformed data or misses accounts.
+pub fn decode_transaction(
+    message: &VersionedMessage,
+    loaded_addresses: &LoadedAddresses,
+    inner_instructions: &[CompiledInnerInstructions],
+) -> Result<Vec<DecodedInstruction>, Box<dyn Error>> {
+    let account_keys: Vec<Pubkey> = message
+        .static_account_keys()
+        .iter()
+        .chain(&loaded_addresses.writable)
+        .chain(&loaded_addresses.readonly)
+        .copied()
+        .collect();
+
+    let mut decoded_instructions = Vec::new();
+    for (instruction_index, instruction) in message.instructions().iter().enumerate() {
+        if let Some(decoded) = decode_compiled_instruction(&account_keys, instruction, instruction_index, None)? {
+            decoded_instructions.push(decoded);
+        }
+
+        for inner in inner_instructions.iter().filter(|inner| inner.index as usize == instruction_index) {
+            for (inner_instruction_index, inner_instruction) in inner.instructions.iter().enumerate() {
+                if let Some(decoded) =
+                    decode_compiled_instruction(&account_keys, inner_instruction, instruction_index, Some(inner_instruction_index))?
+                {
+                    decoded_instructions.push(decoded);
+                }
+            }
+        }
+    }
+
+    Ok(decoded_instructions)
+}
+
+/// Resolves the addresses a versioned message loads from address lookup tables.
+///
+/// # Arguments
+///
+/// * `message` - The message of the transaction.
+/// * `lookup_tables` - The address lookup tables referenced by the message.
+///
+/// # Returns
+///
+/// A `Result` containing the loaded writable and readonly addresses.
+///
+/// # Errors
+///
+/// This function will return an error if a lookup table is missing or an index is out of its bounds.
+pub fn resolve_loaded_addresses(message: &VersionedMessage, lookup_tables: &[AddressLookupTableAccount]) -> Result<LoadedAddresses, Box<dyn Error>> {
+    let mut loaded_addresses = LoadedAddresses {
+        writable: Vec::new(),
+        readonly: Vec::new(),
+    };
+
+    for lookup in message.address_table_lookups().unwrap_or_default() {
+        let lookup_table = lookup_tables
+            .iter()
+            .find(|lookup_table| lookup_table.key == lookup.account_key)
+            .ok_or(format!("Address lookup table {} not found", lookup.account_key))?;
+        let lookup_address = |index: &u8| {
+            lookup_table
+                .addresses
+                .get(*index as usize)
+                .copied()
+                .ok_or(format!("Index {} is out of bounds of address lookup table {}", index, lookup.account_key))
+        };
+        for index in &lookup.writable_indexes {
+            loaded_addresses.writable.push(lookup_address(index)?);
+        }
+        for index in &lookup.readonly_indexes {
+            loaded_addresses.readonly.push(lookup_address(index)?);
+        }
+    }
+
+    Ok(loaded_addresses)
+}
+
+fn decode_compiled_instruction(
+    account_keys: &[Pubkey],
+    instruction: &CompiledInstruction,
+    instruction_index: usize,
+    inner_instruction_index: Option<usize>,
+) -> Result<Option<DecodedInstruction>, Box<dyn Error>> {
+    let account_key = |index: &u8| {
+        account_keys
+            .get(*index as usize)
+            .copied()
+            .ok_or(format!("Account index {} is out of bounds of the transaction accounts", index))
+    };
+
+    if account_key(&instruction.program_id_index)? != FUSIONAMM_ID {
+        return Ok(None);
+    }
+
+    let accounts = instruction.accounts.iter().map(account_key).collect::<Result<Vec<Pubkey>, _>>()?;
+    let (decoded, remaining_accounts) = decode_fusionamm_instruction(&accounts, &instruction.data)?;
+
+    Ok(Some(DecodedInstruction {
+        instruction_index,
+        inner_instruction_index,
+        instruction: decoded,
+        remaining_accounts: remaining_accounts.to_vec(),
+    }))
+}
+
+fn next_account(account_keys: &mut Iter<Pubkey>, name: &str) -> Result<Pubkey, Box<dyn Error>> {
+    Ok(*account_keys.next().ok_or(format!("Missing account {}", name))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SwapInstructionArgs;
+    use solana_instruction::AccountMeta;
+    use solana_message::v0;
+    use solana_program::hash::Hash;
+
+    fn swap_instruction(remaining_accounts: &[AccountMeta]) -> (Swap, SwapInstructionArgs, Instruction) {
+        let accounts = Swap {
+            token_program_a: Pubkey::new_unique(),
+            token_program_b: Pubkey::new_unique(),
+            memo_program: Pubkey::new_unique(),
+            token_authority: Pubkey::new_unique(),
+            fusion_pool: Pubkey::new_unique(),
+            token_mint_a: Pubkey::new_unique(),
+            token_mint_b: Pubkey::new_unique(),
+            token_owner_account_a: Pubkey::new_unique(),
+            token_owner_account_b: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            tick_array0: Pubkey::new_unique(),
+            tick_array1: Pubkey::new_unique(),
+            tick_array2: Pubkey::new_unique(),
+        };
+        let args = SwapInstructionArgs {
+            amount: 1_000_000,
+            other_amount_threshold: 990_000,
+            sqrt_price_limit: 4295048016,
+            amount_specified_is_input: true,
+            a_to_b: true,
+            remaining_accounts_info: None,
+        };
+        let instruction = accounts.instruction_with_remaining_accounts(args.clone(), remaining_accounts);
+        (accounts, args, instruction)
+    }
+
+    #[test]
+    fn test_decode_instruction() {
+        let (accounts, args, instruction) = swap_instruction(&[]);
+
+        let decoded = FusionAmmInstruction::try_from(&instruction).unwrap();
+        assert_eq!(decoded.name(), "Swap");
+        let FusionAmmInstruction::Swap {
+            accounts: decoded_accounts,
+            args: decoded_args,
+        } = decoded
+        else {
+            panic!("Expected a swap instruction");
+        };
+        assert_eq!(decoded_args, args);
+        assert_eq!(decoded_accounts.token_authority, accounts.token_authority);
+        assert_eq!(decoded_accounts.fusion_pool, accounts.fusion_pool);
+        assert_eq!(decoded_accounts.tick_array2, accounts.tick_array2);
+
+        let account_keys: Vec<Pubkey> = instruction.accounts.iter().map(|account| account.pubkey).collect();
+        assert!(FusionAmmInstruction::decode(&account_keys[..13], &instruction.data).is_err());
+        assert!(FusionAmmInstruction::decode(&account_keys, &instruction.data[..7]).is_err());
+        let FusionAmmInstruction::Unknown {
+            discriminator,
+            accounts: unknown_accounts,
+            data,
+        } = FusionAmmInstruction::decode(&account_keys, &[0, 0, 0, 0, 0, 0, 0, 0, 1, 2]).unwrap()
+        else {
+            panic!("Expected an unknown instruction");
+        };
+        assert_eq!(discriminator, [0; 8]);
+        assert_eq!(unknown_accounts, account_keys);
+        assert_eq!(data, vec![1, 2]);
+        assert!(FusionAmmInstruction::try_from(&Instruction::new_with_bytes(Pubkey::new_unique(), &instruction.data, instruction.accounts)).is_err());
+    }
+
+    #[test]
+    fn test_decode_transaction() {
+        let supplemental_tick_array = Pubkey::new_unique();
+        let (accounts, _, swap) = swap_instruction(&[AccountMeta::new(supplemental_tick_array, false)]);
+        let update_fees = UpdateFees {
+            fusion_pool: accounts.fusion_pool,
+            position: accounts.token_owner_account_a,
+            tick_array_lower: accounts.tick_array0,
+            tick_array_upper: accounts.tick_array1,
+        }
+        .instruction();
+        let other = Instruction::new_with_bytes(Pubkey::new_unique(), &[1, 2, 3], vec![]);
+
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![
+                accounts.fusion_pool,
+                accounts.tick_array0,
+                accounts.tick_array1,
+                accounts.tick_array2,
+                supplemental_tick_array,
+            ],
+        };
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(&accounts.token_authority, &[other, swap], &[lookup_table.clone()], Hash::default()).unwrap(),
+        );
+        assert!(resolve_loaded_addresses(&message, &[]).is_err());
+        let loaded_addresses = resolve_loaded_addresses(&message, &[lookup_table]).unwrap();
+        assert_eq!(loaded_addresses.writable.len() + loaded_addresses.readonly.len(), 5);
+
+        let account_keys: Vec<Pubkey> = message
+            .static_account_keys()
+            .iter()
+            .chain(&loaded_addresses.writable)
+            .chain(&loaded_addresses.readonly)
+            .copied()
+            .collect();
+        let account_index = |address: &Pubkey| account_keys.iter().position(|key| key == address).unwrap() as u8;
+        let inner_instructions = vec![CompiledInnerInstructions {
+            index: 1,
+            instructions: vec![
+                CompiledInstruction {
+                    program_id_index: account_index(&FUSIONAMM_ID),
+                    accounts: update_fees.accounts.iter().map(|account| account_index(&account.pubkey)).collect(),
+                    data: update_fees.data,
+                },
+                CompiledInstruction {
+                    program_id_index: account_index(&FUSIONAMM_ID),
+                    accounts: vec![account_index(&accounts.fusion_pool)],
+                    data: vec![255; 8],
+                },
+            ],
+        }];
+
+        let decoded = decode_transaction(&message, &loaded_addresses, &inner_instructions).unwrap();
+        assert_eq!(decoded.len(), 3);
+
+        assert_eq!((decoded[0].instruction_index, decoded[0].inner_instruction_index), (1, None));
+        assert_eq!(decoded[0].remaining_accounts, vec![supplemental_tick_array]);
+        let FusionAmmInstruction::Swap { accounts: swap_accounts, .. } = &decoded[0].instruction else {
+            panic!("Expected a swap instruction");
+        };
+        assert_eq!(swap_accounts.fusion_pool, accounts.fusion_pool);
+        assert_eq!(swap_accounts.tick_array2, accounts.tick_array2);
+
+        assert_eq!((decoded[1].instruction_index, decoded[1].inner_instruction_index), (1, Some(0)));
+        assert!(decoded[1].remaining_accounts.is_empty());
+        let FusionAmmInstruction::UpdateFees {
+            accounts: update_fees_accounts,
+        } = &decoded[1].instruction
+        else {
+            panic!("Expected an update fees instruction");
+        };
+        assert_eq!(update_fees_accounts.position, accounts.token_owner_account_a);
+        assert_eq!(update_fees_accounts.tick_array_upper, accounts.tick_array1);
+
+        assert_eq!((decoded[2].instruction_index, decoded[2].inner_instruction_index), (1, Some(1)));
+        let FusionAmmInstruction::Unknown {
+            discriminator,
+            accounts: unknown_accounts,
+            data,
+        } = &decoded[2].instruction
+        else {
+            panic!("Expected an unknown instruction");
+        };
+        assert_eq!(*discriminator, [255; 8]);
+        assert_eq!(*unknown_accounts, vec![accounts.fusion_pool]);
+        assert!(data.is_empty());
+    }
+}
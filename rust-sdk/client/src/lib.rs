@@ -12,6 +12,7 @@
 mod generated;
 
 mod consts;
+mod decoder;
 mod pda;
 
 #[cfg(feature = "fetch")]
@@ -34,6 +35,7 @@ pub use generated::shared::*;
 pub(crate) use generated::*;
 
 pub use consts::*;
+pub use decoder::*;
 pub use pda::*;
 
 #[cfg(feature = "fetch")]
@@ -68,3 +68,30 @@ pub const INVALID_TICK_ARRAY_SEQUENCE: CoreError = "Invalid tick array sequence"
 
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub const LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC: CoreError = "Limit order and pool/ticks data are out of sync";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const ZERO_LIQUIDITY_DELTA: CoreError = "Zero liquidity delta";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const LIQUIDITY_DELTA_EXCEEDS_POSITION_LIQUIDITY: CoreError = "Liquidity delta exceeds position liquidity";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const TAKER_ORDER_NOT_SUPPORTED: CoreError = "Taker order not supported";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const INVALID_SWAP_HISTORY: CoreError = "Invalid swap history";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const SQRT_PRICE_OUT_OF_DISTRIBUTION_RANGE: CoreError = "Sqrt price out of liquidity distribution range";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const INVALID_ORDER_BOOK_STEP: CoreError = "Invalid order book step";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const TOO_MANY_ORDER_BOOK_ENTRIES: CoreError = "Too many order book entries";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const MA_SQRT_PRICE_NOT_INITIALIZED: CoreError = "Moving average sqrt price not initialized";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const INVALID_DECIMAL_PRICE: CoreError = "Invalid decimal price";
@@ -16,6 +16,9 @@ use fusionamm_macros::wasm_expose;
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub const FEE_RATE_MUL_VALUE: u32 = 1_000_000;
 
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const MAX_FEE_RATE: u16 = 60_000;
+
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub const MAX_PROTOCOL_FEE_RATE: u16 = 2_500;
 
@@ -25,3 +28,6 @@ pub const PROTOCOL_FEE_RATE_MUL_VALUE: u16 = 10_000;
 // TODO: WASM export (which doesn't work with u128 yet)
 pub const MIN_SQRT_PRICE: u128 = 4295048016;
 pub const MAX_SQRT_PRICE: u128 = 79226673515401279992447579055;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const MAX_ORDER_BOOK_ENTRIES: u32 = 100;
@@ -8,18 +8,21 @@
 // See the LICENSE file in the project root for license information.
 //
 
-// FIXME: disable std for non-test builds to decrease wasm binary size.
-// There is currently something in tsify that prevents this:
+// The wasm and serde features require std. Wasm builds can't disable it yet because of tsify:
 // https://github.com/madonoharu/tsify/issues/56
-// #![cfg_attr(not(test), no_std)]
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
 #![allow(clippy::useless_conversion)]
 
+extern crate alloc;
+
 mod constants;
 mod math;
 mod quote;
+mod simulation;
 mod types;
 
 pub use constants::*;
 pub use math::*;
 pub use quote::*;
+pub use simulation::*;
 pub use types::*;
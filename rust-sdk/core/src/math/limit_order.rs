@@ -5,7 +5,10 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use crate::{div_by_sqrt_price_squared, mul_by_sqrt_price_squared, CoreError};
+use crate::{div_by_sqrt_price_squared, mul_by_sqrt_price_squared, CoreError, LimitOrderFacade, LimitOrderStatus, TickFacade};
+
+#[cfg(feature = "wasm")]
+use fusionamm_macros::wasm_expose;
 
 /// Computes the limit order output amount by input amount.
 /// ### Parameters
@@ -21,3 +24,33 @@ pub fn get_limit_order_output_amount(input_amount: u64, a_to_b_order: bool, sqrt
     };
     Ok(output_amount)
 }
+
+/// Calculate the status of a limit order by comparing its age with the age of its tick.
+/// The status can be one of four values:
+/// - Open: The order is not filled
+/// - PartiallyFilled: The order is partially filled
+/// - Filled: The order is fully filled
+/// - OutOfSync: The limit order and the tick data are out of sync
+///
+/// # Parameters
+/// - `limit_order` - The limit order
+/// - `tick` - The tick of the limit order
+///
+/// # Returns
+/// - A LimitOrderStatus enum value indicating the status of the limit order
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn limit_order_status(limit_order: LimitOrderFacade, tick: TickFacade) -> LimitOrderStatus {
+    if limit_order.age == tick.age {
+        LimitOrderStatus::Open
+    } else if limit_order.age + 1 == tick.age {
+        if tick.part_filled_orders_input == 0 {
+            LimitOrderStatus::OutOfSync
+        } else {
+            LimitOrderStatus::PartiallyFilled
+        }
+    } else if limit_order.age + 2 <= tick.age {
+        LimitOrderStatus::Filled
+    } else {
+        LimitOrderStatus::OutOfSync
+    }
+}
@@ -43,6 +43,43 @@ pub fn get_liquidity_from_amount_b(amount_b: u64, sqrt_price_lower: u128, sqrt_p
 
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub fn get_amount_a_from_liquidity(liquidity: u128, sqrt_price_lower: u128, sqrt_price_upper: u128, round_up: bool) -> Result<u64, CoreError> {
+    get_amount_a_delta(liquidity, sqrt_price_lower, sqrt_price_upper, round_up)?
+        .try_into()
+        .map_err(|_| AMOUNT_EXCEEDS_MAX_U64)
+}
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn get_amount_b_from_liquidity(liquidity: u128, sqrt_price_lower: u128, sqrt_price_upper: u128, round_up: bool) -> Result<u64, CoreError> {
+    get_amount_b_delta(liquidity, sqrt_price_lower, sqrt_price_upper, round_up)?
+        .try_into()
+        .map_err(|_| AMOUNT_EXCEEDS_MAX_U64)
+}
+
+/// Same as `get_amount_a_from_liquidity`, but for aggregated amounts that may not fit in a u64.
+pub(crate) fn get_amount_a_from_liquidity_u128(
+    liquidity: u128,
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    round_up: bool,
+) -> Result<u128, CoreError> {
+    get_amount_a_delta(liquidity, sqrt_price_lower, sqrt_price_upper, round_up)?
+        .try_into()
+        .map_err(|_| ARITHMETIC_OVERFLOW)
+}
+
+/// Same as `get_amount_b_from_liquidity`, but for aggregated amounts that may not fit in a u64.
+pub(crate) fn get_amount_b_from_liquidity_u128(
+    liquidity: u128,
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    round_up: bool,
+) -> Result<u128, CoreError> {
+    get_amount_b_delta(liquidity, sqrt_price_lower, sqrt_price_upper, round_up)?
+        .try_into()
+        .map_err(|_| ARITHMETIC_OVERFLOW)
+}
+
+fn get_amount_a_delta(liquidity: u128, sqrt_price_lower: u128, sqrt_price_upper: u128, round_up: bool) -> Result<U256, CoreError> {
     let sqrt_price_diff = sqrt_price_upper - sqrt_price_lower;
     let numerator: U256 = <U256>::from(liquidity)
         .checked_mul(sqrt_price_diff.into())
@@ -55,20 +92,18 @@ pub fn get_amount_a_from_liquidity(liquidity: u128, sqrt_price_lower: u128, sqrt
     let quotient = numerator / denominator;
     let remainder = numerator % denominator;
 
-    let result = if round_up && remainder != 0 { quotient + 1 } else { quotient };
-    result.try_into().map_err(|_| AMOUNT_EXCEEDS_MAX_U64)
+    Ok(if round_up && remainder != 0 { quotient + 1 } else { quotient })
 }
 
-#[cfg_attr(feature = "wasm", wasm_expose)]
-pub fn get_amount_b_from_liquidity(liquidity: u128, sqrt_price_lower: u128, sqrt_price_upper: u128, round_up: bool) -> Result<u64, CoreError> {
+fn get_amount_b_delta(liquidity: u128, sqrt_price_lower: u128, sqrt_price_upper: u128, round_up: bool) -> Result<U256, CoreError> {
     let sqrt_price_diff = sqrt_price_upper - sqrt_price_lower;
     let mul: U256 = <U256>::from(liquidity).checked_mul(sqrt_price_diff.into()).ok_or(ARITHMETIC_OVERFLOW)?;
     let result: U256 = mul >> 64;
-    if round_up && mul & <U256>::from(u64::MAX) > 0 {
-        (result + 1).try_into().map_err(|_| AMOUNT_EXCEEDS_MAX_U64)
+    Ok(if round_up && mul & <U256>::from(u64::MAX) > 0 {
+        result + 1
     } else {
-        result.try_into().map_err(|_| AMOUNT_EXCEEDS_MAX_U64)
-    }
+        result
+    })
 }
 
 #[cfg_attr(feature = "wasm", wasm_expose)]
@@ -11,6 +11,7 @@
 mod bundle;
 mod limit_order;
 mod liquidity;
+mod pool;
 mod position;
 mod price;
 mod tick;
@@ -21,6 +22,7 @@ mod u256_math;
 pub use bundle::*;
 pub use limit_order::*;
 pub use liquidity::*;
+pub use pool::*;
 pub use position::*;
 pub use price::*;
 pub use tick::*;
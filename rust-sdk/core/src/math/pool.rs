@@ -0,0 +1,122 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+#[cfg(feature = "wasm")]
+use fusionamm_macros::wasm_expose;
+
+use crate::{
+    sqrt_price_to_price, CoreError, FusionPoolFacade, ARITHMETIC_OVERFLOW, BPS_DENOMINATOR, INVALID_TIMESTAMP, MA_SQRT_PRICE_NOT_INITIALIZED,
+};
+use ethnum::U256;
+
+/// Convert the moving average sqrt price of a pool into a decimal price
+/// IMPORTANT: floating point operations can reduce the precision of the result.
+/// Make sure to do these operations last and not to use the result for further calculations.
+///
+/// # Parameters
+/// * `fusion_pool` - The fusion_pool state
+/// * `decimals_a` - The number of decimals of the base token
+/// * `decimals_b` - The number of decimals of the quote token
+///
+/// # Returns
+/// * `f64` - The moving average price
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn get_ma_price(fusion_pool: FusionPoolFacade, decimals_a: u8, decimals_b: u8) -> Result<f64, CoreError> {
+    if fusion_pool.ma_sqrt_price == 0 {
+        return Err(MA_SQRT_PRICE_NOT_INITIALIZED);
+    }
+    Ok(sqrt_price_to_price(fusion_pool.ma_sqrt_price, decimals_a, decimals_b))
+}
+
+/// Calculate the deviation of the spot price of a pool from its moving average price.
+/// The result is rounded toward zero.
+///
+/// # Parameters
+/// * `fusion_pool` - The fusion_pool state
+///
+/// # Returns
+/// * `i64` - The deviation in basis points. Positive if the spot price is above the moving average price.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn get_ma_price_deviation_bps(fusion_pool: FusionPoolFacade) -> Result<i64, CoreError> {
+    if fusion_pool.ma_sqrt_price == 0 {
+        return Err(MA_SQRT_PRICE_NOT_INITIALIZED);
+    }
+
+    let spot_price = <U256>::from(fusion_pool.sqrt_price) * <U256>::from(fusion_pool.sqrt_price);
+    let ma_price = <U256>::from(fusion_pool.ma_sqrt_price) * <U256>::from(fusion_pool.ma_sqrt_price);
+    let price_diff = if spot_price >= ma_price {
+        spot_price - ma_price
+    } else {
+        ma_price - spot_price
+    };
+
+    let deviation: U256 = price_diff.checked_mul(<U256>::from(BPS_DENOMINATOR)).ok_or(ARITHMETIC_OVERFLOW)? / ma_price;
+    let deviation: i64 = deviation.try_into().map_err(|_| ARITHMETIC_OVERFLOW)?;
+
+    Ok(if spot_price >= ma_price { deviation } else { -deviation })
+}
+
+/// Calculate the number of seconds elapsed since the last swap in a pool.
+///
+/// # Parameters
+/// * `fusion_pool` - The fusion_pool state
+/// * `timestamp` - The current unix timestamp
+///
+/// # Returns
+/// * `u64` - The number of seconds elapsed since the last swap
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn get_seconds_since_last_swap(fusion_pool: FusionPoolFacade, timestamp: u64) -> Result<u64, CoreError> {
+    timestamp.checked_sub(fusion_pool.last_swap_timestamp).ok_or(INVALID_TIMESTAMP)
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::price_to_sqrt_price;
+    use approx::assert_relative_eq;
+
+    fn test_fusion_pool(price: f64, ma_price: f64) -> FusionPoolFacade {
+        FusionPoolFacade {
+            sqrt_price: price_to_sqrt_price(price, 6, 6),
+            ma_sqrt_price: price_to_sqrt_price(ma_price, 6, 6),
+            last_swap_timestamp: 1_700_000_000,
+            ..FusionPoolFacade::default()
+        }
+    }
+
+    #[test]
+    fn test_ma_price() {
+        assert_relative_eq!(get_ma_price(test_fusion_pool(1.0, 2.5), 6, 6).unwrap(), 2.5, epsilon = 1e-12);
+        assert_relative_eq!(get_ma_price(test_fusion_pool(1.0, 2.5), 9, 6).unwrap(), 2500.0, epsilon = 1e-9);
+        assert_eq!(get_ma_price(FusionPoolFacade::default(), 6, 6), Err(MA_SQRT_PRICE_NOT_INITIALIZED));
+    }
+
+    #[test]
+    fn test_ma_price_deviation_bps() {
+        let fusion_pool = |sqrt_price: u128| FusionPoolFacade {
+            sqrt_price,
+            ma_sqrt_price: 1 << 64,
+            ..FusionPoolFacade::default()
+        };
+        assert_eq!(get_ma_price_deviation_bps(fusion_pool(1 << 64)), Ok(0));
+        assert_eq!(get_ma_price_deviation_bps(fusion_pool((1 << 64) + 1)), Ok(0));
+        assert_eq!(get_ma_price_deviation_bps(fusion_pool((1 << 64) - 1)), Ok(0));
+        // 1.5^2 = 2.25
+        assert_eq!(get_ma_price_deviation_bps(fusion_pool(3 << 63)), Ok(12500));
+        // 0.5^2 = 0.25
+        assert_eq!(get_ma_price_deviation_bps(fusion_pool(1 << 63)), Ok(-7500));
+        assert_eq!(get_ma_price_deviation_bps(FusionPoolFacade::default()), Err(MA_SQRT_PRICE_NOT_INITIALIZED));
+    }
+
+    #[test]
+    fn test_seconds_since_last_swap() {
+        let fusion_pool = test_fusion_pool(1.0, 1.0);
+        assert_eq!(get_seconds_since_last_swap(fusion_pool, 1_700_000_000), Ok(0));
+        assert_eq!(get_seconds_since_last_swap(fusion_pool, 1_700_000_060), Ok(60));
+        assert_eq!(get_seconds_since_last_swap(fusion_pool, 1_699_999_999), Err(INVALID_TIMESTAMP));
+    }
+}
@@ -14,6 +14,10 @@ use fusionamm_macros::wasm_expose;
 use libm::{floor, pow, sqrt};
 
 use super::{invert_tick_index, sqrt_price_to_tick_index, tick_index_to_sqrt_price};
+use crate::{
+    sqrt_u256, CoreError, DecimalPrice, ARITHMETIC_OVERFLOW, INVALID_DECIMAL_PRICE, MAX_SQRT_PRICE, MIN_SQRT_PRICE, SQRT_PRICE_OUT_OF_BOUNDS,
+};
+use ethnum::U256;
 
 pub const Q64_RESOLUTION: f64 = 18446744073709551616.0;
 
@@ -105,11 +109,154 @@ pub fn price_to_tick_index(price: f64, decimals_a: u8, decimals_b: u8) -> i32 {
     sqrt_price_to_tick_index(sqrt_price)
 }
 
+/// Convert a sqrt priceX64 into an exact decimal price, rounded down to `scale` fractional digits.
+///
+/// # Parameters
+/// * `sqrt_price` - The sqrt priceX64 to convert
+/// * `decimals_a` - The number of decimals of the base token
+/// * `decimals_b` - The number of decimals of the quote token
+/// * `scale` - The number of fractional digits of the result
+///
+/// # Returns
+/// * `DecimalPrice` - The decimal price
+pub fn sqrt_price_to_decimal_price(sqrt_price: u128, decimals_a: u8, decimals_b: u8, scale: u8) -> Result<DecimalPrice, CoreError> {
+    let price_x128 = <U256>::from(sqrt_price) * <U256>::from(sqrt_price);
+    let exponent = scale as i32 + decimals_a as i32 - decimals_b as i32;
+    let (mantissa, _) = mul_div_pow10(price_x128, U256::ONE << 128, exponent)?;
+    Ok(DecimalPrice::new(mantissa.try_into().map_err(|_| ARITHMETIC_OVERFLOW)?, scale))
+}
+
+/// Convert an exact decimal price into a sqrt priceX64.
+///
+/// # Parameters
+/// * `price` - The price to convert
+/// * `decimals_a` - The number of decimals of the base token
+/// * `decimals_b` - The number of decimals of the quote token
+/// * `round_up` - Whether to round the sqrt price up. Rounding up makes `sqrt_price_to_decimal_price` return the original
+///   price whenever its scale allows.
+///
+/// # Returns
+/// * `u128` - The sqrt priceX64
+pub fn decimal_price_to_sqrt_price(price: DecimalPrice, decimals_a: u8, decimals_b: u8, round_up: bool) -> Result<u128, CoreError> {
+    let exponent = price.scale as i32 + decimals_a as i32 - decimals_b as i32;
+    let (price_x128, inexact) = mul_div_pow10(<U256>::from(price.mantissa) << 128, U256::ONE, -exponent).map_err(|_| SQRT_PRICE_OUT_OF_BOUNDS)?;
+    checked_sqrt_price(price_x128, inexact, round_up)
+}
+
+/// Convert a sqrt priceX64 into the exact decimal price of the quote token in units of the base token,
+/// rounded down to `scale` fractional digits.
+///
+/// # Parameters
+/// * `sqrt_price` - The sqrt priceX64 to convert
+/// * `decimals_a` - The number of decimals of the base token
+/// * `decimals_b` - The number of decimals of the quote token
+/// * `scale` - The number of fractional digits of the result
+///
+/// # Returns
+/// * `DecimalPrice` - The inverted decimal price
+pub fn sqrt_price_to_inverted_decimal_price(sqrt_price: u128, decimals_a: u8, decimals_b: u8, scale: u8) -> Result<DecimalPrice, CoreError> {
+    if sqrt_price == 0 {
+        return Err(SQRT_PRICE_OUT_OF_BOUNDS);
+    }
+    let price_x128 = <U256>::from(sqrt_price) * <U256>::from(sqrt_price);
+    let exponent = scale as i32 + decimals_b as i32 - decimals_a as i32;
+    let (mantissa, _) = mul_div_pow10(U256::ONE << 128, price_x128, exponent)?;
+    Ok(DecimalPrice::new(mantissa.try_into().map_err(|_| ARITHMETIC_OVERFLOW)?, scale))
+}
+
+/// Convert an exact decimal price of the quote token in units of the base token into a sqrt priceX64.
+///
+/// # Parameters
+/// * `price` - The inverted price to convert
+/// * `decimals_a` - The number of decimals of the base token
+/// * `decimals_b` - The number of decimals of the quote token
+/// * `round_up` - Whether to round the sqrt price up
+///
+/// # Returns
+/// * `u128` - The sqrt priceX64
+pub fn inverted_decimal_price_to_sqrt_price(price: DecimalPrice, decimals_a: u8, decimals_b: u8, round_up: bool) -> Result<u128, CoreError> {
+    if price.mantissa == 0 {
+        return Err(INVALID_DECIMAL_PRICE);
+    }
+    let exponent = price.scale as i32 + decimals_b as i32 - decimals_a as i32;
+    let (price_x128, inexact) = mul_div_pow10(U256::ONE << 128, <U256>::from(price.mantissa), exponent).map_err(|_| SQRT_PRICE_OUT_OF_BOUNDS)?;
+    checked_sqrt_price(price_x128, inexact, round_up)
+}
+
+/// Convert a tick index into an exact decimal price, rounded down to `scale` fractional digits.
+///
+/// # Parameters
+/// * `tick_index` - The tick index to convert
+/// * `decimals_a` - The number of decimals of the base token
+/// * `decimals_b` - The number of decimals of the quote token
+/// * `scale` - The number of fractional digits of the result
+///
+/// # Returns
+/// * `DecimalPrice` - The decimal price
+pub fn tick_index_to_decimal_price(tick_index: i32, decimals_a: u8, decimals_b: u8, scale: u8) -> Result<DecimalPrice, CoreError> {
+    sqrt_price_to_decimal_price(tick_index_to_sqrt_price(tick_index), decimals_a, decimals_b, scale)
+}
+
+/// Convert an exact decimal price into the tick index whose price is the closest one not above it.
+///
+/// # Parameters
+/// * `price` - The price to convert
+/// * `decimals_a` - The number of decimals of the base token
+/// * `decimals_b` - The number of decimals of the quote token
+///
+/// # Returns
+/// * `i32` - The tick index
+pub fn decimal_price_to_tick_index(price: DecimalPrice, decimals_a: u8, decimals_b: u8) -> Result<i32, CoreError> {
+    let sqrt_price = decimal_price_to_sqrt_price(price, decimals_a, decimals_b, false)?;
+    Ok(sqrt_price_to_tick_index(sqrt_price))
+}
+
+// Computes floor(numerator * 10^exponent / denominator) and whether the result is inexact.
+fn mul_div_pow10(numerator: U256, denominator: U256, exponent: i32) -> Result<(U256, bool), CoreError> {
+    let ten = <U256>::from(10u8);
+    let mut quotient = numerator / denominator;
+    let mut remainder = numerator % denominator;
+
+    if exponent >= 0 {
+        // Long division keeps the intermediate values below the denominator multiplied by ten.
+        for _ in 0..exponent {
+            let shifted = remainder.checked_mul(ten).ok_or(ARITHMETIC_OVERFLOW)?;
+            quotient = quotient
+                .checked_mul(ten)
+                .and_then(|q| q.checked_add(shifted / denominator))
+                .ok_or(ARITHMETIC_OVERFLOW)?;
+            remainder = shifted % denominator;
+        }
+        Ok((quotient, remainder != 0))
+    } else {
+        let mut inexact = remainder != 0;
+        for _ in 0..-exponent {
+            inexact |= quotient % ten != 0;
+            quotient /= ten;
+        }
+        Ok((quotient, inexact))
+    }
+}
+
+// Computes the square root of a X128 price that was truncated from its exact value if `inexact` is set.
+fn checked_sqrt_price(price_x128: U256, inexact: bool, round_up: bool) -> Result<u128, CoreError> {
+    let mut sqrt_price = sqrt_u256(price_x128);
+    if round_up && (inexact || sqrt_price * sqrt_price != price_x128) {
+        sqrt_price += 1;
+    }
+    let sqrt_price: u128 = sqrt_price.try_into().map_err(|_| SQRT_PRICE_OUT_OF_BOUNDS)?;
+    if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&sqrt_price) {
+        return Err(SQRT_PRICE_OUT_OF_BOUNDS);
+    }
+    Ok(sqrt_price)
+}
+
 #[cfg(all(test, not(feature = "wasm")))]
 mod tests {
     use approx::assert_relative_eq;
 
     use super::*;
+    use crate::{MAX_TICK_INDEX, MIN_TICK_INDEX};
 
     #[test]
     fn test_price_to_sqrt_price() {
@@ -171,4 +318,81 @@ mod tests {
         let diff_rate = (diff as f64) / (sqrt_price as f64) * 100.0;
         assert_relative_eq!(diff_rate, 0.0, epsilon = 1e-10);
     }
+
+    // Returns the most precise price that fits into the mantissa.
+    fn max_scale_price(to_price: impl Fn(u8) -> Result<DecimalPrice, CoreError>) -> DecimalPrice {
+        (0..=80).rev().find_map(|scale| to_price(scale).ok()).unwrap()
+    }
+
+    #[test]
+    fn test_sqrt_price_to_decimal_price() {
+        assert_eq!(sqrt_price_to_decimal_price(1 << 64, 6, 6, 4), Ok(DecimalPrice::new(10000, 4)));
+        assert_eq!(sqrt_price_to_decimal_price(184467440737095516160, 6, 6, 2), Ok(DecimalPrice::new(10000, 2)));
+        assert_eq!(sqrt_price_to_decimal_price(184467440737095516160, 8, 6, 0), Ok(DecimalPrice::new(10000, 0)));
+        assert_eq!(sqrt_price_to_decimal_price(184467440737095516160, 6, 8, 3), Ok(DecimalPrice::new(1000, 3)));
+        // 0.5^2 = 0.25, rounded down
+        assert_eq!(sqrt_price_to_decimal_price(1 << 63, 6, 6, 1), Ok(DecimalPrice::new(2, 1)));
+        assert_eq!(sqrt_price_to_decimal_price(6918418495991757039, 9, 6, 14).unwrap().to_string(), "140.66116595692345");
+        assert_eq!(sqrt_price_to_decimal_price(MAX_SQRT_PRICE, 0, 0, 30), Err(ARITHMETIC_OVERFLOW));
+    }
+
+    #[test]
+    fn test_decimal_price_to_sqrt_price() {
+        let price = |s: &str| s.parse::<DecimalPrice>().unwrap();
+        assert_eq!(decimal_price_to_sqrt_price(price("1"), 6, 6, false), Ok(1 << 64));
+        assert_eq!(decimal_price_to_sqrt_price(price("100.000"), 6, 6, false), Ok(184467440737095516160));
+        assert_eq!(decimal_price_to_sqrt_price(price("0.25"), 6, 6, true), Ok(1 << 63));
+        assert_eq!(decimal_price_to_sqrt_price(price("0.00999999"), 8, 6, false), Ok(184467348503352089));
+        assert_eq!(decimal_price_to_sqrt_price(price("2"), 6, 6, false), Ok(26087635650665564424));
+        assert_eq!(decimal_price_to_sqrt_price(price("2"), 6, 6, true), Ok(26087635650665564425));
+        assert_eq!(decimal_price_to_sqrt_price(price("0"), 6, 6, false), Err(SQRT_PRICE_OUT_OF_BOUNDS));
+        assert_eq!(decimal_price_to_sqrt_price(price("1000000000000000000000000"), 0, 24, false), Err(SQRT_PRICE_OUT_OF_BOUNDS));
+    }
+
+    #[test]
+    fn test_inverted_decimal_price() {
+        let price = |s: &str| s.parse::<DecimalPrice>().unwrap();
+        let sqrt_price = 184467440737095516160;
+        assert_eq!(sqrt_price_to_inverted_decimal_price(sqrt_price, 6, 6, 4), Ok(DecimalPrice::new(100, 4)));
+        assert_eq!(sqrt_price_to_inverted_decimal_price(sqrt_price, 8, 6, 8), Ok(DecimalPrice::new(10000, 8)));
+        assert_eq!(sqrt_price_to_inverted_decimal_price(sqrt_price, 6, 8, 0), Ok(DecimalPrice::new(1, 0)));
+        assert_eq!(sqrt_price_to_inverted_decimal_price(0, 6, 6, 0), Err(SQRT_PRICE_OUT_OF_BOUNDS));
+        assert_eq!(inverted_decimal_price_to_sqrt_price(price("0.01"), 6, 6, false), Ok(sqrt_price));
+        assert_eq!(inverted_decimal_price_to_sqrt_price(price("0.0001"), 8, 6, true), Ok(sqrt_price));
+        assert_eq!(inverted_decimal_price_to_sqrt_price(price("4"), 6, 6, false), Ok(1 << 63));
+        assert_eq!(inverted_decimal_price_to_sqrt_price(price("0.0"), 6, 6, false), Err(INVALID_DECIMAL_PRICE));
+    }
+
+    #[test]
+    fn test_decimal_price_tick_index() {
+        let price = |s: &str| s.parse::<DecimalPrice>().unwrap();
+        assert_eq!(tick_index_to_decimal_price(0, 6, 6, 6), Ok(DecimalPrice::new(1000000, 6)));
+        assert_eq!(tick_index_to_decimal_price(-92111, 8, 6, 6), Ok(DecimalPrice::new(9997, 6)));
+        assert_eq!(decimal_price_to_tick_index(price("1"), 6, 6), Ok(0));
+        assert_eq!(decimal_price_to_tick_index(price("0.009998"), 8, 6), Ok(-92111));
+        assert_eq!(decimal_price_to_tick_index(price("99.999912"), 6, 8), Ok(92108));
+        for tick_index in [MIN_TICK_INDEX, -92111, -1, 0, 1, 92108, MAX_TICK_INDEX] {
+            let price = max_scale_price(|scale| tick_index_to_decimal_price(tick_index, 6, 6, scale));
+            assert_eq!(decimal_price_to_sqrt_price(price, 6, 6, true), Ok(tick_index_to_sqrt_price(tick_index)));
+        }
+    }
+
+    #[test]
+    fn test_decimal_price_round_trip() {
+        for decimals in [(6u8, 6u8), (9, 6), (5, 6), (0, 18), (18, 0)] {
+            for sqrt_price in [
+                MIN_SQRT_PRICE,
+                265989152599097743,
+                6918418495991757039,
+                1 << 64,
+                184467440737095516160,
+                MAX_SQRT_PRICE,
+            ] {
+                let price = max_scale_price(|scale| sqrt_price_to_decimal_price(sqrt_price, decimals.0, decimals.1, scale));
+                assert_eq!(decimal_price_to_sqrt_price(price, decimals.0, decimals.1, true), Ok(sqrt_price));
+                let inverted_price = max_scale_price(|scale| sqrt_price_to_inverted_decimal_price(sqrt_price, decimals.0, decimals.1, scale));
+                assert_eq!(inverted_decimal_price_to_sqrt_price(inverted_price, decimals.0, decimals.1, false), Ok(sqrt_price));
+            }
+        }
+    }
 }
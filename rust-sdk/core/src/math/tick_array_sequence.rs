@@ -13,16 +13,28 @@ use crate::{
     TickArrayFacade, TickFacade, INVALID_TICK_ARRAY_SEQUENCE, INVALID_TICK_INDEX, MAX_TICK_INDEX, MIN_TICK_INDEX, TICK_ARRAY_SIZE,
     TICK_INDEX_OUT_OF_BOUNDS, TICK_SEQUENCE_EMPTY,
 };
-use once_cell::sync::Lazy;
-use std::collections::HashMap;
-
-static DEFAULT_TICK_FACADE: Lazy<TickFacade> = Lazy::new(TickFacade::default);
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+static DEFAULT_TICK_FACADE: TickFacade = TickFacade {
+    initialized: false,
+    liquidity_net: 0,
+    liquidity_gross: 0,
+    fee_growth_outside_a: 0,
+    fee_growth_outside_b: 0,
+    age: 0,
+    open_orders_input: 0,
+    part_filled_orders_input: 0,
+    part_filled_orders_remaining_input: 0,
+    fulfilled_a_to_b_orders_input: 0,
+    fulfilled_b_to_a_orders_input: 0,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TickArraySequence {
     pub start_tick_index: i32,
     pub end_tick_index: i32,
-    pub ticks: HashMap<i32, TickFacade>,
+    pub ticks: BTreeMap<i32, TickFacade>,
     pub tick_spacing: u16,
 }
 
@@ -35,7 +47,7 @@ impl TickArraySequence {
             return Err(TICK_SEQUENCE_EMPTY);
         }
 
-        let mut ticks = HashMap::new();
+        let mut ticks = BTreeMap::new();
 
         let start_tick_index = tick_arrays[0].start_tick_index;
         let end_tick_index = tick_arrays.last().unwrap().start_tick_index + TICK_ARRAY_SIZE as i32 * tick_spacing as i32 - 1;
@@ -62,7 +74,7 @@ impl TickArraySequence {
         Self {
             start_tick_index: full_range.tick_lower_index,
             end_tick_index: full_range.tick_upper_index,
-            ticks: HashMap::new(),
+            ticks: BTreeMap::new(),
             tick_spacing,
         }
     }
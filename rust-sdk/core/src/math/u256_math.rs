@@ -9,11 +9,12 @@
 //
 
 use crate::{CoreError, ARITHMETIC_OVERFLOW};
-use std::{
+use core::{
     cmp::Ordering,
     fmt::{Display, Formatter, Result as FmtResult},
     str::from_utf8_unchecked,
 };
+use ethnum::U256;
 
 const NUM_WORDS: usize = 4;
 
@@ -481,6 +482,22 @@ pub fn mul_u256(v: u128, n: u128) -> U256Muldiv {
     U256Muldiv::new(c1, c0)
 }
 
+// Returns the floor of the square root of a U256 value.
+pub(crate) fn sqrt_u256(value: U256) -> U256 {
+    if value < 2 {
+        return value;
+    }
+    // Start above the root and use Newton's method, which decreases monotonically to the floor of the root.
+    let mut x = U256::ONE << ((256 - value.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + value / x) >> 1;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
 fn div_loop(
     index: usize,
     num_divisor_words: usize,
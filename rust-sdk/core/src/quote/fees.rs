@@ -39,30 +39,7 @@ pub fn collect_fees_quote(
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
 ) -> Result<CollectFeesQuote, CoreError> {
-    let mut fee_growth_below_a: u128 = tick_lower.fee_growth_outside_a;
-    let mut fee_growth_above_a: u128 = tick_upper.fee_growth_outside_a;
-    let mut fee_growth_below_b: u128 = tick_lower.fee_growth_outside_b;
-    let mut fee_growth_above_b: u128 = tick_upper.fee_growth_outside_b;
-
-    if fusion_pool.tick_current_index < position.tick_lower_index {
-        fee_growth_below_a = fusion_pool.fee_growth_global_a.wrapping_sub(fee_growth_below_a);
-        fee_growth_below_b = fusion_pool.fee_growth_global_b.wrapping_sub(fee_growth_below_b);
-    }
-
-    if fusion_pool.tick_current_index >= position.tick_upper_index {
-        fee_growth_above_a = fusion_pool.fee_growth_global_a.wrapping_sub(fee_growth_above_a);
-        fee_growth_above_b = fusion_pool.fee_growth_global_b.wrapping_sub(fee_growth_above_b);
-    }
-
-    let fee_growth_inside_a = fusion_pool
-        .fee_growth_global_a
-        .wrapping_sub(fee_growth_below_a)
-        .wrapping_sub(fee_growth_above_a);
-
-    let fee_growth_inside_b = fusion_pool
-        .fee_growth_global_b
-        .wrapping_sub(fee_growth_below_b)
-        .wrapping_sub(fee_growth_above_b);
+    let (fee_growth_inside_a, fee_growth_inside_b) = get_fee_growth_inside(fusion_pool, position, tick_lower, tick_upper);
 
     let fee_growth_delta_a = fee_growth_inside_a.wrapping_sub(position.fee_growth_checkpoint_a);
 
@@ -90,6 +67,41 @@ pub fn collect_fees_quote(
     Ok(CollectFeesQuote { fee_owed_a, fee_owed_b })
 }
 
+// Calculates the fee growth per unit of liquidity inside the range of a position.
+pub(crate) fn get_fee_growth_inside(
+    fusion_pool: FusionPoolFacade,
+    position: PositionFacade,
+    tick_lower: TickFacade,
+    tick_upper: TickFacade,
+) -> (u128, u128) {
+    let mut fee_growth_below_a: u128 = tick_lower.fee_growth_outside_a;
+    let mut fee_growth_above_a: u128 = tick_upper.fee_growth_outside_a;
+    let mut fee_growth_below_b: u128 = tick_lower.fee_growth_outside_b;
+    let mut fee_growth_above_b: u128 = tick_upper.fee_growth_outside_b;
+
+    if fusion_pool.tick_current_index < position.tick_lower_index {
+        fee_growth_below_a = fusion_pool.fee_growth_global_a.wrapping_sub(fee_growth_below_a);
+        fee_growth_below_b = fusion_pool.fee_growth_global_b.wrapping_sub(fee_growth_below_b);
+    }
+
+    if fusion_pool.tick_current_index >= position.tick_upper_index {
+        fee_growth_above_a = fusion_pool.fee_growth_global_a.wrapping_sub(fee_growth_above_a);
+        fee_growth_above_b = fusion_pool.fee_growth_global_b.wrapping_sub(fee_growth_above_b);
+    }
+
+    let fee_growth_inside_a = fusion_pool
+        .fee_growth_global_a
+        .wrapping_sub(fee_growth_below_a)
+        .wrapping_sub(fee_growth_above_a);
+
+    let fee_growth_inside_b = fusion_pool
+        .fee_growth_global_b
+        .wrapping_sub(fee_growth_below_b)
+        .wrapping_sub(fee_growth_above_b);
+
+    (fee_growth_inside_a, fee_growth_inside_b)
+}
+
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub fn limit_order_fee(fusion_pool: FusionPoolFacade) -> i32 {
     let fee = fusion_pool.fee_rate as u64 * (PROTOCOL_FEE_RATE_MUL_VALUE as u64 - fusion_pool.protocol_fee_rate as u64)
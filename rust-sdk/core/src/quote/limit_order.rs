@@ -7,9 +7,10 @@
 
 use crate::math::get_limit_order_output_amount;
 use crate::{
-    tick_index_to_sqrt_price, try_apply_transfer_fee, try_mul_div, try_reverse_apply_swap_fee, CoreError, FusionPoolFacade, LimitOrderDecreaseQuote,
-    LimitOrderFacade, TickFacade, TransferFee, AMOUNT_EXCEEDS_LIMIT_ORDER_INPUT_AMOUNT, AMOUNT_EXCEEDS_MAX_U64, FEE_RATE_MUL_VALUE,
-    LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC, PROTOCOL_FEE_RATE_MUL_VALUE,
+    limit_order_status, tick_index_to_sqrt_price, try_apply_transfer_fee, try_mul_div, try_reverse_apply_swap_fee, CoreError, FusionPoolFacade,
+    LimitOrderDecreaseQuote, LimitOrderFacade, LimitOrderFillQuote, LimitOrderStatus, TickFacade, TransferFee,
+    AMOUNT_EXCEEDS_LIMIT_ORDER_INPUT_AMOUNT, AMOUNT_EXCEEDS_MAX_U64, BPS_DENOMINATOR, LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC,
+    PROTOCOL_FEE_RATE_MUL_VALUE,
 };
 
 #[cfg(feature = "wasm")]
@@ -29,12 +30,12 @@ pub fn limit_order_quote_by_input_token(
     fusion_pool: FusionPoolFacade,
 ) -> Result<u64, CoreError> {
     let sqrt_price: u128 = tick_index_to_sqrt_price(tick_index).into();
-    let mut amount_out = get_limit_order_output_amount(amount_in, a_to_b_order, sqrt_price, false)?;
-    amount_out += limit_order_reward_by_output_token(amount_out, fusion_pool.fee_rate, fusion_pool.protocol_fee_rate)?;
-    Ok(amount_out)
+    let amount_out = get_limit_order_output_amount(amount_in, a_to_b_order, sqrt_price, false)?;
+    limit_order_output_with_reward(amount_out, fusion_pool.fee_rate, fusion_pool.protocol_fee_rate)
 }
 
 /// Computes the limit order input amount by output amount.
+/// The returned input amount is the smallest one for which `limit_order_quote_by_input_token` returns at least `amount_out`.
 /// ### Parameters
 /// - `amount_out` - The output token amount of a limit order.
 /// - `a_to_b_order` - The limit order direction.
@@ -47,22 +48,17 @@ pub fn limit_order_quote_by_output_token(
     tick_index: i32,
     fusion_pool: FusionPoolFacade,
 ) -> Result<u64, CoreError> {
-    let sqrt_price: u128 = tick_index_to_sqrt_price(tick_index).into();
-
-    let f = fusion_pool.fee_rate as f64 / FEE_RATE_MUL_VALUE as f64;
-    let p = fusion_pool.protocol_fee_rate as f64 / PROTOCOL_FEE_RATE_MUL_VALUE as f64;
-
-    // Output amount without reward = O
-    // Limit order reward = R = swap_fee⋅(1-p) = O⋅f/(1-f)⋅(1-p)
-    // Output amount with fees = O' = O + R = O ⋅ (1 + f/(1-f)⋅(1-p))
-    let denominator = 1.0 + (f / (1.0 - f) * (1.0 - p));
-    let amount_out_with_fees = amount_out as f64 / denominator;
-
-    if amount_out_with_fees < 0.0 || amount_out_with_fees > u64::MAX as f64 {
-        return Err(AMOUNT_EXCEEDS_MAX_U64);
-    }
-
-    let amount_in = get_limit_order_output_amount(amount_out_with_fees as u64, !a_to_b_order, sqrt_price, true)?;
+    // The output amount is computed with a truncated price and rounded fees, so it can't be inverted exactly with
+    // a formula. The input based quote is monotonic, so the smallest sufficient input amount is found by a binary search.
+    let amount_in = min_sufficient_amount(u64::MAX, |amount| {
+        match limit_order_quote_by_input_token(amount, a_to_b_order, tick_index, fusion_pool) {
+            Ok(output) => output >= amount_out,
+            // The output amount exceeds u64 and is therefore sufficient.
+            Err(_) => true,
+        }
+    })?;
+    // Fails if the smallest sufficient input amount overflows the output amount.
+    limit_order_quote_by_input_token(amount_in, a_to_b_order, tick_index, fusion_pool)?;
 
     Ok(amount_in)
 }
@@ -82,6 +78,33 @@ pub fn limit_order_reward_by_output_token(amount_out: u64, fee_rate: u16, protoc
     Ok(reward)
 }
 
+/// Returns the smallest amount in the range [0; max_amount] for which the monotonic predicate is true.
+fn min_sufficient_amount<F>(max_amount: u64, is_sufficient: F) -> Result<u64, CoreError>
+where
+    F: Fn(u64) -> bool,
+{
+    if !is_sufficient(max_amount) {
+        return Err(AMOUNT_EXCEEDS_MAX_U64);
+    }
+
+    let mut low = 0;
+    let mut high = max_amount;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if is_sufficient(mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Ok(low)
+}
+
+fn limit_order_output_with_reward(amount_out: u64, fee_rate: u16, protocol_fee_rate: u16) -> Result<u64, CoreError> {
+    let reward = limit_order_reward_by_output_token(amount_out, fee_rate, protocol_fee_rate)?;
+    amount_out.checked_add(reward).ok_or(AMOUNT_EXCEEDS_MAX_U64)
+}
+
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub fn decrease_limit_order_quote(
     fusion_pool: FusionPoolFacade,
@@ -95,27 +118,19 @@ pub fn decrease_limit_order_quote(
         return Err(AMOUNT_EXCEEDS_LIMIT_ORDER_INPUT_AMOUNT);
     }
 
-    // Not filled
-    let (amount_in, amount_out) = if limit_order.age == tick.age {
-        (amount, 0)
-    }
-    // Partially filled
-    else if limit_order.age + 1 == tick.age {
-        if tick.part_filled_orders_input == 0 {
-            return Err(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC);
+    let sqrt_price: u128 = tick_index_to_sqrt_price(limit_order.tick_index).into();
+    let (amount_in, amount_out) = match limit_order_status(limit_order, tick) {
+        LimitOrderStatus::Open => (amount, 0),
+        LimitOrderStatus::PartiallyFilled => {
+            let remaining_input = try_mul_div(amount, tick.part_filled_orders_remaining_input as u128, tick.part_filled_orders_input as u128, false)?;
+            let amount_out = get_limit_order_output_amount(amount - remaining_input, limit_order.a_to_b, sqrt_price, false)?;
+            (remaining_input, amount_out)
         }
-        let sqrt_price: u128 = tick_index_to_sqrt_price(limit_order.tick_index).into();
-        let remaining_input = try_mul_div(amount, tick.part_filled_orders_remaining_input as u128, tick.part_filled_orders_input as u128, false)?;
-        let amount_out = get_limit_order_output_amount(amount - remaining_input, limit_order.a_to_b, sqrt_price, false)?;
-        (remaining_input, amount_out)
-    }
-    // Fulfilled
-    else if limit_order.age + 2 <= tick.age {
-        let sqrt_price: u128 = tick_index_to_sqrt_price(limit_order.tick_index).into();
-        let amount_out = get_limit_order_output_amount(amount, limit_order.a_to_b, sqrt_price, false)?;
-        (0, amount_out)
-    } else {
-        return Err(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC);
+        LimitOrderStatus::Filled => {
+            let amount_out = get_limit_order_output_amount(amount, limit_order.a_to_b, sqrt_price, false)?;
+            (0, amount_out)
+        }
+        LimitOrderStatus::OutOfSync => return Err(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC),
     };
 
     let mut amount_out_a;
@@ -160,11 +175,63 @@ pub fn decrease_limit_order_quote(
     })
 }
 
+/// Computes the fill progress of a limit order and the amounts its owner can claim.
+/// ### Parameters
+/// - `fusion_pool` - The fusion_pool state.
+/// - `limit_order` - The limit order.
+/// - `tick` - The tick of the limit order.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn limit_order_fill_quote(
+    fusion_pool: FusionPoolFacade,
+    limit_order: LimitOrderFacade,
+    tick: TickFacade,
+) -> Result<LimitOrderFillQuote, CoreError> {
+    let out_of_sync = LimitOrderFillQuote {
+        status: LimitOrderStatus::OutOfSync,
+        filled_amount: 0,
+        filled_bps: 0,
+        claimable_input: 0,
+        claimable_output: 0,
+        reward: 0,
+    };
+
+    let status = limit_order_status(limit_order, tick);
+    let quote = match decrease_limit_order_quote(fusion_pool, limit_order, tick, limit_order.amount, None, None) {
+        Ok(quote) => quote,
+        Err(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC) => return Ok(out_of_sync),
+        Err(err) => return Err(err),
+    };
+
+    let (claimable_input, claimable_output, reward) = if limit_order.a_to_b {
+        (quote.amount_out_a, quote.amount_out_b - quote.reward_b, quote.reward_b)
+    } else {
+        (quote.amount_out_b, quote.amount_out_a - quote.reward_a, quote.reward_a)
+    };
+
+    let filled_amount = limit_order.amount - claimable_input;
+    let filled_bps = if limit_order.amount == 0 {
+        0
+    } else {
+        try_mul_div(filled_amount, BPS_DENOMINATOR as u128, limit_order.amount as u128, false)? as u16
+    };
+
+    Ok(LimitOrderFillQuote {
+        status,
+        filled_amount,
+        filled_bps,
+        claimable_input,
+        claimable_output,
+        reward,
+    })
+}
+
 #[cfg(all(test, not(feature = "wasm")))]
 mod tests {
     use crate::{
-        decrease_limit_order_quote, limit_order_quote_by_input_token, limit_order_quote_by_output_token, price_to_tick_index,
-        sqrt_price_to_tick_index, FusionPoolFacade, LimitOrderFacade, TickFacade,
+        decrease_limit_order_quote, get_limit_order_output_amount, limit_order_fill_quote, limit_order_quote_by_input_token,
+        limit_order_quote_by_output_token, limit_order_status, price_to_tick_index, sqrt_price_to_tick_index, tick_index_to_sqrt_price,
+        FusionPoolFacade, LimitOrderFacade, LimitOrderStatus, TickFacade, AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW, MAX_FEE_RATE,
+        MAX_PROTOCOL_FEE_RATE, MAX_TICK_INDEX, MIN_TICK_INDEX,
     };
     const TEN_PCT: u16 = 1000;
     const ONE_PCT_FEE_RATE: u16 = 10000;
@@ -404,4 +471,130 @@ mod tests {
             10_000
         );
     }
+
+    #[test]
+    fn test_limit_order_status() {
+        let limit_order = LimitOrderFacade {
+            tick_index: 128,
+            amount: 50_000,
+            a_to_b: true,
+            age: 5,
+        };
+        let tick = |age: u64, part_filled_orders_input: u64| TickFacade {
+            age,
+            part_filled_orders_input,
+            ..TickFacade::default()
+        };
+
+        assert_eq!(limit_order_status(limit_order, tick(5, 0)), LimitOrderStatus::Open);
+        assert_eq!(limit_order_status(limit_order, tick(6, 100)), LimitOrderStatus::PartiallyFilled);
+        assert_eq!(limit_order_status(limit_order, tick(6, 0)), LimitOrderStatus::OutOfSync);
+        assert_eq!(limit_order_status(limit_order, tick(7, 0)), LimitOrderStatus::Filled);
+        assert_eq!(limit_order_status(limit_order, tick(9, 0)), LimitOrderStatus::Filled);
+        assert_eq!(limit_order_status(limit_order, tick(4, 0)), LimitOrderStatus::OutOfSync);
+    }
+
+    #[test]
+    fn test_limit_order_fill_quote() {
+        let fusion_pool = FusionPoolFacade {
+            protocol_fee_rate: TEN_PCT,
+            orders_filled_amount_a: 80_000,
+            olp_fee_owed_b: 500,
+            ..FusionPoolFacade::default()
+        };
+        let limit_order = LimitOrderFacade {
+            tick_index: 128,
+            amount: 50_000,
+            a_to_b: true,
+            age: 5,
+        };
+        let tick = TickFacade {
+            age: 6,
+            part_filled_orders_input: 200_000,
+            part_filled_orders_remaining_input: 120_000,
+            ..TickFacade::default()
+        };
+
+        // Open
+        let quote = limit_order_fill_quote(fusion_pool, limit_order, TickFacade { age: 5, ..tick }).unwrap();
+        assert_eq!(quote.status, LimitOrderStatus::Open);
+        assert_eq!(
+            (quote.filled_amount, quote.filled_bps, quote.claimable_input, quote.claimable_output, quote.reward),
+            (0, 0, 50_000, 0, 0)
+        );
+
+        // Partially filled
+        let quote = limit_order_fill_quote(fusion_pool, limit_order, tick).unwrap();
+        assert_eq!(quote.status, LimitOrderStatus::PartiallyFilled);
+        assert_eq!(quote.filled_amount, 20_000);
+        assert_eq!(quote.filled_bps, 4000);
+        assert_eq!(quote.claimable_input, 30_000);
+        assert_eq!(quote.claimable_output, get_limit_order_output_amount(20_000, true, tick_index_to_sqrt_price(128), false).unwrap());
+        assert_eq!(quote.reward, 125);
+
+        let decrease_quote = decrease_limit_order_quote(fusion_pool, limit_order, tick, limit_order.amount, None, None).unwrap();
+        assert_eq!(quote.claimable_input, decrease_quote.amount_out_a);
+        assert_eq!(quote.claimable_output + quote.reward, decrease_quote.amount_out_b);
+
+        // Filled
+        let quote = limit_order_fill_quote(fusion_pool, limit_order, TickFacade { age: 7, ..tick }).unwrap();
+        assert_eq!(quote.status, LimitOrderStatus::Filled);
+        assert_eq!((quote.filled_amount, quote.filled_bps, quote.claimable_input), (50_000, 10_000, 0));
+        assert_eq!(quote.reward, 312);
+
+        // Out of sync
+        let quote = limit_order_fill_quote(fusion_pool, limit_order, TickFacade { age: 4, ..tick }).unwrap();
+        assert_eq!(quote.status, LimitOrderStatus::OutOfSync);
+        let quote = limit_order_fill_quote(FusionPoolFacade::default(), limit_order, tick).unwrap();
+        assert_eq!(quote.status, LimitOrderStatus::OutOfSync);
+    }
+
+    #[test]
+    fn test_limit_order_quote_by_output_token_round_trip() {
+        let fee_settings = [
+            (0, 0),
+            (100, 0),
+            (3000, TEN_PCT),
+            (ONE_PCT_FEE_RATE, 2500),
+            (MAX_FEE_RATE, TEN_PCT),
+            (MAX_FEE_RATE, MAX_PROTOCOL_FEE_RATE),
+        ];
+        let amounts = [1, 2, 7, 999, 1_000_000, 123_456_789_123, 1 << 50];
+
+        for tick_index in (MIN_TICK_INDEX..=MAX_TICK_INDEX).step_by(7919).chain([MIN_TICK_INDEX, 0, MAX_TICK_INDEX]) {
+            for (fee_rate, protocol_fee_rate) in fee_settings {
+                let fusion_pool = test_fusion_pool(1 << 64, fee_rate, protocol_fee_rate);
+                for a_to_b_order in [true, false] {
+                    let quote_by_input = |amount_in: u64| limit_order_quote_by_input_token(amount_in, a_to_b_order, tick_index, fusion_pool);
+
+                    for amount_out in amounts {
+                        let amount_in = match limit_order_quote_by_output_token(amount_out, a_to_b_order, tick_index, fusion_pool) {
+                            Ok(amount_in) => amount_in,
+                            // The input amount doesn't fit into u64 at extreme prices.
+                            Err(err) => {
+                                assert!(err == AMOUNT_EXCEEDS_MAX_U64 || err == ARITHMETIC_OVERFLOW);
+                                continue;
+                            }
+                        };
+
+                        // The input amount is sufficient...
+                        assert!(quote_by_input(amount_in).unwrap() >= amount_out);
+                        // ...and exactly sufficient.
+                        if amount_in > 0 {
+                            assert!(quote_by_input(amount_in - 1).unwrap() < amount_out);
+                        }
+                    }
+
+                    for amount_in in amounts {
+                        let Ok(amount_out) = quote_by_input(amount_in) else {
+                            continue;
+                        };
+                        if amount_out > 0 {
+                            assert!(limit_order_quote_by_output_token(amount_out, a_to_b_order, tick_index, fusion_pool).unwrap() <= amount_in);
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,513 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::quote::get_next_liquidity;
+use crate::{
+    get_amount_a_from_liquidity_u128, get_amount_b_from_liquidity_u128, get_limit_order_output_amount, tick_index_to_sqrt_price, CoreError,
+    FusionPoolFacade, TickArraySequence, TickFacade, ARITHMETIC_OVERFLOW, BPS_DENOMINATOR, FEE_RATE_MUL_VALUE, INVALID_RANGE_BOUNDS, MAX_SQRT_PRICE,
+    MIN_SQRT_PRICE, SQRT_PRICE_OUT_OF_BOUNDS, SQRT_PRICE_OUT_OF_DISTRIBUTION_RANGE,
+};
+use alloc::{vec, vec::Vec};
+
+/// An initialized tick of the liquidity distribution.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LiquidityDistributionTick {
+    pub tick_index: i32,
+    pub sqrt_price: u128,
+    pub liquidity_net: i128,
+    /// The active liquidity between this tick and the next initialized tick above it.
+    pub active_liquidity: u128,
+    /// The remaining input amount of the open and partially filled limit orders resting on the tick.
+    pub limit_order_input: u64,
+    /// The direction of the resting limit orders. Ticks above the pool price hold A to B orders (token A input),
+    /// the other ones hold B to A orders (token B input).
+    pub limit_order_a_to_b: bool,
+}
+
+/// A point of the depth curve. Amounts are cumulative from the current pool price up to `sqrt_price` and include
+/// the limit orders resting on the tick at `sqrt_price` (if any). Swap fees are not included.
+///
+/// On the ASK side token A is bought with token B, on the BID side token B is bought with token A.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LiquidityDepthPoint {
+    pub sqrt_price: u128,
+    /// The active liquidity beyond this point in the direction away from the current price.
+    pub liquidity: u128,
+    pub concentrated_amount_a: u128,
+    pub concentrated_amount_b: u128,
+    pub limit_amount_a: u128,
+    pub limit_amount_b: u128,
+}
+
+/// Liquidity distribution of a pool over a price range.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LiquidityDistribution {
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    pub liquidity: u128,
+    pub fee_rate: u16,
+    /// Initialized ticks in the range, sorted by tick index.
+    pub ticks: Vec<LiquidityDistributionTick>,
+    /// Depth points above the current price, sorted by ascending price. The first point is the current price.
+    pub ask_depth: Vec<LiquidityDepthPoint>,
+    /// Depth points below the current price, sorted by descending price. The first point is the current price.
+    pub bid_depth: Vec<LiquidityDepthPoint>,
+}
+
+/// The amounts required to move the pool price to a target price.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LiquidityDepthQuote {
+    pub sqrt_price: u128,
+    /// The swap direction required to move the price.
+    pub a_to_b: bool,
+    /// The swap input amount excluding the swap fee (token B for the ASK side, token A for the BID side).
+    pub amount_in: u128,
+    /// The swap output amount (token A for the ASK side, token B for the BID side).
+    pub amount_out: u128,
+    /// The swap fee in the input token.
+    pub fee_amount: u128,
+}
+
+/// Calculate the liquidity distribution of a pool: the active liquidity and the resting limit orders of every
+/// initialized tick, and the cumulative depth on both sides of the current price.
+///
+/// The distribution is truncated at the bounds of the tick sequence if they are reached before the range bounds.
+///
+/// # Parameters
+/// - `fusion_pool`: The fusion_pool state
+/// - `tick_sequence`: The tick sequence
+/// - `sqrt_price_lower`: The lower bound of the range. Must not be above the current pool price.
+/// - `sqrt_price_upper`: The upper bound of the range. Must not be below the current pool price.
+///
+/// # Returns
+/// - `LiquidityDistribution`: The liquidity distribution over the range
+pub fn get_liquidity_distribution(
+    fusion_pool: &FusionPoolFacade,
+    tick_sequence: &TickArraySequence,
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+) -> Result<LiquidityDistribution, CoreError> {
+    if sqrt_price_lower < MIN_SQRT_PRICE || sqrt_price_upper > MAX_SQRT_PRICE {
+        return Err(SQRT_PRICE_OUT_OF_BOUNDS);
+    }
+    if sqrt_price_lower > fusion_pool.sqrt_price || sqrt_price_upper < fusion_pool.sqrt_price {
+        return Err(INVALID_RANGE_BOUNDS);
+    }
+
+    let (mut bid_ticks, bid_depth) = walk_depth(fusion_pool, tick_sequence, sqrt_price_lower, true)?;
+    let (ask_ticks, ask_depth) = walk_depth(fusion_pool, tick_sequence, sqrt_price_upper, false)?;
+
+    bid_ticks.reverse();
+    bid_ticks.extend(ask_ticks);
+
+    Ok(LiquidityDistribution {
+        sqrt_price: fusion_pool.sqrt_price,
+        tick_current_index: fusion_pool.tick_current_index,
+        liquidity: fusion_pool.liquidity,
+        fee_rate: fusion_pool.fee_rate,
+        ticks: bid_ticks,
+        ask_depth,
+        bid_depth,
+    })
+}
+
+/// Calculate the amounts required to move the pool price to the target price.
+///
+/// The limit orders resting on the tick at the target price are not filled since the price only reaches it.
+///
+/// # Parameters
+/// - `distribution`: The liquidity distribution
+/// - `target_sqrt_price`: The target sqrt price. Must be inside the distribution range.
+///
+/// # Returns
+/// - `LiquidityDepthQuote`: The swap amounts
+pub fn get_liquidity_depth_at_sqrt_price(distribution: &LiquidityDistribution, target_sqrt_price: u128) -> Result<LiquidityDepthQuote, CoreError> {
+    let a_to_b = target_sqrt_price < distribution.sqrt_price;
+    let depth = if a_to_b { &distribution.bid_depth } else { &distribution.ask_depth };
+
+    let last_point = depth.last().ok_or(SQRT_PRICE_OUT_OF_DISTRIBUTION_RANGE)?;
+    let in_range = if a_to_b {
+        target_sqrt_price >= last_point.sqrt_price
+    } else {
+        target_sqrt_price <= last_point.sqrt_price
+    };
+    if !in_range {
+        return Err(SQRT_PRICE_OUT_OF_DISTRIBUTION_RANGE);
+    }
+
+    // The last point strictly before the target price. The first point is the current price.
+    let point = depth
+        .iter()
+        .take_while(|point| {
+            point.sqrt_price == distribution.sqrt_price
+                || (a_to_b && point.sqrt_price > target_sqrt_price)
+                || (!a_to_b && point.sqrt_price < target_sqrt_price)
+        })
+        .last()
+        .ok_or(SQRT_PRICE_OUT_OF_DISTRIBUTION_RANGE)?;
+
+    let (amount_a, amount_b) = get_segment_amounts(point.liquidity, point.sqrt_price, target_sqrt_price, a_to_b)?;
+    let total_a = checked_add(checked_add(point.concentrated_amount_a, point.limit_amount_a)?, amount_a)?;
+    let total_b = checked_add(checked_add(point.concentrated_amount_b, point.limit_amount_b)?, amount_b)?;
+
+    let (amount_in, amount_out) = if a_to_b { (total_a, total_b) } else { (total_b, total_a) };
+
+    // Same rounding as the swap fee of a fully filled step.
+    let fee_rate = distribution.fee_rate as u128;
+    let fee_numerator = amount_in.checked_mul(fee_rate).ok_or(ARITHMETIC_OVERFLOW)?;
+    let fee_denominator = FEE_RATE_MUL_VALUE as u128 - fee_rate;
+    let fee_amount = fee_numerator.div_ceil(fee_denominator);
+
+    Ok(LiquidityDepthQuote {
+        sqrt_price: target_sqrt_price,
+        a_to_b,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+/// Calculate the amounts required to move the pool price by the provided percentage.
+///
+/// # Parameters
+/// - `distribution`: The liquidity distribution
+/// - `price_change_bps`: The price change in basis points. Positive to move the price up, negative to move it down.
+///
+/// # Returns
+/// - `LiquidityDepthQuote`: The swap amounts
+pub fn get_liquidity_depth_for_price_change(distribution: &LiquidityDistribution, price_change_bps: i32) -> Result<LiquidityDepthQuote, CoreError> {
+    let bps_denominator = BPS_DENOMINATOR as i32;
+    if price_change_bps <= -bps_denominator {
+        return Err(SQRT_PRICE_OUT_OF_BOUNDS);
+    }
+
+    let price_ratio = (bps_denominator + price_change_bps) as f64 / bps_denominator as f64;
+    let target_sqrt_price = (distribution.sqrt_price as f64 * libm::sqrt(price_ratio)) as u128;
+    if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&target_sqrt_price) {
+        return Err(SQRT_PRICE_OUT_OF_BOUNDS);
+    }
+
+    get_liquidity_depth_at_sqrt_price(distribution, target_sqrt_price)
+}
+
+// Private functions
+
+fn walk_depth(
+    fusion_pool: &FusionPoolFacade,
+    tick_sequence: &TickArraySequence,
+    sqrt_price_bound: u128,
+    a_to_b: bool,
+) -> Result<(Vec<LiquidityDistributionTick>, Vec<LiquidityDepthPoint>), CoreError> {
+    let mut ticks: Vec<LiquidityDistributionTick> = vec![];
+    let mut point = LiquidityDepthPoint {
+        sqrt_price: fusion_pool.sqrt_price,
+        liquidity: fusion_pool.liquidity,
+        concentrated_amount_a: 0,
+        concentrated_amount_b: 0,
+        limit_amount_a: 0,
+        limit_amount_b: 0,
+    };
+    let mut depth = vec![point];
+    let mut current_tick_index = fusion_pool.tick_current_index;
+
+    while point.sqrt_price != sqrt_price_bound {
+        let next_tick_result = if a_to_b {
+            tick_sequence.prev_initialized_tick(current_tick_index)
+        } else {
+            tick_sequence.next_initialized_tick(current_tick_index)
+        };
+
+        // The end of the tick sequence is reached.
+        let (next_tick, next_tick_index) = match next_tick_result {
+            Ok(r) => r,
+            Err(_) => break,
+        };
+
+        let next_tick_sqrt_price = tick_index_to_sqrt_price(next_tick_index);
+        let next_sqrt_price = if a_to_b {
+            sqrt_price_bound.max(next_tick_sqrt_price)
+        } else {
+            sqrt_price_bound.min(next_tick_sqrt_price)
+        };
+
+        let (amount_a, amount_b) = get_segment_amounts(point.liquidity, point.sqrt_price, next_sqrt_price, a_to_b)?;
+        point.concentrated_amount_a = checked_add(point.concentrated_amount_a, amount_a)?;
+        point.concentrated_amount_b = checked_add(point.concentrated_amount_b, amount_b)?;
+        point.sqrt_price = next_sqrt_price;
+
+        match next_tick {
+            Some(tick) if next_sqrt_price == next_tick_sqrt_price => {
+                let limit_order_input = get_limit_order_input(tick)?;
+                if limit_order_input > 0 {
+                    // A swap in one direction fills the limit orders of the opposite direction.
+                    let limit_order_output = get_limit_order_output_amount(limit_order_input, !a_to_b, next_tick_sqrt_price, true)?;
+                    let (limit_a, limit_b) = if a_to_b {
+                        (limit_order_output, limit_order_input)
+                    } else {
+                        (limit_order_input, limit_order_output)
+                    };
+                    point.limit_amount_a = checked_add(point.limit_amount_a, limit_a as u128)?;
+                    point.limit_amount_b = checked_add(point.limit_amount_b, limit_b as u128)?;
+                }
+
+                let next_liquidity = get_next_liquidity(point.liquidity, Some(tick), a_to_b);
+                ticks.push(distribution_tick(tick, next_tick_index, if a_to_b { point.liquidity } else { next_liquidity }, !a_to_b)?);
+
+                point.liquidity = next_liquidity;
+                current_tick_index = if a_to_b { next_tick_index - 1 } else { next_tick_index };
+                depth.push(point);
+            }
+            _ => {
+                depth.push(point);
+                if next_sqrt_price != sqrt_price_bound {
+                    // The end of the tick sequence is reached.
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok((ticks, depth))
+}
+
+fn distribution_tick(
+    tick: &TickFacade,
+    tick_index: i32,
+    active_liquidity: u128,
+    limit_order_a_to_b: bool,
+) -> Result<LiquidityDistributionTick, CoreError> {
+    Ok(LiquidityDistributionTick {
+        tick_index,
+        sqrt_price: tick_index_to_sqrt_price(tick_index),
+        liquidity_net: tick.liquidity_net,
+        active_liquidity,
+        limit_order_input: get_limit_order_input(tick)?,
+        limit_order_a_to_b,
+    })
+}
+
+/// Returns the input amount of the open and partially filled limit orders of a tick.
+fn get_limit_order_input(tick: &TickFacade) -> Result<u64, CoreError> {
+    tick.open_orders_input
+        .checked_add(tick.part_filled_orders_remaining_input)
+        .ok_or(ARITHMETIC_OVERFLOW)
+}
+
+/// Returns the (token A, token B) amounts swapped by concentrated liquidity between two prices.
+/// The input amount is rounded up and the output amount is rounded down.
+fn get_segment_amounts(liquidity: u128, sqrt_price_from: u128, sqrt_price_to: u128, a_to_b: bool) -> Result<(u128, u128), CoreError> {
+    let sqrt_price_lower = sqrt_price_from.min(sqrt_price_to);
+    let sqrt_price_upper = sqrt_price_from.max(sqrt_price_to);
+    if liquidity == 0 || sqrt_price_lower == sqrt_price_upper {
+        return Ok((0, 0));
+    }
+
+    let amount_a = get_amount_a_from_liquidity_u128(liquidity, sqrt_price_lower, sqrt_price_upper, a_to_b)?;
+    let amount_b = get_amount_b_from_liquidity_u128(liquidity, sqrt_price_lower, sqrt_price_upper, !a_to_b)?;
+    Ok((amount_a, amount_b))
+}
+
+fn checked_add(a: u128, b: u128) -> Result<u128, CoreError> {
+    a.checked_add(b).ok_or(ARITHMETIC_OVERFLOW)
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{
+        compute_swap, get_amount_a_from_liquidity, get_amount_b_from_liquidity, TickArrayFacade, TickArraySequence, TickFacade,
+        AMOUNT_EXCEEDS_MAX_U64, TICK_ARRAY_SIZE,
+    };
+
+    const LIQUIDITY: u128 = 1_000_000_000;
+
+    fn test_fusion_pool() -> FusionPoolFacade {
+        FusionPoolFacade {
+            tick_current_index: 0,
+            sqrt_price: 1 << 64,
+            tick_spacing: 2,
+            fee_rate: 3000,
+            liquidity: LIQUIDITY,
+            ..FusionPoolFacade::default()
+        }
+    }
+
+    fn test_tick_arrays() -> Vec<TickArrayFacade> {
+        let mut tick_arrays: Vec<TickArrayFacade> = [-352, -176, 0, 176, 352]
+            .into_iter()
+            .map(|start_tick_index| TickArrayFacade {
+                start_tick_index,
+                ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+            })
+            .collect();
+
+        // A position in the range [-150; 150].
+        tick_arrays[1].ticks[13] = TickFacade {
+            initialized: true,
+            liquidity_net: LIQUIDITY as i128,
+            liquidity_gross: LIQUIDITY,
+            ..TickFacade::default()
+        };
+        tick_arrays[2].ticks[75] = TickFacade {
+            initialized: true,
+            liquidity_net: -(LIQUIDITY as i128),
+            liquidity_gross: LIQUIDITY,
+            ..TickFacade::default()
+        };
+        // A to B orders at tick 200 and B to A orders at tick -200.
+        tick_arrays[3].ticks[12] = TickFacade {
+            initialized: true,
+            open_orders_input: 100_000,
+            part_filled_orders_remaining_input: 50_000,
+            ..TickFacade::default()
+        };
+        tick_arrays[0].ticks[76] = TickFacade {
+            initialized: true,
+            open_orders_input: 200_000,
+            ..TickFacade::default()
+        };
+        tick_arrays
+    }
+
+    fn test_distribution() -> LiquidityDistribution {
+        let tick_sequence = TickArraySequence::new(test_tick_arrays(), 2).unwrap();
+        get_liquidity_distribution(&test_fusion_pool(), &tick_sequence, MIN_SQRT_PRICE, MAX_SQRT_PRICE).unwrap()
+    }
+
+    #[test]
+    fn test_distribution_ticks() {
+        let distribution = test_distribution();
+        let ticks: Vec<(i32, u128, u64, bool)> = distribution
+            .ticks
+            .iter()
+            .map(|tick| (tick.tick_index, tick.active_liquidity, tick.limit_order_input, tick.limit_order_a_to_b))
+            .collect();
+        assert_eq!(
+            ticks,
+            vec![
+                (-200, 0, 200_000, false),
+                (-150, LIQUIDITY, 0, false),
+                (150, 0, 0, true),
+                (200, 0, 150_000, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distribution_depth() {
+        let distribution = test_distribution();
+        let sqrt_price = 1u128 << 64;
+
+        // Current price, ticks 150 and 200, the end of the tick sequence.
+        assert_eq!(distribution.ask_depth.len(), 4);
+        let ask = distribution.ask_depth[1];
+        assert_eq!(ask.sqrt_price, tick_index_to_sqrt_price(150));
+        assert_eq!(ask.liquidity, 0);
+        assert_eq!(ask.concentrated_amount_a, get_amount_a_from_liquidity(LIQUIDITY, sqrt_price, ask.sqrt_price, false).unwrap() as u128);
+        assert_eq!(ask.concentrated_amount_b, get_amount_b_from_liquidity(LIQUIDITY, sqrt_price, ask.sqrt_price, true).unwrap() as u128);
+        let ask = distribution.ask_depth[2];
+        assert_eq!(ask.limit_amount_a, 150_000);
+        assert_eq!(ask.limit_amount_b, get_limit_order_output_amount(150_000, true, tick_index_to_sqrt_price(200), true).unwrap() as u128);
+        assert_eq!(distribution.ask_depth[3].sqrt_price, tick_index_to_sqrt_price(527));
+        assert_eq!(distribution.ask_depth[3].concentrated_amount_a, distribution.ask_depth[1].concentrated_amount_a);
+
+        // Current price, ticks -150 and -200, the start of the tick sequence.
+        assert_eq!(distribution.bid_depth.len(), 4);
+        let bid = distribution.bid_depth[1];
+        assert_eq!(bid.sqrt_price, tick_index_to_sqrt_price(-150));
+        assert_eq!(bid.concentrated_amount_b, get_amount_b_from_liquidity(LIQUIDITY, bid.sqrt_price, sqrt_price, false).unwrap() as u128);
+        let bid = distribution.bid_depth[2];
+        assert_eq!(bid.limit_amount_b, 200_000);
+        assert_eq!(bid.limit_amount_a, get_limit_order_output_amount(200_000, false, tick_index_to_sqrt_price(-200), true).unwrap() as u128);
+        assert_eq!(distribution.bid_depth[3].sqrt_price, tick_index_to_sqrt_price(-352));
+    }
+
+    #[test]
+    fn test_distribution_range() {
+        let fusion_pool = test_fusion_pool();
+        let tick_sequence = TickArraySequence::new(test_tick_arrays(), 2).unwrap();
+        let upper = tick_index_to_sqrt_price(100);
+        let distribution = get_liquidity_distribution(&fusion_pool, &tick_sequence, fusion_pool.sqrt_price, upper).unwrap();
+        assert!(distribution.ticks.is_empty());
+        assert_eq!(distribution.bid_depth.len(), 1);
+        assert_eq!(distribution.ask_depth.len(), 2);
+        assert_eq!(distribution.ask_depth[1].sqrt_price, upper);
+        assert_eq!(distribution.ask_depth[1].liquidity, LIQUIDITY);
+
+        assert_eq!(get_liquidity_distribution(&fusion_pool, &tick_sequence, upper, MAX_SQRT_PRICE), Err(INVALID_RANGE_BOUNDS));
+        assert_eq!(
+            get_liquidity_depth_at_sqrt_price(&distribution, tick_index_to_sqrt_price(102)),
+            Err(SQRT_PRICE_OUT_OF_DISTRIBUTION_RANGE)
+        );
+    }
+
+    #[test]
+    fn test_depth_matches_swap() {
+        let distribution = test_distribution();
+        for target_tick_index in [-220, -200, -170, -50, 90, 150, 180, 200, 220] {
+            let target_sqrt_price = tick_index_to_sqrt_price(target_tick_index);
+            let quote = get_liquidity_depth_at_sqrt_price(&distribution, target_sqrt_price).unwrap();
+            let tick_sequence = TickArraySequence::new(test_tick_arrays(), 2).unwrap();
+            let swap = compute_swap(u64::MAX / 2, target_sqrt_price, test_fusion_pool(), tick_sequence, quote.a_to_b, true).unwrap();
+            let (swap_in, swap_out) = if quote.a_to_b {
+                (swap.token_a, swap.token_b)
+            } else {
+                (swap.token_b, swap.token_a)
+            };
+
+            assert_eq!(swap.next_sqrt_price, target_sqrt_price);
+            // The swap fills the limit orders on the target tick before it stops there, the distribution does not.
+            if target_tick_index.abs() != 200 {
+                assert!((quote.amount_in + quote.fee_amount).abs_diff(swap_in as u128) <= 2);
+                assert!(quote.amount_out.abs_diff(swap_out as u128) <= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_depth_for_price_change() {
+        let distribution = test_distribution();
+        let zero = get_liquidity_depth_for_price_change(&distribution, 0).unwrap();
+        assert_eq!((zero.amount_in, zero.amount_out, zero.fee_amount), (0, 0, 0));
+
+        let up = get_liquidity_depth_for_price_change(&distribution, 100).unwrap();
+        assert!(!up.a_to_b);
+        assert_eq!(up.amount_out, get_amount_a_from_liquidity(LIQUIDITY, 1 << 64, up.sqrt_price, false).unwrap() as u128);
+
+        let down = get_liquidity_depth_for_price_change(&distribution, -100).unwrap();
+        assert!(down.a_to_b);
+        assert_eq!(down.amount_out, get_amount_b_from_liquidity(LIQUIDITY, down.sqrt_price, 1 << 64, false).unwrap() as u128);
+
+        assert_eq!(get_liquidity_depth_for_price_change(&distribution, -10_000), Err(SQRT_PRICE_OUT_OF_BOUNDS));
+        assert_eq!(get_liquidity_depth_for_price_change(&distribution, 1_000_000), Err(SQRT_PRICE_OUT_OF_DISTRIBUTION_RANGE));
+    }
+
+    #[test]
+    fn test_limit_order_input_overflow() {
+        let tick = TickFacade {
+            open_orders_input: u64::MAX,
+            part_filled_orders_remaining_input: 1,
+            ..TickFacade::default()
+        };
+        assert_eq!(get_limit_order_input(&tick), Err(ARITHMETIC_OVERFLOW));
+    }
+
+    #[test]
+    fn test_full_range_segment_amounts() {
+        let liquidity: u128 = 1 << 80;
+        assert_eq!(get_amount_a_from_liquidity(liquidity, MIN_SQRT_PRICE, MAX_SQRT_PRICE, true), Err(AMOUNT_EXCEEDS_MAX_U64));
+        assert_eq!(get_amount_b_from_liquidity(liquidity, MIN_SQRT_PRICE, MAX_SQRT_PRICE, false), Err(AMOUNT_EXCEEDS_MAX_U64));
+
+        let (amount_a, amount_b) = get_segment_amounts(liquidity, MAX_SQRT_PRICE, MIN_SQRT_PRICE, true).unwrap();
+        assert!(amount_a > u64::MAX as u128);
+        assert!(amount_b > u64::MAX as u128);
+        assert_eq!(amount_b, (MAX_SQRT_PRICE - MIN_SQRT_PRICE) << 16);
+        assert_eq!(get_segment_amounts(liquidity, MIN_SQRT_PRICE, MAX_SQRT_PRICE, false).unwrap().1, amount_b);
+
+        assert_eq!(get_segment_amounts(u128::MAX, MIN_SQRT_PRICE, MAX_SQRT_PRICE, true), Err(ARITHMETIC_OVERFLOW));
+    }
+}
@@ -10,11 +10,15 @@
 mod fees;
 mod limit_order;
 mod liquidity;
+mod liquidity_distribution;
 mod order_book;
+mod position;
 mod swap;
 
 pub use fees::*;
 pub use limit_order::*;
 pub use liquidity::*;
+pub use liquidity_distribution::*;
 pub use order_book::*;
+pub use position::*;
 pub use swap::*;
@@ -7,9 +7,12 @@
 
 use crate::quote::get_next_liquidity;
 use crate::{
-    get_limit_order_output_amount, price_to_sqrt_price, sqrt_price_to_price, tick_index_to_sqrt_price, CoreError, FusionPoolFacade,
-    TickArraySequence, MAX_SQRT_PRICE, MIN_SQRT_PRICE,
+    get_limit_order_output_amount, price_to_sqrt_price, sqrt_price_to_price, tick_index_to_sqrt_price, try_get_amount_delta_a,
+    try_get_amount_delta_b, try_reverse_apply_swap_fee, CoreError, FusionPoolFacade, TickArraySequence, ARITHMETIC_OVERFLOW, INVALID_ORDER_BOOK_STEP,
+    MAX_ORDER_BOOK_ENTRIES, MAX_SQRT_PRICE, MAX_TICK_INDEX, MIN_SQRT_PRICE, MIN_TICK_INDEX, TOO_MANY_ORDER_BOOK_ENTRIES,
 };
+use alloc::{vec, vec::Vec};
+use libm::{ceil, floor};
 
 #[derive(Debug)]
 pub struct OrderBookEntry {
@@ -51,8 +54,12 @@ pub fn get_order_book_side(
     decimals_b: u8,
 ) -> Result<Vec<OrderBookEntry>, CoreError> {
     let price_step_abs = price_step.abs();
-    assert!(price_step_abs >= 0.0000000000001, "price_step is too small");
-    assert!(max_num_entries <= 100, "the maximum allowed number of entries is too large");
+    if price_step_abs.is_nan() || price_step_abs < 0.0000000000001 {
+        return Err(INVALID_ORDER_BOOK_STEP);
+    }
+    if max_num_entries > MAX_ORDER_BOOK_ENTRIES {
+        return Err(TOO_MANY_ORDER_BOOK_ENTRIES);
+    }
 
     // a_to_b is false (ASK side) if the price_step is positive and not inverted.
     let a_to_b = (price_step < 0.0) != invert_price;
@@ -63,9 +70,9 @@ pub fn get_order_book_side(
     }
 
     let mut next_order_book_price = if price_step > 0.0 {
-        (current_price / price_step_abs).floor() * price_step_abs
+        floor(current_price / price_step_abs) * price_step_abs
     } else {
-        (current_price / price_step_abs).ceil() * price_step_abs
+        ceil(current_price / price_step_abs) * price_step_abs
     };
 
     let mut current_sqrt_price = fusion_pool.sqrt_price;
@@ -99,7 +106,7 @@ pub fn get_order_book_side(
         ))
         .clamp(MIN_SQRT_PRICE, MAX_SQRT_PRICE);
 
-        order_book_entries.push(OrderBookEntry {
+        let mut book_entry = OrderBookEntry {
             concentrated_amount: 0,
             concentrated_amount_quote: 0,
             concentrated_total,
@@ -110,9 +117,7 @@ pub fn get_order_book_side(
             limit_total_quote,
             price: next_order_book_price,
             ask_side: !a_to_b,
-        });
-
-        let book_entry: &mut OrderBookEntry = order_book_entries.last_mut().unwrap();
+        };
 
         while current_sqrt_price != next_order_book_sqrt_price {
             let next_tick_result = if a_to_b {
@@ -156,20 +161,20 @@ pub fn get_order_book_side(
             // Move to the next tick
             if current_sqrt_price == next_tick_sqrt_price {
                 if let Some(tick) = next_tick {
-                    let swap_in = tick.open_orders_input + tick.part_filled_orders_remaining_input;
+                    let swap_in = checked_add(tick.open_orders_input, tick.part_filled_orders_remaining_input)?;
                     let swap_out = if swap_in > 0 {
                         get_limit_order_output_amount(swap_in, !a_to_b, current_sqrt_price, false)?
                     } else {
                         0
                     };
 
-                    book_entry.limit_amount += swap_in;
-                    book_entry.limit_total += swap_in;
-                    limit_total += swap_in;
+                    book_entry.limit_amount = checked_add(book_entry.limit_amount, swap_in)?;
+                    book_entry.limit_total = checked_add(book_entry.limit_total, swap_in)?;
+                    limit_total = checked_add(limit_total, swap_in)?;
 
-                    book_entry.limit_amount_quote += swap_out;
-                    book_entry.limit_total_quote += swap_out;
-                    limit_total_quote += swap_out;
+                    book_entry.limit_amount_quote = checked_add(book_entry.limit_amount_quote, swap_out)?;
+                    book_entry.limit_total_quote = checked_add(book_entry.limit_total_quote, swap_out)?;
+                    limit_total_quote = checked_add(limit_total_quote, swap_out)?;
                 }
 
                 current_liquidity = get_next_liquidity(current_liquidity, next_tick, a_to_b);
@@ -177,10 +182,196 @@ pub fn get_order_book_side(
             }
         }
 
+        order_book_entries.push(book_entry);
         current_price = next_order_book_price;
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TickOrderBookEntry {
+    pub concentrated_amount: u64,
+    pub concentrated_amount_quote: u64,
+    pub concentrated_total: u64,
+    pub concentrated_total_quote: u64,
+    pub limit_amount: u64,
+    pub limit_amount_quote: u64,
+    pub limit_total: u64,
+    pub limit_total_quote: u64,
+    /// The tick index of the entry boundary farthest from the current pool price.
+    pub tick_index: i32,
+    /// The sqrt price of the entry boundary farthest from the current pool price.
+    pub sqrt_price: u128,
+    /// True for the ASK side of an order book and false for the BID one.
+    /// ASK-side liquidity is denominated in token A. Quote amounts indicate how much of token B you need to spend to purchase the available liquidity.
+    /// BID-side liquidity is denominated in token B. Quote amounts indicate how much of token A you need to spend to purchase the available liquidity.
+    pub ask_side: bool,
+}
+
+/// Calculate order book entries bucketed by ticks.
+///
+/// Amounts are computed with the same integer math as swap quotes: liquidity amounts are rounded down and
+/// quote amounts are rounded up. Entry boundaries are multiples of `tick_step`. The order book is truncated
+/// if the end of the tick sequence is reached.
+///
+/// # Parameters
+/// - `fusion_pool`: The fusion_pool state
+/// - `tick_sequence`: The tick sequence
+/// - `ask_side`: True for the ASK side of an order book and false for the BID one.
+/// - `tick_step` - The number of ticks of an order book entry.
+/// - `max_num_entries` - The maximum number of entries.
+/// - `include_fees` - Include swap fees in the quote amounts.
+///
+/// # Returns
+/// - Order book entries for one side of the order book.
+pub fn get_order_book_side_by_ticks(
+    fusion_pool: &FusionPoolFacade,
+    tick_sequence: &TickArraySequence,
+    ask_side: bool,
+    tick_step: u32,
+    max_num_entries: u32,
+    include_fees: bool,
+) -> Result<Vec<TickOrderBookEntry>, CoreError> {
+    if tick_step == 0 || tick_step > (MAX_TICK_INDEX - MIN_TICK_INDEX) as u32 {
+        return Err(INVALID_ORDER_BOOK_STEP);
+    }
+    if max_num_entries > MAX_ORDER_BOOK_ENTRIES {
+        return Err(TOO_MANY_ORDER_BOOK_ENTRIES);
+    }
+
+    let tick_step = tick_step as i32;
+    // The ASK side is filled by B to A swaps.
+    let a_to_b = !ask_side;
+
+    // The first entry boundary strictly beyond the current price.
+    let mut next_entry_tick_index = if a_to_b {
+        let tick_index = fusion_pool.tick_current_index.div_euclid(tick_step) * tick_step;
+        if tick_index_to_sqrt_price(tick_index) == fusion_pool.sqrt_price {
+            tick_index - tick_step
+        } else {
+            tick_index
+        }
+    } else {
+        fusion_pool.tick_current_index.div_euclid(tick_step) * tick_step + tick_step
+    };
+
+    let mut current_sqrt_price = fusion_pool.sqrt_price;
+    let mut current_tick_index = fusion_pool.tick_current_index;
+    let mut current_liquidity = fusion_pool.liquidity;
+
+    let mut concentrated_total = 0u64;
+    let mut concentrated_total_quote = 0u64;
+    let mut limit_total = 0u64;
+    let mut limit_total_quote = 0u64;
+    let mut order_book_entries: Vec<TickOrderBookEntry> = vec![];
+
+    let mut end_of_sequence = false;
+
+    let sqrt_price_bound = if a_to_b { MIN_SQRT_PRICE } else { MAX_SQRT_PRICE };
+
+    while order_book_entries.len() < max_num_entries as usize && current_sqrt_price != sqrt_price_bound {
+        let entry_tick_index = next_entry_tick_index.clamp(MIN_TICK_INDEX, MAX_TICK_INDEX);
+        let entry_sqrt_price = tick_index_to_sqrt_price(entry_tick_index);
+        next_entry_tick_index += if a_to_b { -tick_step } else { tick_step };
+
+        let mut entry = TickOrderBookEntry {
+            concentrated_amount: 0,
+            concentrated_amount_quote: 0,
+            concentrated_total,
+            concentrated_total_quote,
+            limit_amount: 0,
+            limit_amount_quote: 0,
+            limit_total,
+            limit_total_quote,
+            tick_index: entry_tick_index,
+            sqrt_price: entry_sqrt_price,
+            ask_side,
+        };
+
+        while current_sqrt_price != entry_sqrt_price {
+            let next_tick_result = if a_to_b {
+                tick_sequence.prev_initialized_tick(current_tick_index)
+            } else {
+                tick_sequence.next_initialized_tick(current_tick_index)
+            };
+
+            let (next_tick, next_tick_index) = match next_tick_result {
+                Ok(r) => r,
+                Err(_) => {
+                    end_of_sequence = true;
+                    break;
+                }
+            };
+
+            let next_tick_sqrt_price = tick_index_to_sqrt_price(next_tick_index);
+            let next_sqrt_price = if a_to_b {
+                entry_sqrt_price.max(next_tick_sqrt_price)
+            } else {
+                entry_sqrt_price.min(next_tick_sqrt_price)
+            };
+
+            // Liquidity token is B if a_to_b = true, A otherwise.
+            let (amount, mut amount_quote) = if a_to_b {
+                (
+                    try_get_amount_delta_b(current_sqrt_price, next_sqrt_price, current_liquidity, false)?,
+                    try_get_amount_delta_a(current_sqrt_price, next_sqrt_price, current_liquidity, true)?,
+                )
+            } else {
+                (
+                    try_get_amount_delta_a(current_sqrt_price, next_sqrt_price, current_liquidity, false)?,
+                    try_get_amount_delta_b(current_sqrt_price, next_sqrt_price, current_liquidity, true)?,
+                )
+            };
+            if include_fees {
+                amount_quote = try_reverse_apply_swap_fee(amount_quote, fusion_pool.fee_rate)?;
+            }
+
+            entry.concentrated_amount = checked_add(entry.concentrated_amount, amount)?;
+            entry.concentrated_amount_quote = checked_add(entry.concentrated_amount_quote, amount_quote)?;
+            concentrated_total = checked_add(concentrated_total, amount)?;
+            concentrated_total_quote = checked_add(concentrated_total_quote, amount_quote)?;
+
+            current_sqrt_price = next_sqrt_price;
+
+            // Move to the next tick
+            if current_sqrt_price == next_tick_sqrt_price {
+                if let Some(tick) = next_tick {
+                    let swap_out = checked_add(tick.open_orders_input, tick.part_filled_orders_remaining_input)?;
+                    if swap_out > 0 {
+                        let mut swap_in = get_limit_order_output_amount(swap_out, !a_to_b, current_sqrt_price, true)?;
+                        if include_fees {
+                            swap_in = try_reverse_apply_swap_fee(swap_in, fusion_pool.fee_rate)?;
+                        }
+
+                        entry.limit_amount = checked_add(entry.limit_amount, swap_out)?;
+                        entry.limit_amount_quote = checked_add(entry.limit_amount_quote, swap_in)?;
+                        limit_total = checked_add(limit_total, swap_out)?;
+                        limit_total_quote = checked_add(limit_total_quote, swap_in)?;
+                    }
+                }
+
+                current_liquidity = get_next_liquidity(current_liquidity, next_tick, a_to_b);
+                current_tick_index = if a_to_b { next_tick_index - 1 } else { next_tick_index }
+            }
+        }
+
+        if end_of_sequence {
+            break;
+        }
+
+        entry.concentrated_total = concentrated_total;
+        entry.concentrated_total_quote = concentrated_total_quote;
+        entry.limit_total = limit_total;
+        entry.limit_total_quote = limit_total_quote;
+        order_book_entries.push(entry);
+    }
+
+    Ok(order_book_entries)
+}
+
+fn checked_add(a: u64, b: u64) -> Result<u64, CoreError> {
+    a.checked_add(b).ok_or(ARITHMETIC_OVERFLOW)
+}
+
 const Q64_RESOLUTION: f64 = 18446744073709551616.0;
 
 pub fn try_get_amount_delta_a_and_b(sqrt_price_1_x64: u128, sqrt_price_2_x64: u128, liquidity: u128) -> Result<(u64, u64), CoreError> {
@@ -211,8 +402,10 @@ pub fn try_get_amount_delta_a_and_b(sqrt_price_1_x64: u128, sqrt_price_2_x64: u1
 #[cfg(all(test, not(feature = "wasm")))]
 mod order_book_tests {
     use crate::{
-        get_order_book_side, increase_liquidity_quote_a, increase_liquidity_quote_b, price_to_sqrt_price, sqrt_price_to_tick_index, FusionPoolFacade,
-        TickArrayFacade, TickArraySequence, TickFacade, TICK_ARRAY_SIZE,
+        compute_swap, get_limit_order_output_amount, get_order_book_side, get_order_book_side_by_ticks, increase_liquidity_quote_a,
+        increase_liquidity_quote_b, price_to_sqrt_price, sqrt_price_to_tick_index, tick_index_to_sqrt_price, try_reverse_apply_swap_fee,
+        FusionPoolFacade, TickArrayFacade, TickArraySequence, TickFacade, ARITHMETIC_OVERFLOW, INVALID_ORDER_BOOK_STEP, TICK_ARRAY_SIZE,
+        TOO_MANY_ORDER_BOOK_ENTRIES,
     };
 
     fn test_fusion_pool(sqrt_price: u128) -> FusionPoolFacade {
@@ -781,6 +974,148 @@ mod order_book_tests {
         assert_eq!(order_book[7].concentrated_amount_quote, 48);
     }
 
+    fn test_tick_order_book_sequence(fusion_pool: &FusionPoolFacade) -> TickArraySequence {
+        let mut tick_arrays = test_tick_arrays();
+        // A position in the range [-120; 120].
+        tick_arrays[1].ticks[28].liquidity_net = fusion_pool.liquidity as i128;
+        tick_arrays[1].ticks[28].initialized = true;
+        tick_arrays[2].ticks[60].liquidity_net = -(fusion_pool.liquidity as i128);
+        tick_arrays[2].ticks[60].initialized = true;
+        // Limit orders at ticks 180 and -180.
+        tick_arrays[3].ticks[2].open_orders_input = 100_000;
+        tick_arrays[3].ticks[2].initialized = true;
+        tick_arrays[0].ticks[86].open_orders_input = 100_000;
+        tick_arrays[0].ticks[86].initialized = true;
+        TickArraySequence::new(tick_arrays, fusion_pool.tick_spacing).unwrap()
+    }
+
+    fn test_tick_order_book_pool() -> FusionPoolFacade {
+        FusionPoolFacade {
+            liquidity: 1_000_000_000,
+            fee_rate: 3000,
+            ..test_fusion_pool(1 << 64)
+        }
+    }
+
+    #[test]
+    fn test_tick_order_book_matches_swap() {
+        let fusion_pool = test_tick_order_book_pool();
+        let tick_sequence = test_tick_order_book_sequence(&fusion_pool);
+
+        for ask_side in [true, false] {
+            let order_book = get_order_book_side_by_ticks(&fusion_pool, &tick_sequence, ask_side, 60, 4, true).unwrap();
+            let tick_indexes: Vec<i32> = order_book.iter().map(|entry| entry.tick_index).collect();
+            assert_eq!(
+                tick_indexes,
+                if ask_side {
+                    vec![60, 120, 180, 240]
+                } else {
+                    vec![-60, -120, -180, -240]
+                }
+            );
+
+            for (i, entry) in order_book.iter().enumerate() {
+                assert_eq!(entry.ask_side, ask_side);
+                assert_eq!(entry.sqrt_price, tick_index_to_sqrt_price(entry.tick_index));
+
+                let swap = compute_swap(u64::MAX / 2, entry.sqrt_price, fusion_pool, tick_sequence.clone(), !ask_side, true).unwrap();
+                let (swap_in, swap_out) = if ask_side {
+                    (swap.token_b, swap.token_a)
+                } else {
+                    (swap.token_a, swap.token_b)
+                };
+
+                // The first entry is a single swap step. The other ones are split differently from the swap steps.
+                let tolerance = if i == 0 { 0 } else { 2 };
+                assert!((entry.concentrated_total_quote + entry.limit_total_quote).abs_diff(swap_in) <= tolerance);
+                assert!((entry.concentrated_total + entry.limit_total).abs_diff(swap_out) <= tolerance);
+            }
+
+            assert_eq!(order_book[0].concentrated_amount, order_book[0].concentrated_total);
+            assert_eq!(order_book[2].limit_amount, 100_000);
+            assert_eq!(order_book[3].concentrated_amount, 0);
+            assert_eq!(order_book[3].limit_total, 100_000);
+        }
+    }
+
+    #[test]
+    fn test_tick_order_book_fees() {
+        let fusion_pool = test_tick_order_book_pool();
+        let tick_sequence = test_tick_order_book_sequence(&fusion_pool);
+
+        let with_fees = get_order_book_side_by_ticks(&fusion_pool, &tick_sequence, true, 60, 3, true).unwrap();
+        let without_fees = get_order_book_side_by_ticks(&fusion_pool, &tick_sequence, true, 60, 3, false).unwrap();
+
+        for (with_fees, without_fees) in with_fees.iter().zip(without_fees.iter()) {
+            assert_eq!(with_fees.concentrated_amount, without_fees.concentrated_amount);
+            assert_eq!(with_fees.limit_amount, without_fees.limit_amount);
+            assert_eq!(
+                with_fees.concentrated_amount_quote,
+                try_reverse_apply_swap_fee(without_fees.concentrated_amount_quote, fusion_pool.fee_rate).unwrap()
+            );
+        }
+        assert_eq!(
+            with_fees[2].limit_amount_quote,
+            try_reverse_apply_swap_fee(without_fees[2].limit_amount_quote, fusion_pool.fee_rate).unwrap()
+        );
+        assert_eq!(
+            without_fees[2].limit_amount_quote,
+            get_limit_order_output_amount(100_000, true, tick_index_to_sqrt_price(180), true).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tick_order_book_bucket_alignment() {
+        let fusion_pool = FusionPoolFacade {
+            liquidity: 1_000_000_000,
+            ..test_fusion_pool(tick_index_to_sqrt_price(30) + 1)
+        };
+        let tick_sequence = test_tick_order_book_sequence(&fusion_pool);
+
+        let asks = get_order_book_side_by_ticks(&fusion_pool, &tick_sequence, true, 60, 1, false).unwrap();
+        assert_eq!(asks[0].tick_index, 60);
+        let bids = get_order_book_side_by_ticks(&fusion_pool, &tick_sequence, false, 60, 1, false).unwrap();
+        assert_eq!(bids[0].tick_index, 0);
+    }
+
+    #[test]
+    fn test_tick_order_book_truncated_at_sequence_end() {
+        let fusion_pool = test_tick_order_book_pool();
+        let tick_sequence = test_tick_order_book_sequence(&fusion_pool);
+
+        // The sequence ends at tick 527.
+        let asks = get_order_book_side_by_ticks(&fusion_pool, &tick_sequence, true, 200, 10, false).unwrap();
+        assert_eq!(asks.len(), 2);
+        // The sequence starts at tick -352.
+        let bids = get_order_book_side_by_ticks(&fusion_pool, &tick_sequence, false, 200, 10, false).unwrap();
+        assert_eq!(bids.len(), 1);
+    }
+
+    #[test]
+    fn test_order_book_invalid_arguments() {
+        let fusion_pool = test_tick_order_book_pool();
+        let tick_sequence = test_tick_order_book_sequence(&fusion_pool);
+
+        assert_eq!(get_order_book_side_by_ticks(&fusion_pool, &tick_sequence, true, 0, 10, false), Err(INVALID_ORDER_BOOK_STEP));
+        assert_eq!(get_order_book_side_by_ticks(&fusion_pool, &tick_sequence, true, 60, 101, false), Err(TOO_MANY_ORDER_BOOK_ENTRIES));
+        assert_eq!(get_order_book_side(&fusion_pool, &tick_sequence, 0.0, 10, false, 6, 6).unwrap_err(), INVALID_ORDER_BOOK_STEP);
+        assert_eq!(get_order_book_side(&fusion_pool, &tick_sequence, 0.01, 101, false, 6, 6).unwrap_err(), TOO_MANY_ORDER_BOOK_ENTRIES);
+    }
+
+    #[test]
+    fn test_order_book_limit_order_input_overflow() {
+        let fusion_pool = test_tick_order_book_pool();
+        let mut tick_arrays = test_tick_arrays();
+        // Limit orders at tick 180 whose total input doesn't fit in a u64.
+        tick_arrays[3].ticks[2].open_orders_input = u64::MAX;
+        tick_arrays[3].ticks[2].part_filled_orders_remaining_input = 1;
+        tick_arrays[3].ticks[2].initialized = true;
+        let tick_sequence = TickArraySequence::new(tick_arrays, fusion_pool.tick_spacing).unwrap();
+
+        assert_eq!(get_order_book_side_by_ticks(&fusion_pool, &tick_sequence, true, 60, 4, false), Err(ARITHMETIC_OVERFLOW));
+        assert_eq!(get_order_book_side(&fusion_pool, &tick_sequence, 0.5, 4, false, 6, 6).unwrap_err(), ARITHMETIC_OVERFLOW);
+    }
+
     /*
     fn test_large_tick_arrays_with_initialized_ticks() -> Vec<TickArrayFacade> {
         let mut tick_arrays: Vec<TickArrayFacade> = vec![];
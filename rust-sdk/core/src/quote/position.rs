@@ -0,0 +1,287 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+#[cfg(feature = "wasm")]
+use fusionamm_macros::wasm_expose;
+
+use crate::quote::fees::get_fee_growth_inside;
+use crate::{
+    collect_fees_quote, get_amounts_from_liquidity, tick_index_to_sqrt_price, CoreError, FeeGrowthSnapshot, FusionPoolFacade, PositionAnalytics,
+    PositionFacade, TickFacade, TransferFee, ARITHMETIC_OVERFLOW, BPS_DENOMINATOR, INVALID_TIMESTAMP,
+};
+use ethnum::U256;
+
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// Calculate the analytics of a position: its token amounts and value, uncollected fees
+/// and impermanent loss versus holding the tokens deposited at the entry price.
+/// The entry amounts are derived from the current liquidity of the position.
+///
+/// # Parameters
+/// - `fusion_pool`: The fusion_pool state
+/// - `position`: The position state
+/// - `tick_lower`: The lower tick state
+/// - `tick_upper`: The upper tick state
+/// - `entry_sqrt_price`: The sqrt price at which the position was opened
+/// - `transfer_fee_a`: The transfer fee for token A
+/// - `transfer_fee_b`: The transfer fee for token B
+///
+/// # Returns
+/// - `PositionAnalytics`: The position analytics. Values are rounded down and don't include fees.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn position_analytics(
+    fusion_pool: FusionPoolFacade,
+    position: PositionFacade,
+    tick_lower: TickFacade,
+    tick_upper: TickFacade,
+    entry_sqrt_price: u128,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<PositionAnalytics, CoreError> {
+    let sqrt_price_lower = tick_index_to_sqrt_price(position.tick_lower_index);
+    let sqrt_price_upper = tick_index_to_sqrt_price(position.tick_upper_index);
+
+    let amounts = get_amounts_from_liquidity(position.liquidity, fusion_pool.sqrt_price, sqrt_price_lower, sqrt_price_upper, false)?;
+    let hold_amounts = get_amounts_from_liquidity(position.liquidity, entry_sqrt_price, sqrt_price_lower, sqrt_price_upper, false)?;
+    let fees = collect_fees_quote(fusion_pool, position, tick_lower, tick_upper, transfer_fee_a, transfer_fee_b)?;
+
+    let value_x128 = value_in_b_x128(amounts.a, amounts.b, fusion_pool.sqrt_price)?;
+    let hold_value_x128 = value_in_b_x128(hold_amounts.a, hold_amounts.b, fusion_pool.sqrt_price)?;
+
+    let impermanent_loss_bps = if hold_value_x128 == 0 {
+        0
+    } else if value_x128 >= hold_value_x128 {
+        ratio_bps(value_x128 - hold_value_x128, hold_value_x128)? as i64
+    } else {
+        -(ratio_bps(hold_value_x128 - value_x128, hold_value_x128)? as i64)
+    };
+
+    Ok(PositionAnalytics {
+        amount_a: amounts.a,
+        amount_b: amounts.b,
+        value_in_a: value_in_a(value_x128, fusion_pool.sqrt_price)?,
+        value_in_b: (value_x128 >> 128u32).as_u128(),
+        fee_owed_a: fees.fee_owed_a,
+        fee_owed_b: fees.fee_owed_b,
+        hold_amount_a: hold_amounts.a,
+        hold_amount_b: hold_amounts.b,
+        hold_value_in_b: (hold_value_x128 >> 128u32).as_u128(),
+        impermanent_loss_bps,
+    })
+}
+
+/// Take a snapshot of the fee growth inside the range of a position.
+/// Two snapshots of the same position can be used to estimate its fee yield.
+///
+/// # Parameters
+/// - `fusion_pool`: The fusion_pool state
+/// - `position`: The position state
+/// - `tick_lower`: The lower tick state
+/// - `tick_upper`: The upper tick state
+/// - `timestamp`: The unix timestamp of the pool and tick states
+///
+/// # Returns
+/// - `FeeGrowthSnapshot`: The fee growth snapshot
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn fee_growth_snapshot(
+    fusion_pool: FusionPoolFacade,
+    position: PositionFacade,
+    tick_lower: TickFacade,
+    tick_upper: TickFacade,
+    timestamp: u64,
+) -> FeeGrowthSnapshot {
+    let (fee_growth_inside_a, fee_growth_inside_b) = get_fee_growth_inside(fusion_pool, position, tick_lower, tick_upper);
+    FeeGrowthSnapshot {
+        fee_growth_inside_a,
+        fee_growth_inside_b,
+        timestamp,
+    }
+}
+
+/// Estimate the annualized fee yield of a position from two fee growth snapshots.
+/// The fees earned between the snapshots and the position are valued at the current pool price.
+///
+/// # Parameters
+/// - `fusion_pool`: The fusion_pool state
+/// - `position`: The position state
+/// - `snapshot_start`: The earlier fee growth snapshot
+/// - `snapshot_end`: The later fee growth snapshot
+///
+/// # Returns
+/// - `u64`: The annualized fee yield in basis points, rounded down
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn position_fee_apr_bps(
+    fusion_pool: FusionPoolFacade,
+    position: PositionFacade,
+    snapshot_start: FeeGrowthSnapshot,
+    snapshot_end: FeeGrowthSnapshot,
+) -> Result<u64, CoreError> {
+    if snapshot_end.timestamp <= snapshot_start.timestamp {
+        return Err(INVALID_TIMESTAMP);
+    }
+
+    let sqrt_price_lower = tick_index_to_sqrt_price(position.tick_lower_index);
+    let sqrt_price_upper = tick_index_to_sqrt_price(position.tick_upper_index);
+    let amounts = get_amounts_from_liquidity(position.liquidity, fusion_pool.sqrt_price, sqrt_price_lower, sqrt_price_upper, false)?;
+    let value_x128 = value_in_b_x128(amounts.a, amounts.b, fusion_pool.sqrt_price)?;
+    if value_x128 == 0 {
+        return Ok(0);
+    }
+
+    let fee_a = earned_fee(snapshot_start.fee_growth_inside_a, snapshot_end.fee_growth_inside_a, position.liquidity)?;
+    let fee_b = earned_fee(snapshot_start.fee_growth_inside_b, snapshot_end.fee_growth_inside_b, position.liquidity)?;
+    let fee_value_x128 = value_in_b_x128(fee_a, fee_b, fusion_pool.sqrt_price)?;
+
+    let elapsed = snapshot_end.timestamp - snapshot_start.timestamp;
+    let annualized_fee_value_x128 = fee_value_x128.checked_mul(SECONDS_PER_YEAR.into()).ok_or(ARITHMETIC_OVERFLOW)? / <U256>::from(elapsed);
+
+    ratio_bps(annualized_fee_value_x128, value_x128)?
+        .try_into()
+        .map_err(|_| ARITHMETIC_OVERFLOW)
+}
+
+// The fee earned by the liquidity between two fee growth checkpoints.
+fn earned_fee(fee_growth_start: u128, fee_growth_end: u128, liquidity: u128) -> Result<u64, CoreError> {
+    let fee: U256 = <U256>::from(fee_growth_end.wrapping_sub(fee_growth_start))
+        .checked_mul(liquidity.into())
+        .ok_or(ARITHMETIC_OVERFLOW)?
+        >> 64;
+    fee.try_into().map_err(|_| ARITHMETIC_OVERFLOW)
+}
+
+// The value of the token amounts in token B, as a X128 number.
+fn value_in_b_x128(amount_a: u64, amount_b: u64, sqrt_price: u128) -> Result<U256, CoreError> {
+    let price_x128 = <U256>::from(sqrt_price) * <U256>::from(sqrt_price);
+    price_x128
+        .checked_mul(amount_a.into())
+        .and_then(|value_a| value_a.checked_add(<U256>::from(amount_b) << 128))
+        .ok_or(ARITHMETIC_OVERFLOW)
+}
+
+// Converts a X128 value in token B to token A, rounded down.
+fn value_in_a(value_in_b_x128: U256, sqrt_price: u128) -> Result<u128, CoreError> {
+    let price_x128 = <U256>::from(sqrt_price) * <U256>::from(sqrt_price);
+    if price_x128 == 0 {
+        return Err(ARITHMETIC_OVERFLOW);
+    }
+    (value_in_b_x128 / price_x128).try_into().map_err(|_| ARITHMETIC_OVERFLOW)
+}
+
+// numerator / denominator in basis points, rounded down.
+fn ratio_bps(numerator: U256, denominator: U256) -> Result<u128, CoreError> {
+    let ratio: U256 = numerator.checked_mul(BPS_DENOMINATOR.into()).ok_or(ARITHMETIC_OVERFLOW)? / denominator;
+    ratio.try_into().map_err(|_| ARITHMETIC_OVERFLOW)
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::MIN_SQRT_PRICE;
+
+    fn test_fusion_pool(sqrt_price: u128) -> FusionPoolFacade {
+        FusionPoolFacade {
+            sqrt_price,
+            tick_current_index: crate::sqrt_price_to_tick_index(sqrt_price),
+            tick_spacing: 64,
+            ..FusionPoolFacade::default()
+        }
+    }
+
+    fn test_position() -> PositionFacade {
+        PositionFacade {
+            liquidity: 1_000_000_000,
+            tick_lower_index: -6400,
+            tick_upper_index: 6400,
+            ..PositionFacade::default()
+        }
+    }
+
+    #[test]
+    fn test_position_analytics_at_entry_price() {
+        let fusion_pool = test_fusion_pool(1 << 64);
+        let result = position_analytics(fusion_pool, test_position(), TickFacade::default(), TickFacade::default(), 1 << 64, None, None).unwrap();
+        let amounts =
+            get_amounts_from_liquidity(1_000_000_000, 1 << 64, tick_index_to_sqrt_price(-6400), tick_index_to_sqrt_price(6400), false).unwrap();
+        assert_eq!(result.amount_a, amounts.a);
+        assert_eq!(result.amount_b, amounts.b);
+        assert_eq!(result.value_in_a, (amounts.a + amounts.b) as u128);
+        assert_eq!(result.value_in_b, (amounts.a + amounts.b) as u128);
+        assert_eq!(result.hold_amount_a, amounts.a);
+        assert_eq!(result.hold_amount_b, amounts.b);
+        assert_eq!(result.hold_value_in_b, result.value_in_b);
+        assert_eq!(result.impermanent_loss_bps, 0);
+    }
+
+    #[test]
+    fn test_position_analytics_impermanent_loss() {
+        // The price rose by 50% since the position was opened.
+        let fusion_pool = test_fusion_pool(tick_index_to_sqrt_price(4055));
+        let result = position_analytics(fusion_pool, test_position(), TickFacade::default(), TickFacade::default(), 1 << 64, None, None).unwrap();
+        assert!(result.amount_a < result.hold_amount_a);
+        assert!(result.amount_b > result.hold_amount_b);
+        assert!(result.value_in_b < result.hold_value_in_b);
+        assert!(result.value_in_a < result.value_in_b);
+        // -7.38% for this range, a full range position would lose 2.02%.
+        assert_eq!(result.impermanent_loss_bps, -737);
+
+        // Above the range the position holds only token B.
+        let fusion_pool = test_fusion_pool(tick_index_to_sqrt_price(7000));
+        let result = position_analytics(fusion_pool, test_position(), TickFacade::default(), TickFacade::default(), 1 << 64, None, None).unwrap();
+        assert_eq!(result.amount_a, 0);
+        assert_eq!(result.value_in_b, result.amount_b as u128);
+    }
+
+    #[test]
+    fn test_position_analytics_fees() {
+        let fusion_pool = FusionPoolFacade {
+            fee_growth_global_a: 3 << 64,
+            fee_growth_global_b: 5 << 64,
+            ..test_fusion_pool(1 << 64)
+        };
+        let position = PositionFacade {
+            fee_owed_a: 10,
+            ..test_position()
+        };
+        let result = position_analytics(fusion_pool, position, TickFacade::default(), TickFacade::default(), 1 << 64, None, None).unwrap();
+        assert_eq!(result.fee_owed_a, 3_000_000_010);
+        assert_eq!(result.fee_owed_b, 5_000_000_000);
+    }
+
+    #[test]
+    fn test_position_fee_apr_bps() {
+        let fusion_pool = test_fusion_pool(1 << 64);
+        let position = PositionFacade {
+            liquidity: 1 << 40,
+            tick_lower_index: -443584,
+            tick_upper_index: 443584,
+            ..PositionFacade::default()
+        };
+        // A full range position at price 1 holds about `liquidity` of each token.
+        let snapshot_start = fee_growth_snapshot(fusion_pool, position, TickFacade::default(), TickFacade::default(), 1_700_000_000);
+        let fusion_pool_end = FusionPoolFacade {
+            fee_growth_global_a: 1 << 58,
+            fee_growth_global_b: 1 << 58,
+            ..fusion_pool
+        };
+        let snapshot_end = fee_growth_snapshot(fusion_pool_end, position, TickFacade::default(), TickFacade::default(), 1_700_000_000 + 86400);
+        assert_eq!(snapshot_end.fee_growth_inside_a, 1 << 58);
+
+        // Fees of 1/64 of each token per day.
+        let apr = position_fee_apr_bps(fusion_pool_end, position, snapshot_start, snapshot_end).unwrap();
+        assert_eq!(apr, 365 * BPS_DENOMINATOR as u64 / 64);
+
+        assert_eq!(position_fee_apr_bps(fusion_pool_end, position, snapshot_end, snapshot_start), Err(INVALID_TIMESTAMP));
+        assert_eq!(position_fee_apr_bps(fusion_pool_end, PositionFacade::default(), snapshot_start, snapshot_end), Ok(0));
+    }
+
+    #[test]
+    fn test_value_in_a_at_low_price() {
+        let value_x128 = value_in_b_x128(u64::MAX, u64::MAX, MIN_SQRT_PRICE).unwrap();
+        let value = value_in_a(value_x128, MIN_SQRT_PRICE).unwrap();
+        assert!(value > u64::MAX as u128 * (u64::MAX as u128 / 2));
+    }
+}
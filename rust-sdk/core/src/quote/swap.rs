@@ -9,13 +9,14 @@
 //
 
 use crate::{
-    get_limit_order_output_amount, sqrt_price_to_tick_index, tick_index_to_sqrt_price, try_apply_swap_fee, try_apply_transfer_fee,
-    try_get_amount_delta_a, try_get_amount_delta_b, try_get_max_amount_with_slippage_tolerance, try_get_min_amount_with_slippage_tolerance,
-    try_get_next_sqrt_price_from_a, try_get_next_sqrt_price_from_b, try_mul_div, try_reverse_apply_swap_fee, try_reverse_apply_transfer_fee,
-    CoreError, ExactInSwapQuote, ExactOutSwapQuote, FusionPoolFacade, TickArraySequence, TickArrays, TickFacade, TransferFee, AMOUNT_EXCEEDS_MAX_U64,
-    ARITHMETIC_OVERFLOW, FEE_RATE_MUL_VALUE, INVALID_SQRT_PRICE_LIMIT_DIRECTION, MAX_SQRT_PRICE, MIN_SQRT_PRICE, SQRT_PRICE_LIMIT_OUT_OF_BOUNDS,
-    ZERO_TRADABLE_AMOUNT,
+    get_limit_order_output_amount, get_tick_array_start_tick_index, sqrt_price_to_tick_index, sqrt_u256, tick_index_to_sqrt_price,
+    try_apply_swap_fee, try_apply_transfer_fee, try_get_amount_delta_a, try_get_amount_delta_b, try_get_max_amount_with_slippage_tolerance,
+    try_get_min_amount_with_slippage_tolerance, try_get_next_sqrt_price_from_a, try_get_next_sqrt_price_from_b, try_mul_div,
+    try_reverse_apply_swap_fee, try_reverse_apply_transfer_fee, CoreError, ExactInSwapQuote, ExactOutSwapQuote, FusionPoolFacade, TickArraySequence,
+    TickArrays, TickFacade, TransferFee, AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW, BPS_DENOMINATOR, FEE_RATE_MUL_VALUE,
+    INVALID_SQRT_PRICE_LIMIT_DIRECTION, MAX_SQRT_PRICE, MIN_SQRT_PRICE, SQRT_PRICE_LIMIT_OUT_OF_BOUNDS, ZERO_TRADABLE_AMOUNT,
 };
+use ethnum::U256;
 
 #[cfg(feature = "wasm")]
 use fusionamm_macros::wasm_expose;
@@ -32,7 +33,7 @@ use fusionamm_macros::wasm_expose;
 /// - `transfer_fee_b`: The transfer fee for token B.
 ///
 /// # Returns
-/// The exact input or output amount for the swap transaction.
+/// The input amount, the estimated and minimum output amounts and the execution breakdown of the swap.
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub fn swap_quote_by_input_token(
     token_in: u64,
@@ -42,6 +43,47 @@ pub fn swap_quote_by_input_token(
     tick_arrays: TickArrays,
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
+) -> Result<ExactInSwapQuote, CoreError> {
+    swap_quote_by_input_token_with_sqrt_price_limit(
+        token_in,
+        specified_token_a,
+        slippage_tolerance_bps,
+        0,
+        fusion_pool,
+        tick_arrays,
+        transfer_fee_a,
+        transfer_fee_b,
+    )
+}
+
+/// Computes the exact input or output amount for a swap transaction that stops at a price limit.
+///
+/// The swap is filled until either the input amount is exhausted or the price reaches `sqrt_price_limit`.
+/// If the limit is hit first, `token_in` of the returned quote is lower than the requested amount.
+///
+/// # Arguments
+/// - `token_in`: The input token amount.
+/// - `specified_token_a`: If `true`, the input token is token A. Otherwise, it is token B.
+/// - `slippage_tolerance`: The slippage tolerance in basis points.
+/// - `sqrt_price_limit`: The price limit represented as a square root. If set to `0`, the swap is not limited.
+/// - `fusion_pool`: The fusion_pool state.
+/// - `tick_arrays`: The tick arrays needed for the swap.
+/// - `transfer_fee_a`: The transfer fee for token A.
+/// - `transfer_fee_b`: The transfer fee for token B.
+///
+/// # Returns
+/// The quote for the part of the input that is swapped before the price limit is reached.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn swap_quote_by_input_token_with_sqrt_price_limit(
+    token_in: u64,
+    specified_token_a: bool,
+    slippage_tolerance_bps: u16,
+    sqrt_price_limit: u128,
+    fusion_pool: FusionPoolFacade,
+    tick_arrays: TickArrays,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
 ) -> Result<ExactInSwapQuote, CoreError> {
     let (transfer_fee_in, transfer_fee_out) = if specified_token_a {
         (transfer_fee_a, transfer_fee_b)
@@ -52,7 +94,7 @@ pub fn swap_quote_by_input_token(
 
     let tick_sequence = TickArraySequence::new(tick_arrays.into(), fusion_pool.tick_spacing)?;
 
-    let swap_result = compute_swap(token_in_after_fee.into(), 0, fusion_pool, tick_sequence, specified_token_a, true)?;
+    let swap_result = compute_swap(token_in_after_fee.into(), sqrt_price_limit, fusion_pool, tick_sequence, specified_token_a, true)?;
 
     let (token_in_after_fees, token_est_out_before_fee) = if specified_token_a {
         (swap_result.token_a, swap_result.token_b)
@@ -66,12 +108,24 @@ pub fn swap_quote_by_input_token(
 
     let token_min_out = try_get_min_amount_with_slippage_tolerance(token_est_out, slippage_tolerance_bps)?;
 
+    let execution = get_swap_execution(&fusion_pool, &swap_result, specified_token_a, token_in, token_est_out)?;
+
     Ok(ExactInSwapQuote {
         token_in,
         token_est_out,
         token_min_out,
         trade_fee: swap_result.fee_amount,
         next_sqrt_price: swap_result.next_sqrt_price,
+        spot_sqrt_price: fusion_pool.sqrt_price,
+        execution_sqrt_price: execution.execution_sqrt_price,
+        price_impact_bps: execution.price_impact_bps,
+        liquidity_amount_in: swap_result.liquidity_amount_in,
+        liquidity_amount_out: swap_result.liquidity_amount_out,
+        limit_order_amount_in: swap_result.limit_order_amount_in,
+        limit_order_amount_out: swap_result.limit_order_amount_out,
+        ticks_crossed: swap_result.ticks_crossed,
+        first_tick_array_start_index: execution.first_tick_array_start_index,
+        last_tick_array_start_index: execution.last_tick_array_start_index,
     })
 }
 
@@ -87,7 +141,7 @@ pub fn swap_quote_by_input_token(
 /// - `transfer_fee_b`: The transfer fee for token B.
 ///
 /// # Returns
-/// The exact input or output amount for the swap transaction.
+/// The output amount, the estimated and maximum input amounts and the execution breakdown of the swap.
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub fn swap_quote_by_output_token(
     token_out: u64,
@@ -97,6 +151,47 @@ pub fn swap_quote_by_output_token(
     tick_arrays: TickArrays,
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
+) -> Result<ExactOutSwapQuote, CoreError> {
+    swap_quote_by_output_token_with_sqrt_price_limit(
+        token_out,
+        specified_token_a,
+        slippage_tolerance_bps,
+        0,
+        fusion_pool,
+        tick_arrays,
+        transfer_fee_a,
+        transfer_fee_b,
+    )
+}
+
+/// Computes the exact input or output amount for a swap transaction that stops at a price limit.
+///
+/// The swap is filled until either the output amount is reached or the price reaches `sqrt_price_limit`.
+/// If the limit is hit first, `token_out` of the returned quote is lower than the requested amount.
+///
+/// # Arguments
+/// - `token_out`: The output token amount.
+/// - `specified_token_a`: If `true`, the output token is token A. Otherwise, it is token B.
+/// - `slippage_tolerance`: The slippage tolerance in basis points.
+/// - `sqrt_price_limit`: The price limit represented as a square root. If set to `0`, the swap is not limited.
+/// - `fusion_pool`: The fusion_pool state.
+/// - `tick_arrays`: The tick arrays needed for the swap.
+/// - `transfer_fee_a`: The transfer fee for token A.
+/// - `transfer_fee_b`: The transfer fee for token B.
+///
+/// # Returns
+/// The quote for the part of the output that is received before the price limit is reached.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn swap_quote_by_output_token_with_sqrt_price_limit(
+    token_out: u64,
+    specified_token_a: bool,
+    slippage_tolerance_bps: u16,
+    sqrt_price_limit: u128,
+    fusion_pool: FusionPoolFacade,
+    tick_arrays: TickArrays,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
 ) -> Result<ExactOutSwapQuote, CoreError> {
     let (transfer_fee_in, transfer_fee_out) = if specified_token_a {
         (transfer_fee_b, transfer_fee_a)
@@ -107,7 +202,7 @@ pub fn swap_quote_by_output_token(
 
     let tick_sequence = TickArraySequence::new(tick_arrays.into(), fusion_pool.tick_spacing)?;
 
-    let swap_result = compute_swap(token_out_before_fee.into(), 0, fusion_pool, tick_sequence, !specified_token_a, false)?;
+    let swap_result = compute_swap(token_out_before_fee.into(), sqrt_price_limit, fusion_pool, tick_sequence, !specified_token_a, false)?;
 
     let (token_out_before_fee, token_est_in_after_fee) = if specified_token_a {
         (swap_result.token_a, swap_result.token_b)
@@ -121,20 +216,38 @@ pub fn swap_quote_by_output_token(
 
     let token_max_in = try_get_max_amount_with_slippage_tolerance(token_est_in, slippage_tolerance_bps)?;
 
+    let execution = get_swap_execution(&fusion_pool, &swap_result, !specified_token_a, token_est_in, token_out)?;
+
     Ok(ExactOutSwapQuote {
         token_out,
         token_est_in,
         token_max_in,
         trade_fee: swap_result.fee_amount,
         next_sqrt_price: swap_result.next_sqrt_price,
+        spot_sqrt_price: fusion_pool.sqrt_price,
+        execution_sqrt_price: execution.execution_sqrt_price,
+        price_impact_bps: execution.price_impact_bps,
+        liquidity_amount_in: swap_result.liquidity_amount_in,
+        liquidity_amount_out: swap_result.liquidity_amount_out,
+        limit_order_amount_in: swap_result.limit_order_amount_in,
+        limit_order_amount_out: swap_result.limit_order_amount_out,
+        ticks_crossed: swap_result.ticks_crossed,
+        first_tick_array_start_index: execution.first_tick_array_start_index,
+        last_tick_array_start_index: execution.last_tick_array_start_index,
     })
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct SwapResult {
     pub token_a: u64,
     pub token_b: u64,
     pub fee_amount: u64,
     pub next_sqrt_price: u128,
+    pub liquidity_amount_in: u64,
+    pub liquidity_amount_out: u64,
+    pub limit_order_amount_in: u64,
+    pub limit_order_amount_out: u64,
+    pub ticks_crossed: u32,
 }
 
 /// Computes the amounts of tokens A and B based on the current FusionPool state and tick sequence.
@@ -165,6 +278,45 @@ pub fn compute_swap(
     a_to_b: bool,
     specified_input: bool,
 ) -> Result<SwapResult, CoreError> {
+    let (swap_result, _) = compute_swap_steps(token_amount, sqrt_price_limit, fusion_pool, tick_sequence, a_to_b, specified_input, |_| Ok(()))?;
+    Ok(swap_result)
+}
+
+/// A single step of a swap: the part filled by concentrated liquidity up to the next initialized tick
+/// and, if that tick is reached, the limit orders filled on it before it is crossed.
+pub(crate) struct SwapStep {
+    /// The liquidity used to fill the step.
+    pub liquidity: u128,
+    /// The swap fee paid to the liquidity providers and the protocol in the step.
+    pub fee_amount: u64,
+    /// The initialized tick reached and crossed at the end of the step.
+    pub crossed_tick: Option<CrossedTick>,
+}
+
+pub(crate) struct CrossedTick {
+    pub tick_index: i32,
+    pub limit_order_fill: LimitSwapComputation,
+}
+
+/// The pool state after a swap that is not part of `SwapResult`.
+pub(crate) struct SwapEndState {
+    pub tick_current_index: i32,
+    pub liquidity: u128,
+}
+
+/// Runs the swap loop of `compute_swap`, reporting each step to `on_step` as it is filled.
+pub(crate) fn compute_swap_steps<F>(
+    token_amount: u64,
+    sqrt_price_limit: u128,
+    fusion_pool: FusionPoolFacade,
+    tick_sequence: TickArraySequence,
+    a_to_b: bool,
+    specified_input: bool,
+    mut on_step: F,
+) -> Result<(SwapResult, SwapEndState), CoreError>
+where
+    F: FnMut(SwapStep) -> Result<(), CoreError>,
+{
     let sqrt_price_limit = if sqrt_price_limit == 0 {
         if a_to_b {
             MIN_SQRT_PRICE
@@ -193,6 +345,11 @@ pub fn compute_swap(
     let mut current_tick_index = fusion_pool.tick_current_index;
     let mut current_liquidity = fusion_pool.liquidity;
     let mut fee_amount = 0;
+    let mut liquidity_amount_in = 0u64;
+    let mut liquidity_amount_out = 0u64;
+    let mut limit_order_amount_in = 0u64;
+    let mut limit_order_amount_out = 0u64;
+    let mut ticks_crossed = 0u32;
 
     while amount_remaining > 0 && sqrt_price_limit != current_sqrt_price {
         let (next_tick, next_tick_index) = if a_to_b {
@@ -218,6 +375,8 @@ pub fn compute_swap(
         )?;
 
         fee_amount += step_quote.fee_amount;
+        liquidity_amount_in = liquidity_amount_in.checked_add(step_quote.amount_in).ok_or(ARITHMETIC_OVERFLOW)?;
+        liquidity_amount_out = liquidity_amount_out.checked_add(step_quote.amount_out).ok_or(ARITHMETIC_OVERFLOW)?;
 
         if specified_input {
             amount_remaining = amount_remaining
@@ -240,6 +399,15 @@ pub fn compute_swap(
                 fill_limit_orders(next_tick, next_tick_sqrt_price, a_to_b, specified_input, amount_remaining, fusion_pool.fee_rate)?;
 
             fee_amount += limit_swap_computation.fee_amount;
+            limit_order_amount_in = limit_order_amount_in
+                .checked_add(limit_swap_computation.amount_in)
+                .ok_or(ARITHMETIC_OVERFLOW)?;
+            limit_order_amount_out = limit_order_amount_out
+                .checked_add(limit_swap_computation.amount_out)
+                .ok_or(ARITHMETIC_OVERFLOW)?;
+            if next_tick.is_some() {
+                ticks_crossed += 1;
+            }
 
             if specified_input {
                 amount_remaining = amount_remaining
@@ -261,10 +429,27 @@ pub fn compute_swap(
                     .ok_or(ARITHMETIC_OVERFLOW)?;
             };
 
+            on_step(SwapStep {
+                liquidity: current_liquidity,
+                fee_amount: step_quote.fee_amount,
+                crossed_tick: next_tick.map(|_| CrossedTick {
+                    tick_index: next_tick_index,
+                    limit_order_fill: limit_swap_computation,
+                }),
+            })?;
+
             current_liquidity = get_next_liquidity(current_liquidity, next_tick, a_to_b);
             current_tick_index = if a_to_b { next_tick_index - 1 } else { next_tick_index }
-        } else if step_quote.next_sqrt_price != current_sqrt_price {
-            current_tick_index = sqrt_price_to_tick_index(step_quote.next_sqrt_price.into()).into();
+        } else {
+            on_step(SwapStep {
+                liquidity: current_liquidity,
+                fee_amount: step_quote.fee_amount,
+                crossed_tick: None,
+            })?;
+
+            if step_quote.next_sqrt_price != current_sqrt_price {
+                current_tick_index = sqrt_price_to_tick_index(step_quote.next_sqrt_price.into()).into();
+            }
         }
 
         current_sqrt_price = step_quote.next_sqrt_price;
@@ -275,12 +460,23 @@ pub fn compute_swap(
     let token_a = if a_to_b == specified_input { swapped_amount } else { amount_calculated };
     let token_b = if a_to_b == specified_input { amount_calculated } else { swapped_amount };
 
-    Ok(SwapResult {
-        token_a,
-        token_b,
-        fee_amount,
-        next_sqrt_price: current_sqrt_price,
-    })
+    Ok((
+        SwapResult {
+            token_a,
+            token_b,
+            fee_amount,
+            next_sqrt_price: current_sqrt_price,
+            liquidity_amount_in,
+            liquidity_amount_out,
+            limit_order_amount_in,
+            limit_order_amount_out,
+            ticks_crossed,
+        },
+        SwapEndState {
+            tick_current_index: current_tick_index,
+            liquidity: current_liquidity,
+        },
+    ))
 }
 
 pub(crate) fn get_next_liquidity(current_liquidity: u128, next_tick: Option<&TickFacade>, a_to_b: bool) -> u128 {
@@ -301,6 +497,50 @@ pub(crate) fn get_next_liquidity(current_liquidity: u128, next_tick: Option<&Tic
 
 // Private functions
 
+struct SwapExecution {
+    execution_sqrt_price: u128,
+    price_impact_bps: u32,
+    first_tick_array_start_index: i32,
+    last_tick_array_start_index: i32,
+}
+
+// The execution price is computed from the amounts the user pays and receives, so it includes the trade fee and transfer fees.
+fn get_swap_execution(
+    fusion_pool: &FusionPoolFacade,
+    swap_result: &SwapResult,
+    a_to_b: bool,
+    amount_in: u64,
+    amount_out: u64,
+) -> Result<SwapExecution, CoreError> {
+    let (amount_a, amount_b) = if a_to_b { (amount_in, amount_out) } else { (amount_out, amount_in) };
+
+    let (execution_sqrt_price, price_impact_bps) = if amount_a == 0 || amount_b == 0 {
+        (0, 0)
+    } else {
+        // Prices are compared in Q128.128 format to avoid rounding the execution price.
+        let spot_price_x128 = <U256>::from(fusion_pool.sqrt_price) * <U256>::from(fusion_pool.sqrt_price);
+        let execution_price_x128 = (<U256>::from(amount_b) << 128) / <U256>::from(amount_a);
+        let price_diff = spot_price_x128.abs_diff(execution_price_x128);
+        let price_impact_bps = price_diff * <U256>::from(BPS_DENOMINATOR) / spot_price_x128;
+        let execution_sqrt_price: u128 = sqrt_u256(execution_price_x128).try_into().map_err(|_| ARITHMETIC_OVERFLOW)?;
+        (execution_sqrt_price, price_impact_bps.try_into().unwrap_or(u32::MAX))
+    };
+
+    let first_tick_array_start_index = get_tick_array_start_tick_index(fusion_pool.tick_current_index, fusion_pool.tick_spacing);
+    let last_tick_array_start_index = if swap_result.next_sqrt_price == fusion_pool.sqrt_price {
+        first_tick_array_start_index
+    } else {
+        get_tick_array_start_tick_index(sqrt_price_to_tick_index(swap_result.next_sqrt_price), fusion_pool.tick_spacing)
+    };
+
+    Ok(SwapExecution {
+        execution_sqrt_price,
+        price_impact_bps,
+        first_tick_array_start_index,
+        last_tick_array_start_index,
+    })
+}
+
 #[derive(PartialEq, Debug, Default)]
 pub struct LimitSwapComputation {
     pub amount_in: u64,
@@ -686,4 +926,137 @@ mod tests {
         assert_eq!(result_3428.token_in, 3428);
         assert!(matches!(result_3429, Err(INVALID_TICK_ARRAY_SEQUENCE)));
     }
+
+    #[test]
+    fn test_exact_in_with_zero_sqrt_price_limit_matches_unlimited_quote() {
+        let limited =
+            swap_quote_by_input_token_with_sqrt_price_limit(1000, true, 1000, 0, test_fusion_pool(1 << 64, false), test_tick_arrays(), None, None)
+                .unwrap();
+        let unlimited = swap_quote_by_input_token(1000, true, 1000, test_fusion_pool(1 << 64, false), test_tick_arrays(), None, None).unwrap();
+        assert_eq!(limited, unlimited);
+    }
+
+    #[test]
+    fn test_exact_in_a_to_b_with_sqrt_price_limit() {
+        let sqrt_price_limit = tick_index_to_sqrt_price(-2);
+        let result = swap_quote_by_input_token_with_sqrt_price_limit(
+            1000,
+            true,
+            1000,
+            sqrt_price_limit,
+            test_fusion_pool(1 << 64, false),
+            test_tick_arrays(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.token_in, 28);
+        assert_eq!(result.token_est_out, 26);
+        assert_eq!(result.token_min_out, 23);
+        assert_eq!(result.trade_fee, 1);
+        assert_eq!(result.next_sqrt_price, sqrt_price_limit);
+    }
+
+    #[test]
+    fn test_exact_in_b_to_a_with_sqrt_price_limit() {
+        let sqrt_price_limit = tick_index_to_sqrt_price(2);
+        let result = swap_quote_by_input_token_with_sqrt_price_limit(
+            1000,
+            false,
+            1000,
+            sqrt_price_limit,
+            test_fusion_pool(1 << 64, false),
+            test_tick_arrays(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.token_in, 28);
+        assert_eq!(result.token_est_out, 26);
+        assert_eq!(result.token_min_out, 23);
+        assert_eq!(result.trade_fee, 1);
+        assert_eq!(result.next_sqrt_price, sqrt_price_limit);
+    }
+
+    #[test]
+    fn test_exact_out_a_to_b_with_sqrt_price_limit() {
+        let sqrt_price_limit = tick_index_to_sqrt_price(-2);
+        let result = swap_quote_by_output_token_with_sqrt_price_limit(
+            1000,
+            false,
+            1000,
+            sqrt_price_limit,
+            test_fusion_pool(1 << 64, false),
+            test_tick_arrays(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.token_out, 26);
+        assert_eq!(result.token_est_in, 28);
+        assert_eq!(result.token_max_in, 31);
+        assert_eq!(result.trade_fee, 1);
+        assert_eq!(result.next_sqrt_price, sqrt_price_limit);
+    }
+
+    #[test]
+    fn test_swap_quote_with_sqrt_price_limit_in_wrong_direction() {
+        let result = swap_quote_by_input_token_with_sqrt_price_limit(
+            1000,
+            true,
+            1000,
+            tick_index_to_sqrt_price(2),
+            test_fusion_pool(1 << 64, false),
+            test_tick_arrays(),
+            None,
+            None,
+        );
+        assert!(matches!(result, Err(INVALID_SQRT_PRICE_LIMIT_DIRECTION)));
+    }
+
+    #[test]
+    fn test_exact_in_a_to_b_execution_breakdown() {
+        let result = swap_quote_by_input_token(1000, true, 1000, test_fusion_pool(1 << 64, false), test_tick_arrays(), None, None).unwrap();
+        assert_eq!(result.spot_sqrt_price, 1 << 64);
+        assert_eq!(result.execution_sqrt_price, 17693495346235672107);
+        assert_eq!(result.price_impact_bps, 800);
+        assert_eq!(result.liquidity_amount_in, 962);
+        assert_eq!(result.liquidity_amount_out, 920);
+        assert_eq!(result.limit_order_amount_in, 0);
+        assert_eq!(result.limit_order_amount_out, 0);
+        assert_eq!(result.ticks_crossed, 39);
+        assert_eq!(result.first_tick_array_start_index, 0);
+        assert_eq!(result.last_tick_array_start_index, -176);
+    }
+
+    #[test]
+    fn test_exact_out_b_to_a_execution_breakdown() {
+        let result = swap_quote_by_output_token(1000, true, 1000, test_fusion_pool(1 << 64, false), test_tick_arrays(), None, None).unwrap();
+        assert_eq!(result.spot_sqrt_price, 1 << 64);
+        assert_eq!(result.execution_sqrt_price, 19241289333357602422);
+        assert_eq!(result.price_impact_bps, 879);
+        assert_eq!(result.liquidity_amount_in, 1046);
+        assert_eq!(result.liquidity_amount_out, 1000);
+        assert_eq!(result.limit_order_amount_in, 0);
+        assert_eq!(result.limit_order_amount_out, 0);
+        assert_eq!(result.ticks_crossed, 41);
+        assert_eq!(result.first_tick_array_start_index, 0);
+        assert_eq!(result.last_tick_array_start_index, 0);
+    }
+
+    #[test]
+    fn test_exact_in_a_to_b_with_orders_execution_breakdown() {
+        let result =
+            swap_quote_by_input_token(85000, true, 1000, test_fusion_pool_with_zero_liquidity(1 << 64), test_tick_arrays_with_orders(), None, None)
+                .unwrap();
+        assert_eq!(result.execution_sqrt_price, 18345770197474800229);
+        assert_eq!(result.price_impact_bps, 109);
+        assert_eq!(result.liquidity_amount_in, 0);
+        assert_eq!(result.liquidity_amount_out, 0);
+        assert_eq!(result.limit_order_amount_in, 84142);
+        assert_eq!(result.limit_order_amount_out, 84072);
+        assert_eq!(result.ticks_crossed, 9);
+        assert_eq!(result.first_tick_array_start_index, 0);
+        assert_eq!(result.last_tick_array_start_index, -176);
+    }
 }
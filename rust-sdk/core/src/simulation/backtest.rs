@@ -0,0 +1,423 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::{
+    collect_fees_quote, decrease_limit_order_quote, decrease_liquidity_quote, sqrt_price_to_price, CoreError, LimitOrderFacade, PoolSimulator,
+    PositionFacade,
+};
+use alloc::vec::Vec;
+
+#[cfg(feature = "backtest")]
+use crate::INVALID_SWAP_HISTORY;
+
+/// A swap of the historical stream replayed by the backtest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoricalSwap {
+    /// Unix timestamp of the swap. Only used to label the inventory snapshots.
+    pub timestamp: u64,
+    /// The input or output amount of the swap, depending on `specified_input`.
+    pub amount: u64,
+    /// The swap direction.
+    pub a_to_b: bool,
+    /// If `true`, `amount` is the input amount. Otherwise, it is the output amount.
+    pub specified_input: bool,
+    /// The price limit of the swap represented as a square root. `0` means no limit.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sqrt_price_limit: u128,
+}
+
+/// A position opened at the start of the backtest and closed at its end.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct BacktestPosition {
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub liquidity: u128,
+}
+
+/// A limit order placed at the start of the backtest and closed at its end.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct BacktestLimitOrder {
+    pub tick_index: i32,
+    pub amount: u64,
+    pub a_to_b: bool,
+}
+
+/// The holdings of a position or a limit order after a swap, if it were closed at that moment.
+/// For limit orders, the fees are the limit order rewards.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct InventorySnapshot {
+    pub timestamp: u64,
+    pub sqrt_price: u128,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub fee_a: u64,
+    pub fee_b: u64,
+}
+
+/// The backtest outcome of a position.
+///
+/// Values are expressed in raw units of token B at the final pool price.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PositionBacktestResult {
+    pub position: BacktestPosition,
+    pub deposited_a: u64,
+    pub deposited_b: u64,
+    pub withdrawn_a: u64,
+    pub withdrawn_b: u64,
+    pub fees_a: u64,
+    pub fees_b: u64,
+    pub inventory: Vec<InventorySnapshot>,
+    /// The value of the withdrawn tokens and the collected fees.
+    pub value: f64,
+    /// The value of the deposited tokens if they were held instead.
+    pub hold_value: f64,
+    /// `value - hold_value`.
+    pub pnl: f64,
+}
+
+/// The backtest outcome of a limit order.
+///
+/// Values are expressed in raw units of token B at the final pool price.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct LimitOrderBacktestResult {
+    pub limit_order: BacktestLimitOrder,
+    /// The filled part of the order input amount.
+    pub filled_amount: u64,
+    /// The withdrawn amounts, including the rewards.
+    pub amount_out_a: u64,
+    pub amount_out_b: u64,
+    pub reward_a: u64,
+    pub reward_b: u64,
+    pub inventory: Vec<InventorySnapshot>,
+    /// The value of the withdrawn tokens.
+    pub value: f64,
+    /// The value of the order input if it was held instead.
+    pub hold_value: f64,
+    /// `value - hold_value`.
+    pub pnl: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct BacktestReport {
+    pub positions: Vec<PositionBacktestResult>,
+    pub limit_orders: Vec<LimitOrderBacktestResult>,
+    /// The number of replayed swaps.
+    pub swaps_executed: u32,
+    /// Indexes of the swaps that couldn't be executed against the simulated pool, for example
+    /// because they run out of the loaded tick arrays. Such swaps are skipped.
+    pub skipped_swaps: Vec<usize>,
+    pub final_sqrt_price: u128,
+}
+
+/// Replays a historical stream of swaps against a simulated pool with the given positions and limit orders.
+///
+/// The positions and the limit orders are opened before the first swap, so they take part in the swaps,
+/// and are closed after the last one. After every swap, the inventory of each position and limit order is recorded.
+///
+/// # Arguments
+/// - `simulator`: The simulated pool in its initial state.
+/// - `positions`: The positions to evaluate.
+/// - `limit_orders`: The limit orders to evaluate.
+/// - `swaps`: The historical swaps in the order of their execution.
+///
+/// # Returns
+/// The backtest report, or an error if a position or a limit order can't be opened or closed.
+pub fn run_backtest(
+    simulator: PoolSimulator,
+    positions: &[BacktestPosition],
+    limit_orders: &[BacktestLimitOrder],
+    swaps: &[HistoricalSwap],
+) -> Result<BacktestReport, CoreError> {
+    let mut simulator = simulator;
+    let mut report = BacktestReport::default();
+
+    let mut open_positions: Vec<PositionFacade> = Vec::with_capacity(positions.len());
+    for backtest_position in positions {
+        let mut position = simulator.open_position(backtest_position.tick_lower_index, backtest_position.tick_upper_index)?;
+        let quote = simulator.increase_liquidity(&mut position, backtest_position.liquidity)?;
+        open_positions.push(position);
+        report.positions.push(PositionBacktestResult {
+            position: *backtest_position,
+            deposited_a: quote.token_est_a,
+            deposited_b: quote.token_est_b,
+            ..PositionBacktestResult::default()
+        });
+    }
+
+    let mut open_limit_orders: Vec<LimitOrderFacade> = Vec::with_capacity(limit_orders.len());
+    for backtest_limit_order in limit_orders {
+        let limit_order = simulator.open_limit_order(backtest_limit_order.amount, backtest_limit_order.tick_index, backtest_limit_order.a_to_b)?;
+        open_limit_orders.push(limit_order);
+        report.limit_orders.push(LimitOrderBacktestResult {
+            limit_order: *backtest_limit_order,
+            ..LimitOrderBacktestResult::default()
+        });
+    }
+
+    for (index, swap) in swaps.iter().enumerate() {
+        if simulator
+            .swap(swap.amount, swap.sqrt_price_limit, swap.a_to_b, swap.specified_input)
+            .is_err()
+        {
+            report.skipped_swaps.push(index);
+            continue;
+        }
+        report.swaps_executed += 1;
+
+        for (position, result) in open_positions.iter().zip(report.positions.iter_mut()) {
+            result.inventory.push(position_inventory(&simulator, position, swap.timestamp)?);
+        }
+        for (limit_order, result) in open_limit_orders.iter().zip(report.limit_orders.iter_mut()) {
+            result.inventory.push(limit_order_inventory(&simulator, limit_order, swap.timestamp)?);
+        }
+    }
+
+    let final_sqrt_price = simulator.fusion_pool().sqrt_price;
+    let price = sqrt_price_to_price(final_sqrt_price, 0, 0);
+    report.final_sqrt_price = final_sqrt_price;
+
+    for (position, result) in open_positions.iter_mut().zip(report.positions.iter_mut()) {
+        let liquidity = position.liquidity;
+        let quote = simulator.decrease_liquidity(position, liquidity)?;
+        let fees = simulator.collect_fees(position)?;
+
+        result.withdrawn_a = quote.token_est_a;
+        result.withdrawn_b = quote.token_est_b;
+        result.fees_a = fees.fee_owed_a;
+        result.fees_b = fees.fee_owed_b;
+        result.value = get_value(result.withdrawn_a as f64 + result.fees_a as f64, result.withdrawn_b as f64 + result.fees_b as f64, price);
+        result.hold_value = get_value(result.deposited_a as f64, result.deposited_b as f64, price);
+        result.pnl = result.value - result.hold_value;
+    }
+
+    for (limit_order, result) in open_limit_orders.iter_mut().zip(report.limit_orders.iter_mut()) {
+        let amount = limit_order.amount;
+        let a_to_b = limit_order.a_to_b;
+        let quote = simulator.decrease_limit_order(limit_order, amount)?;
+
+        // The unfilled part of the order is returned in its input token.
+        let remaining_input = if a_to_b { quote.amount_out_a } else { quote.amount_out_b };
+        result.filled_amount = amount - remaining_input;
+        result.amount_out_a = quote.amount_out_a;
+        result.amount_out_b = quote.amount_out_b;
+        result.reward_a = quote.reward_a;
+        result.reward_b = quote.reward_b;
+        result.value = get_value(quote.amount_out_a as f64, quote.amount_out_b as f64, price);
+        result.hold_value = if a_to_b { get_value(amount as f64, 0.0, price) } else { amount as f64 };
+        result.pnl = result.value - result.hold_value;
+    }
+
+    Ok(report)
+}
+
+/// Parses a CSV stream of historical swaps.
+///
+/// The first row is a header with the `timestamp`, `amount`, `a_to_b`, `specified_input` and optionally
+/// `sqrt_price_limit` columns, in any order.
+#[cfg(feature = "backtest")]
+pub fn read_historical_swaps_csv<R: std::io::Read>(reader: R) -> Result<Vec<HistoricalSwap>, CoreError> {
+    csv::Reader::from_reader(reader)
+        .deserialize()
+        .collect::<Result<Vec<HistoricalSwap>, _>>()
+        .map_err(|_| INVALID_SWAP_HISTORY)
+}
+
+/// Parses a JSON array of historical swaps. The objects have the same fields as `HistoricalSwap`.
+#[cfg(feature = "backtest")]
+pub fn read_historical_swaps_json<R: std::io::Read>(reader: R) -> Result<Vec<HistoricalSwap>, CoreError> {
+    serde_json::from_reader(reader).map_err(|_| INVALID_SWAP_HISTORY)
+}
+
+// Private functions
+
+fn get_value(amount_a: f64, amount_b: f64, price: f64) -> f64 {
+    amount_a * price + amount_b
+}
+
+fn position_inventory(simulator: &PoolSimulator, position: &PositionFacade, timestamp: u64) -> Result<InventorySnapshot, CoreError> {
+    let fusion_pool = simulator.fusion_pool();
+    let amounts =
+        decrease_liquidity_quote(position.liquidity, 0, fusion_pool.sqrt_price, position.tick_lower_index, position.tick_upper_index, None, None)?;
+    let fees = collect_fees_quote(
+        fusion_pool,
+        *position,
+        simulator.tick(position.tick_lower_index)?,
+        simulator.tick(position.tick_upper_index)?,
+        None,
+        None,
+    )?;
+
+    Ok(InventorySnapshot {
+        timestamp,
+        sqrt_price: fusion_pool.sqrt_price,
+        amount_a: amounts.token_est_a,
+        amount_b: amounts.token_est_b,
+        fee_a: fees.fee_owed_a,
+        fee_b: fees.fee_owed_b,
+    })
+}
+
+fn limit_order_inventory(simulator: &PoolSimulator, limit_order: &LimitOrderFacade, timestamp: u64) -> Result<InventorySnapshot, CoreError> {
+    let fusion_pool = simulator.fusion_pool();
+    let quote = decrease_limit_order_quote(fusion_pool, *limit_order, simulator.tick(limit_order.tick_index)?, limit_order.amount, None, None)?;
+
+    Ok(InventorySnapshot {
+        timestamp,
+        sqrt_price: fusion_pool.sqrt_price,
+        amount_a: quote.amount_out_a - quote.reward_a,
+        amount_b: quote.amount_out_b - quote.reward_b,
+        fee_a: quote.reward_a,
+        fee_b: quote.reward_b,
+    })
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{FusionPoolFacade, TickArrayFacade, TickFacade, TICK_ARRAY_SIZE};
+
+    const TICK_ARRAY_SPAN: i32 = TICK_ARRAY_SIZE as i32 * 64;
+
+    fn test_simulator() -> PoolSimulator {
+        let fusion_pool = FusionPoolFacade {
+            tick_spacing: 64,
+            fee_rate: 3000,
+            protocol_fee_rate: 1000,
+            sqrt_price: 1 << 64,
+            ..FusionPoolFacade::default()
+        };
+        let tick_arrays = (-2..2)
+            .map(|i| TickArrayFacade {
+                start_tick_index: i * TICK_ARRAY_SPAN,
+                ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+            })
+            .collect();
+        PoolSimulator::new(fusion_pool, tick_arrays).unwrap()
+    }
+
+    fn test_swap(timestamp: u64, amount: u64, a_to_b: bool) -> HistoricalSwap {
+        HistoricalSwap {
+            timestamp,
+            amount,
+            a_to_b,
+            specified_input: true,
+            sqrt_price_limit: 0,
+        }
+    }
+
+    const POSITION: BacktestPosition = BacktestPosition {
+        tick_lower_index: -TICK_ARRAY_SPAN,
+        tick_upper_index: TICK_ARRAY_SPAN,
+        liquidity: 1_000_000_000,
+    };
+
+    #[test]
+    fn test_position_earns_fees_on_round_trip() {
+        let swaps = [
+            test_swap(1, 10_000_000, true),
+            test_swap(2, 10_000_000, false),
+            test_swap(3, 10_000_000, true),
+        ];
+        let report = run_backtest(test_simulator(), &[POSITION], &[], &swaps).unwrap();
+
+        assert_eq!(report.swaps_executed, 3);
+        assert!(report.skipped_swaps.is_empty());
+
+        let result = &report.positions[0];
+        assert!(result.deposited_a > 0 && result.deposited_b > 0);
+        // The position is the only liquidity, so it earns the swap fees minus the protocol fee and rounding.
+        assert_eq!(result.fees_a, 53_999);
+        assert!(result.fees_b > 0);
+        assert!((result.pnl - (result.value - result.hold_value)).abs() < f64::EPSILON);
+
+        assert_eq!(result.inventory.len(), 3);
+        assert_eq!(result.inventory.iter().map(|snapshot| snapshot.timestamp).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(result.inventory[2].sqrt_price, report.final_sqrt_price);
+        assert_eq!(result.inventory[2].fee_a, result.fees_a);
+        assert_eq!(result.inventory[2].amount_a, result.withdrawn_a);
+    }
+
+    #[test]
+    fn test_limit_order_is_filled() {
+        let limit_order = BacktestLimitOrder {
+            tick_index: 640,
+            amount: 1_000_000,
+            a_to_b: true,
+        };
+        let swaps = [test_swap(1, 10_000_000, false), test_swap(2, 50_000_000, false)];
+        let report = run_backtest(test_simulator(), &[POSITION], &[limit_order], &swaps).unwrap();
+
+        let result = &report.limit_orders[0];
+        assert_eq!(result.inventory[0].amount_a, 1_000_000);
+        assert_eq!(result.inventory[0].amount_b, 0);
+
+        assert_eq!(result.filled_amount, 1_000_000);
+        assert_eq!(result.amount_out_a, 0);
+        assert!(result.reward_b > 0);
+        assert_eq!(result.amount_out_b, result.inventory[1].amount_b + result.inventory[1].fee_b);
+        assert_eq!(result.hold_value, sqrt_price_to_price(report.final_sqrt_price, 0, 0) * 1_000_000.0);
+    }
+
+    #[test]
+    fn test_skips_swaps_out_of_tick_arrays() {
+        let swaps = [
+            test_swap(1, 10_000_000, true),
+            test_swap(2, u64::MAX / 2, true),
+            test_swap(3, 10_000_000, false),
+        ];
+        let report = run_backtest(test_simulator(), &[POSITION], &[], &swaps).unwrap();
+        assert_eq!(report.swaps_executed, 2);
+        assert_eq!(report.skipped_swaps, vec![1]);
+        assert_eq!(report.positions[0].inventory.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_strategy() {
+        let position = BacktestPosition {
+            tick_lower_index: 64,
+            tick_upper_index: 0,
+            liquidity: 1,
+        };
+        assert!(run_backtest(test_simulator(), &[position], &[], &[]).is_err());
+    }
+
+    #[cfg(feature = "backtest")]
+    #[test]
+    fn test_read_historical_swaps_csv() {
+        let data = "timestamp,amount,a_to_b,specified_input\n1,1000,true,true\n2,500,false,false\n";
+        let swaps = read_historical_swaps_csv(data.as_bytes()).unwrap();
+        assert_eq!(
+            swaps,
+            vec![
+                test_swap(1, 1000, true),
+                HistoricalSwap {
+                    specified_input: false,
+                    ..test_swap(2, 500, false)
+                }
+            ]
+        );
+
+        assert_eq!(read_historical_swaps_csv("timestamp,amount\n1,x\n".as_bytes()), Err(INVALID_SWAP_HISTORY));
+    }
+
+    #[cfg(feature = "backtest")]
+    #[test]
+    fn test_read_historical_swaps_json() {
+        let data = r#"[{"timestamp":1,"amount":1000,"a_to_b":true,"specified_input":true,"sqrt_price_limit":18446744073709551616}]"#;
+        let swaps = read_historical_swaps_json(data.as_bytes()).unwrap();
+        assert_eq!(
+            swaps,
+            vec![HistoricalSwap {
+                sqrt_price_limit: 1 << 64,
+                ..test_swap(1, 1000, true)
+            }]
+        );
+
+        assert_eq!(read_historical_swaps_json("{}".as_bytes()), Err(INVALID_SWAP_HISTORY));
+    }
+}
@@ -0,0 +1,12 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+mod backtest;
+mod pool_simulator;
+
+pub use backtest::*;
+pub use pool_simulator::*;
@@ -0,0 +1,859 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::quote::{compute_swap_steps, get_fee_growth_inside, SwapStep};
+use crate::{
+    decrease_limit_order_quote, decrease_liquidity_quote, get_tick_array_start_tick_index, get_tick_index_in_array, increase_liquidity_quote,
+    is_tick_index_in_bounds, is_tick_initializable, tick_index_to_sqrt_price, try_mul_div, CollectFeesQuote, CoreError, DecreaseLiquidityQuote,
+    FusionPoolFacade, IncreaseLiquidityQuote, LimitOrderDecreaseQuote, LimitOrderFacade, LimitSwapComputation, PositionFacade, SwapResult,
+    TickArrayFacade, TickArraySequence, TickFacade, AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW, INVALID_RANGE_BOUNDS, INVALID_TICK_INDEX,
+    LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC, LIQUIDITY_DELTA_EXCEEDS_POSITION_LIQUIDITY, PROTOCOL_FEE_RATE_MUL_VALUE, TAKER_ORDER_NOT_SUPPORTED,
+    TICK_ARRAY_NOT_EVENLY_SPACED, TICK_INDEX_OUT_OF_BOUNDS, TICK_SEQUENCE_EMPTY, ZERO_LIQUIDITY_DELTA, ZERO_TRADABLE_AMOUNT,
+};
+use alloc::vec::Vec;
+use ethnum::U256;
+
+/// An in-memory copy of a fusion pool and its tick arrays that applies actions with the same state transitions as the program.
+///
+/// Unlike the quote functions, which only compute the outcome of a single action against a fixed state,
+/// the simulator keeps the post-action pool and ticks (fee growth, crossed ticks, limit order fills and tick ages),
+/// so a sequence of actions can be quoted against the evolving state.
+///
+/// Positions and limit orders are owned by the caller and updated in place. Every action is atomic:
+/// if it fails, neither the simulator nor the passed position or limit order is modified.
+///
+/// Transfer fees are not taken into account. All amounts are the ones that enter or leave the pool vaults.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolSimulator {
+    fusion_pool: FusionPoolFacade,
+    tick_arrays: Vec<TickArrayFacade>,
+    protocol_fee_owed_a: u64,
+    protocol_fee_owed_b: u64,
+}
+
+impl PoolSimulator {
+    /// Creates a simulator from the pool state and the tick arrays that the simulated actions may touch.
+    ///
+    /// # Arguments
+    /// - `fusion_pool`: The fusion_pool state.
+    /// - `tick_arrays`: The tick arrays of the pool. Their order doesn't matter.
+    ///
+    /// # Returns
+    /// The simulator, or an error if no tick arrays are provided or a tick array has an invalid start index.
+    pub fn new(fusion_pool: FusionPoolFacade, tick_arrays: Vec<TickArrayFacade>) -> Result<Self, CoreError> {
+        let mut tick_arrays = tick_arrays;
+        tick_arrays.sort_by_key(|tick_array| tick_array.start_tick_index);
+        tick_arrays.dedup_by_key(|tick_array| tick_array.start_tick_index);
+
+        if tick_arrays.is_empty() {
+            return Err(TICK_SEQUENCE_EMPTY);
+        }
+
+        for tick_array in &tick_arrays {
+            if get_tick_array_start_tick_index(tick_array.start_tick_index, fusion_pool.tick_spacing) != tick_array.start_tick_index {
+                return Err(TICK_ARRAY_NOT_EVENLY_SPACED);
+            }
+        }
+
+        Ok(Self {
+            fusion_pool,
+            tick_arrays,
+            protocol_fee_owed_a: 0,
+            protocol_fee_owed_b: 0,
+        })
+    }
+
+    /// Returns the current pool state.
+    pub fn fusion_pool(&self) -> FusionPoolFacade {
+        self.fusion_pool
+    }
+
+    /// Returns the current tick arrays sorted by their start tick index.
+    pub fn tick_arrays(&self) -> &[TickArrayFacade] {
+        &self.tick_arrays
+    }
+
+    /// Returns the current state of a tick.
+    pub fn tick(&self, tick_index: i32) -> Result<TickFacade, CoreError> {
+        let (array_index, tick_offset) = self.tick_location(tick_index)?;
+        Ok(self.tick_arrays[array_index].ticks[tick_offset])
+    }
+
+    /// Returns the protocol fees accrued since the simulation started or since they were last collected.
+    pub fn protocol_fees_owed(&self) -> CollectFeesQuote {
+        CollectFeesQuote {
+            fee_owed_a: self.protocol_fee_owed_a,
+            fee_owed_b: self.protocol_fee_owed_b,
+        }
+    }
+
+    /// Swaps tokens against the pool. The arguments and the result are the same as of `compute_swap`.
+    ///
+    /// # Arguments
+    /// - `token_amount`: The input or output amount specified for the swap. Must be non-zero.
+    /// - `sqrt_price_limit`: The price limit for the swap represented as a square root. If set to `0`,
+    ///   it defaults to the minimum or maximum sqrt price based on the direction of the swap.
+    /// - `a_to_b`: If `true`, swaps token A to token B. Otherwise, swaps token B to token A.
+    /// - `specified_input`: If `true`, `token_amount` is the input amount. Otherwise, it is the output amount.
+    ///
+    /// # Returns
+    /// The swap amounts. After the swap, the pool price, liquidity, fee growth, the filled limit orders
+    /// and the crossed ticks are updated.
+    pub fn swap(&mut self, token_amount: u64, sqrt_price_limit: u128, a_to_b: bool, specified_input: bool) -> Result<SwapResult, CoreError> {
+        let tick_sequence = TickArraySequence::new(self.tick_arrays.clone(), self.fusion_pool.tick_spacing)?;
+
+        let mut next = self.clone();
+        let (swap_result, end_state) =
+            compute_swap_steps(token_amount, sqrt_price_limit, self.fusion_pool, tick_sequence, a_to_b, specified_input, |step| {
+                next.apply_swap_step(step, a_to_b)
+            })?;
+
+        next.fusion_pool.sqrt_price = swap_result.next_sqrt_price;
+        next.fusion_pool.tick_current_index = end_state.tick_current_index;
+        next.fusion_pool.liquidity = end_state.liquidity;
+
+        *self = next;
+        Ok(swap_result)
+    }
+
+    /// Creates an empty position in the given range. The ticks are initialized when liquidity is added.
+    pub fn open_position(&self, tick_lower_index: i32, tick_upper_index: i32) -> Result<PositionFacade, CoreError> {
+        let tick_spacing = self.fusion_pool.tick_spacing;
+        if tick_lower_index >= tick_upper_index {
+            return Err(INVALID_RANGE_BOUNDS);
+        }
+        if !is_tick_index_in_bounds(tick_lower_index) || !is_tick_index_in_bounds(tick_upper_index) {
+            return Err(TICK_INDEX_OUT_OF_BOUNDS);
+        }
+        if !is_tick_initializable(tick_lower_index, tick_spacing) || !is_tick_initializable(tick_upper_index, tick_spacing) {
+            return Err(INVALID_TICK_INDEX);
+        }
+
+        Ok(PositionFacade {
+            tick_lower_index,
+            tick_upper_index,
+            ..PositionFacade::default()
+        })
+    }
+
+    /// Adds liquidity to a position.
+    ///
+    /// # Returns
+    /// The token amounts deposited into the pool.
+    pub fn increase_liquidity(&mut self, position: &mut PositionFacade, liquidity_delta: u128) -> Result<IncreaseLiquidityQuote, CoreError> {
+        if liquidity_delta == 0 {
+            return Err(ZERO_LIQUIDITY_DELTA);
+        }
+        let liquidity_delta_signed: i128 = liquidity_delta.try_into().map_err(|_| ARITHMETIC_OVERFLOW)?;
+
+        let mut next = self.clone();
+        let mut next_position = *position;
+        next.modify_liquidity(&mut next_position, liquidity_delta_signed)?;
+
+        let quote = increase_liquidity_quote(
+            liquidity_delta,
+            0,
+            self.fusion_pool.sqrt_price,
+            position.tick_lower_index,
+            position.tick_upper_index,
+            None,
+            None,
+        )?;
+
+        *self = next;
+        *position = next_position;
+        Ok(quote)
+    }
+
+    /// Removes liquidity from a position. Fees earned so far are credited to the position and can be collected with `collect_fees`.
+    ///
+    /// # Returns
+    /// The token amounts withdrawn from the pool.
+    pub fn decrease_liquidity(&mut self, position: &mut PositionFacade, liquidity_delta: u128) -> Result<DecreaseLiquidityQuote, CoreError> {
+        if liquidity_delta == 0 {
+            return Err(ZERO_LIQUIDITY_DELTA);
+        }
+        if liquidity_delta > position.liquidity {
+            return Err(LIQUIDITY_DELTA_EXCEEDS_POSITION_LIQUIDITY);
+        }
+        let liquidity_delta_signed: i128 = liquidity_delta.try_into().map_err(|_| ARITHMETIC_OVERFLOW)?;
+
+        let mut next = self.clone();
+        let mut next_position = *position;
+        next.modify_liquidity(&mut next_position, -liquidity_delta_signed)?;
+
+        let quote = decrease_liquidity_quote(
+            liquidity_delta,
+            0,
+            self.fusion_pool.sqrt_price,
+            position.tick_lower_index,
+            position.tick_upper_index,
+            None,
+            None,
+        )?;
+
+        *self = next;
+        *position = next_position;
+        Ok(quote)
+    }
+
+    /// Collects the fees earned by a position.
+    ///
+    /// # Returns
+    /// The collected fees. The fees owed by the position are reset to zero.
+    pub fn collect_fees(&mut self, position: &mut PositionFacade) -> Result<CollectFeesQuote, CoreError> {
+        let mut next_position = *position;
+        if next_position.liquidity > 0 {
+            self.update_position_fees(&mut next_position)?;
+        }
+
+        let quote = CollectFeesQuote {
+            fee_owed_a: next_position.fee_owed_a,
+            fee_owed_b: next_position.fee_owed_b,
+        };
+        next_position.fee_owed_a = 0;
+        next_position.fee_owed_b = 0;
+
+        *position = next_position;
+        Ok(quote)
+    }
+
+    /// Collects the accrued protocol fees.
+    pub fn collect_protocol_fees(&mut self) -> CollectFeesQuote {
+        let quote = self.protocol_fees_owed();
+        self.protocol_fee_owed_a = 0;
+        self.protocol_fee_owed_b = 0;
+        quote
+    }
+
+    /// Places a limit order.
+    ///
+    /// # Arguments
+    /// - `amount`: The input token amount of the order.
+    /// - `tick_index`: The tick index of the order price.
+    /// - `a_to_b`: The order direction. An A to B order sells token A and must be placed above the current price,
+    ///   a B to A order sells token B and must be placed below the current price.
+    ///
+    /// # Returns
+    /// The new limit order.
+    pub fn open_limit_order(&mut self, amount: u64, tick_index: i32, a_to_b: bool) -> Result<LimitOrderFacade, CoreError> {
+        if amount == 0 {
+            return Err(ZERO_TRADABLE_AMOUNT);
+        }
+        if !is_tick_index_in_bounds(tick_index) {
+            return Err(TICK_INDEX_OUT_OF_BOUNDS);
+        }
+        if !is_tick_initializable(tick_index, self.fusion_pool.tick_spacing) {
+            return Err(INVALID_TICK_INDEX);
+        }
+
+        let limit_sqrt_price = tick_index_to_sqrt_price(tick_index);
+        let is_taker_order =
+            (limit_sqrt_price <= self.fusion_pool.sqrt_price && a_to_b) || (limit_sqrt_price >= self.fusion_pool.sqrt_price && !a_to_b);
+        if is_taker_order {
+            return Err(TAKER_ORDER_NOT_SUPPORTED);
+        }
+
+        let mut fusion_pool = self.fusion_pool;
+        let orders_total_amount = if a_to_b {
+            &mut fusion_pool.orders_total_amount_a
+        } else {
+            &mut fusion_pool.orders_total_amount_b
+        };
+        *orders_total_amount = orders_total_amount.checked_add(amount).ok_or(AMOUNT_EXCEEDS_MAX_U64)?;
+
+        let mut tick = self.tick(tick_index)?;
+        initialize_tick(&mut tick, tick_index, &fusion_pool);
+        tick.open_orders_input = tick.open_orders_input.checked_add(amount).ok_or(AMOUNT_EXCEEDS_MAX_U64)?;
+
+        self.fusion_pool = fusion_pool;
+        self.set_tick(tick_index, tick)?;
+
+        Ok(LimitOrderFacade {
+            tick_index,
+            amount,
+            a_to_b,
+            age: tick.age,
+        })
+    }
+
+    /// Decreases a limit order, withdrawing its unfilled input and the filled output together with the reward.
+    ///
+    /// # Returns
+    /// The withdrawn amounts, the same as returned by `decrease_limit_order_quote`.
+    pub fn decrease_limit_order(&mut self, limit_order: &mut LimitOrderFacade, amount: u64) -> Result<LimitOrderDecreaseQuote, CoreError> {
+        let mut tick = self.tick(limit_order.tick_index)?;
+        let quote = decrease_limit_order_quote(self.fusion_pool, *limit_order, tick, amount, None, None)?;
+
+        // decrease_limit_order_quote has already checked that the order is in sync with the tick.
+        let remaining_input = if limit_order.age == tick.age {
+            tick.open_orders_input = tick.open_orders_input.checked_sub(amount).ok_or(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC)?;
+            amount
+        } else if limit_order.age + 1 == tick.age {
+            let remaining_input = try_mul_div(amount, tick.part_filled_orders_remaining_input as u128, tick.part_filled_orders_input as u128, false)?;
+            tick.part_filled_orders_input = tick
+                .part_filled_orders_input
+                .checked_sub(amount)
+                .ok_or(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC)?;
+            tick.part_filled_orders_remaining_input = tick
+                .part_filled_orders_remaining_input
+                .checked_sub(remaining_input)
+                .ok_or(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC)?;
+            remaining_input
+        } else {
+            let fulfilled_orders_input = if limit_order.a_to_b {
+                &mut tick.fulfilled_a_to_b_orders_input
+            } else {
+                &mut tick.fulfilled_b_to_a_orders_input
+            };
+            *fulfilled_orders_input = fulfilled_orders_input.checked_sub(amount).ok_or(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC)?;
+            0
+        };
+        let filled_amount = amount - remaining_input;
+
+        let mut fusion_pool = self.fusion_pool;
+        let (orders_total_amount, orders_filled_amount, olp_fee_owed, reward) = if limit_order.a_to_b {
+            (
+                &mut fusion_pool.orders_total_amount_a,
+                &mut fusion_pool.orders_filled_amount_a,
+                &mut fusion_pool.olp_fee_owed_b,
+                quote.reward_b,
+            )
+        } else {
+            (
+                &mut fusion_pool.orders_total_amount_b,
+                &mut fusion_pool.orders_filled_amount_b,
+                &mut fusion_pool.olp_fee_owed_a,
+                quote.reward_a,
+            )
+        };
+        *orders_total_amount = orders_total_amount.checked_sub(amount).ok_or(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC)?;
+        *orders_filled_amount = orders_filled_amount
+            .checked_sub(filled_amount)
+            .ok_or(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC)?;
+        *olp_fee_owed = olp_fee_owed.checked_sub(reward).ok_or(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC)?;
+
+        release_tick(&mut tick);
+
+        self.fusion_pool = fusion_pool;
+        self.set_tick(limit_order.tick_index, tick)?;
+        limit_order.amount -= amount;
+
+        Ok(quote)
+    }
+
+    // Private functions
+
+    fn tick_location(&self, tick_index: i32) -> Result<(usize, usize), CoreError> {
+        let tick_spacing = self.fusion_pool.tick_spacing;
+        if !is_tick_initializable(tick_index, tick_spacing) {
+            return Err(INVALID_TICK_INDEX);
+        }
+        let start_tick_index = get_tick_array_start_tick_index(tick_index, tick_spacing);
+        let array_index = self
+            .tick_arrays
+            .binary_search_by_key(&start_tick_index, |tick_array| tick_array.start_tick_index)
+            .map_err(|_| TICK_INDEX_OUT_OF_BOUNDS)?;
+        let tick_offset = get_tick_index_in_array(tick_index, start_tick_index, tick_spacing)?;
+        Ok((array_index, tick_offset as usize))
+    }
+
+    fn set_tick(&mut self, tick_index: i32, tick: TickFacade) -> Result<(), CoreError> {
+        let (array_index, tick_offset) = self.tick_location(tick_index)?;
+        self.tick_arrays[array_index].ticks[tick_offset] = tick;
+        Ok(())
+    }
+
+    fn apply_swap_step(&mut self, step: SwapStep, a_to_b: bool) -> Result<(), CoreError> {
+        // Liquidity providers earn the swap fee of the step, minus the protocol fee.
+        let protocol_fee = try_mul_div(step.fee_amount, self.fusion_pool.protocol_fee_rate as u128, PROTOCOL_FEE_RATE_MUL_VALUE as u128, false)?;
+        self.accrue_protocol_fee(protocol_fee, a_to_b)?;
+        // Without liquidity there is nobody to distribute the fee to.
+        if let Some(fee_growth) = (((step.fee_amount - protocol_fee) as u128) << 64).checked_div(step.liquidity) {
+            if a_to_b {
+                self.fusion_pool.fee_growth_global_a = self.fusion_pool.fee_growth_global_a.wrapping_add(fee_growth);
+            } else {
+                self.fusion_pool.fee_growth_global_b = self.fusion_pool.fee_growth_global_b.wrapping_add(fee_growth);
+            }
+        }
+
+        if let Some(crossed_tick) = step.crossed_tick {
+            let mut tick = self.tick(crossed_tick.tick_index)?;
+            self.fill_limit_orders(&mut tick, &crossed_tick.limit_order_fill, a_to_b)?;
+            tick.fee_growth_outside_a = self.fusion_pool.fee_growth_global_a.wrapping_sub(tick.fee_growth_outside_a);
+            tick.fee_growth_outside_b = self.fusion_pool.fee_growth_global_b.wrapping_sub(tick.fee_growth_outside_b);
+            self.set_tick(crossed_tick.tick_index, tick)?;
+        }
+
+        Ok(())
+    }
+
+    fn accrue_protocol_fee(&mut self, protocol_fee: u64, a_to_b: bool) -> Result<(), CoreError> {
+        let protocol_fee_owed = if a_to_b {
+            &mut self.protocol_fee_owed_a
+        } else {
+            &mut self.protocol_fee_owed_b
+        };
+        *protocol_fee_owed = protocol_fee_owed.checked_add(protocol_fee).ok_or(AMOUNT_EXCEEDS_MAX_U64)?;
+        Ok(())
+    }
+
+    /// Fills the limit orders of a tick reached by a swap in the `a_to_b` direction.
+    ///
+    /// Orders are filled in the order they were placed: partially filled orders first, then the open ones.
+    /// Each time a group of orders changes its state, the tick age is advanced, so that an order with
+    /// `order.age == tick.age` is open, with `order.age + 1 == tick.age` is partially filled and with
+    /// `order.age + 2 <= tick.age` is fulfilled.
+    fn fill_limit_orders(&mut self, tick: &mut TickFacade, fill: &LimitSwapComputation, a_to_b: bool) -> Result<(), CoreError> {
+        let filled_amount = fill.amount_out;
+        if filled_amount == 0 && fill.fee_amount == 0 {
+            return Ok(());
+        }
+
+        // The fee paid for the limit order fill is rewarded to the orders, minus the protocol fee.
+        let protocol_fee = try_mul_div(fill.fee_amount, self.fusion_pool.protocol_fee_rate as u128, PROTOCOL_FEE_RATE_MUL_VALUE as u128, false)?;
+        self.accrue_protocol_fee(protocol_fee, a_to_b)?;
+        let (olp_fee_owed, orders_filled_amount) = if a_to_b {
+            (&mut self.fusion_pool.olp_fee_owed_a, &mut self.fusion_pool.orders_filled_amount_b)
+        } else {
+            (&mut self.fusion_pool.olp_fee_owed_b, &mut self.fusion_pool.orders_filled_amount_a)
+        };
+        *olp_fee_owed = olp_fee_owed.checked_add(fill.fee_amount - protocol_fee).ok_or(AMOUNT_EXCEEDS_MAX_U64)?;
+        *orders_filled_amount = orders_filled_amount.checked_add(filled_amount).ok_or(AMOUNT_EXCEEDS_MAX_U64)?;
+
+        let total_remaining_input = tick.part_filled_orders_remaining_input + tick.open_orders_input;
+        if filled_amount > total_remaining_input {
+            return Err(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC);
+        }
+
+        let fulfilled_orders_input = if a_to_b {
+            &mut tick.fulfilled_b_to_a_orders_input
+        } else {
+            &mut tick.fulfilled_a_to_b_orders_input
+        };
+
+        if filled_amount < tick.part_filled_orders_remaining_input {
+            // Only the partially filled orders are filled further.
+            tick.part_filled_orders_remaining_input -= filled_amount;
+        } else if filled_amount < total_remaining_input {
+            // The partially filled orders are fulfilled and the open orders become partially filled.
+            *fulfilled_orders_input = fulfilled_orders_input
+                .checked_add(tick.part_filled_orders_input)
+                .ok_or(AMOUNT_EXCEEDS_MAX_U64)?;
+            tick.part_filled_orders_input = tick.open_orders_input;
+            tick.part_filled_orders_remaining_input = total_remaining_input - filled_amount;
+            tick.open_orders_input = 0;
+            tick.age += 1;
+        } else {
+            // All orders are fulfilled.
+            *fulfilled_orders_input = fulfilled_orders_input
+                .checked_add(tick.part_filled_orders_input)
+                .ok_or(AMOUNT_EXCEEDS_MAX_U64)?
+                .checked_add(tick.open_orders_input)
+                .ok_or(AMOUNT_EXCEEDS_MAX_U64)?;
+            tick.age += if tick.open_orders_input > 0 { 2 } else { 1 };
+            tick.part_filled_orders_input = 0;
+            tick.part_filled_orders_remaining_input = 0;
+            tick.open_orders_input = 0;
+        }
+
+        Ok(())
+    }
+
+    fn modify_liquidity(&mut self, position: &mut PositionFacade, liquidity_delta: i128) -> Result<(), CoreError> {
+        let mut tick_lower = self.tick(position.tick_lower_index)?;
+        let mut tick_upper = self.tick(position.tick_upper_index)?;
+        update_tick_liquidity(&mut tick_lower, position.tick_lower_index, &self.fusion_pool, liquidity_delta, false)?;
+        update_tick_liquidity(&mut tick_upper, position.tick_upper_index, &self.fusion_pool, liquidity_delta, true)?;
+        self.set_tick(position.tick_lower_index, tick_lower)?;
+        self.set_tick(position.tick_upper_index, tick_upper)?;
+
+        // Fees are accrued on the ticks state before they are released, otherwise the fee growth outside of a released tick is lost.
+        self.update_position_fees_with_ticks(position, &tick_lower, &tick_upper)?;
+        position.liquidity = position.liquidity.checked_add_signed(liquidity_delta).ok_or(ARITHMETIC_OVERFLOW)?;
+
+        if self.fusion_pool.tick_current_index >= position.tick_lower_index && self.fusion_pool.tick_current_index < position.tick_upper_index {
+            self.fusion_pool.liquidity = self
+                .fusion_pool
+                .liquidity
+                .checked_add_signed(liquidity_delta)
+                .ok_or(ARITHMETIC_OVERFLOW)?;
+        }
+
+        release_tick(&mut tick_lower);
+        release_tick(&mut tick_upper);
+        self.set_tick(position.tick_lower_index, tick_lower)?;
+        self.set_tick(position.tick_upper_index, tick_upper)?;
+
+        Ok(())
+    }
+
+    fn update_position_fees(&self, position: &mut PositionFacade) -> Result<(), CoreError> {
+        let tick_lower = self.tick(position.tick_lower_index)?;
+        let tick_upper = self.tick(position.tick_upper_index)?;
+        self.update_position_fees_with_ticks(position, &tick_lower, &tick_upper)
+    }
+
+    fn update_position_fees_with_ticks(
+        &self,
+        position: &mut PositionFacade,
+        tick_lower: &TickFacade,
+        tick_upper: &TickFacade,
+    ) -> Result<(), CoreError> {
+        let (fee_growth_inside_a, fee_growth_inside_b) = get_fee_growth_inside(self.fusion_pool, *position, *tick_lower, *tick_upper);
+
+        let fee_owed_delta_a = get_fee_owed_delta(fee_growth_inside_a.wrapping_sub(position.fee_growth_checkpoint_a), position.liquidity)?;
+        let fee_owed_delta_b = get_fee_owed_delta(fee_growth_inside_b.wrapping_sub(position.fee_growth_checkpoint_b), position.liquidity)?;
+
+        position.fee_owed_a = position.fee_owed_a.checked_add(fee_owed_delta_a).ok_or(AMOUNT_EXCEEDS_MAX_U64)?;
+        position.fee_owed_b = position.fee_owed_b.checked_add(fee_owed_delta_b).ok_or(AMOUNT_EXCEEDS_MAX_U64)?;
+        position.fee_growth_checkpoint_a = fee_growth_inside_a;
+        position.fee_growth_checkpoint_b = fee_growth_inside_b;
+
+        Ok(())
+    }
+}
+
+fn get_fee_owed_delta(fee_growth_delta: u128, liquidity: u128) -> Result<u64, CoreError> {
+    let fee_owed_delta: U256 = <U256>::from(fee_growth_delta).checked_mul(liquidity.into()).ok_or(ARITHMETIC_OVERFLOW)? >> 64;
+    fee_owed_delta.try_into().map_err(|_| AMOUNT_EXCEEDS_MAX_U64)
+}
+
+/// Marks a tick as initialized. By convention, all fee growth is assumed to have happened below the tick.
+fn initialize_tick(tick: &mut TickFacade, tick_index: i32, fusion_pool: &FusionPoolFacade) {
+    if tick.initialized {
+        return;
+    }
+    if fusion_pool.tick_current_index >= tick_index {
+        tick.fee_growth_outside_a = fusion_pool.fee_growth_global_a;
+        tick.fee_growth_outside_b = fusion_pool.fee_growth_global_b;
+    }
+    tick.initialized = true;
+}
+
+fn update_tick_liquidity(
+    tick: &mut TickFacade,
+    tick_index: i32,
+    fusion_pool: &FusionPoolFacade,
+    liquidity_delta: i128,
+    is_upper_tick: bool,
+) -> Result<(), CoreError> {
+    initialize_tick(tick, tick_index, fusion_pool);
+    tick.liquidity_gross = tick.liquidity_gross.checked_add_signed(liquidity_delta).ok_or(ARITHMETIC_OVERFLOW)?;
+    tick.liquidity_net = if is_upper_tick {
+        tick.liquidity_net.checked_sub(liquidity_delta)
+    } else {
+        tick.liquidity_net.checked_add(liquidity_delta)
+    }
+    .ok_or(ARITHMETIC_OVERFLOW)?;
+    Ok(())
+}
+
+/// Uninitializes a tick that is no longer referenced by positions or limit orders. The tick age is preserved.
+fn release_tick(tick: &mut TickFacade) {
+    let has_limit_orders = tick.open_orders_input > 0
+        || tick.part_filled_orders_input > 0
+        || tick.fulfilled_a_to_b_orders_input > 0
+        || tick.fulfilled_b_to_a_orders_input > 0;
+    if tick.liquidity_gross == 0 && !has_limit_orders {
+        *tick = TickFacade {
+            age: tick.age,
+            ..TickFacade::default()
+        };
+    }
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{compute_swap, sqrt_price_to_tick_index, AMOUNT_EXCEEDS_LIMIT_ORDER_INPUT_AMOUNT, INVALID_TICK_ARRAY_SEQUENCE, TICK_ARRAY_SIZE};
+
+    const TICK_SPACING: u16 = 64;
+    const TICK_ARRAY_SPAN: i32 = TICK_ARRAY_SIZE as i32 * TICK_SPACING as i32;
+
+    fn test_fusion_pool() -> FusionPoolFacade {
+        FusionPoolFacade {
+            tick_spacing: TICK_SPACING,
+            fee_rate: 3000,
+            protocol_fee_rate: 1000,
+            sqrt_price: 1 << 64,
+            tick_current_index: 0,
+            ..FusionPoolFacade::default()
+        }
+    }
+
+    fn test_simulator() -> PoolSimulator {
+        let tick_arrays = (-2..2)
+            .map(|i| TickArrayFacade {
+                start_tick_index: i * TICK_ARRAY_SPAN,
+                ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+            })
+            .collect();
+        PoolSimulator::new(test_fusion_pool(), tick_arrays).unwrap()
+    }
+
+    fn test_simulator_with_liquidity() -> (PoolSimulator, PositionFacade) {
+        let mut simulator = test_simulator();
+        let mut position = simulator.open_position(-TICK_ARRAY_SPAN, TICK_ARRAY_SPAN).unwrap();
+        simulator.increase_liquidity(&mut position, 1_000_000_000).unwrap();
+        (simulator, position)
+    }
+
+    fn expected_swap(simulator: &PoolSimulator, token_amount: u64, a_to_b: bool, specified_input: bool) -> SwapResult {
+        let tick_sequence = TickArraySequence::new(simulator.tick_arrays().to_vec(), TICK_SPACING).unwrap();
+        compute_swap(token_amount, 0, simulator.fusion_pool(), tick_sequence, a_to_b, specified_input).unwrap()
+    }
+
+    #[test]
+    fn test_new_requires_tick_arrays() {
+        assert_eq!(PoolSimulator::new(test_fusion_pool(), vec![]), Err(TICK_SEQUENCE_EMPTY));
+        let tick_array = TickArrayFacade {
+            start_tick_index: 64,
+            ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+        };
+        assert_eq!(PoolSimulator::new(test_fusion_pool(), vec![tick_array]), Err(TICK_ARRAY_NOT_EVENLY_SPACED));
+    }
+
+    #[test]
+    fn test_increase_liquidity_initializes_ticks() {
+        let (simulator, position) = test_simulator_with_liquidity();
+        assert_eq!(position.liquidity, 1_000_000_000);
+        assert_eq!(simulator.fusion_pool().liquidity, 1_000_000_000);
+
+        let tick_lower = simulator.tick(-TICK_ARRAY_SPAN).unwrap();
+        assert!(tick_lower.initialized);
+        assert_eq!(tick_lower.liquidity_gross, 1_000_000_000);
+        assert_eq!(tick_lower.liquidity_net, 1_000_000_000);
+
+        let tick_upper = simulator.tick(TICK_ARRAY_SPAN).unwrap();
+        assert!(tick_upper.initialized);
+        assert_eq!(tick_upper.liquidity_net, -1_000_000_000);
+    }
+
+    #[test]
+    fn test_swaps_match_compute_swap_against_evolving_state() {
+        let (mut simulator, _) = test_simulator_with_liquidity();
+
+        for (amount, a_to_b, specified_input) in [
+            (100_000, true, true),
+            (50_000, false, false),
+            (250_000, false, true),
+            (10_000, true, false),
+        ] {
+            let expected = expected_swap(&simulator, amount, a_to_b, specified_input);
+            let result = simulator.swap(amount, 0, a_to_b, specified_input).unwrap();
+
+            assert_eq!(result.token_a, expected.token_a);
+            assert_eq!(result.token_b, expected.token_b);
+            assert_eq!(result.fee_amount, expected.fee_amount);
+            assert_eq!(simulator.fusion_pool().sqrt_price, expected.next_sqrt_price);
+            assert_eq!(simulator.fusion_pool().tick_current_index, sqrt_price_to_tick_index(expected.next_sqrt_price));
+        }
+    }
+
+    #[test]
+    fn test_swap_accrues_fees() {
+        let (mut simulator, mut position) = test_simulator_with_liquidity();
+        let result = simulator.swap(1_000_000, 0, true, true).unwrap();
+        assert_eq!(result.fee_amount, 3000);
+
+        // 10% of the swap fee is taken by the protocol.
+        assert_eq!(
+            simulator.protocol_fees_owed(),
+            CollectFeesQuote {
+                fee_owed_a: 300,
+                fee_owed_b: 0
+            }
+        );
+        assert_eq!(simulator.fusion_pool().fee_growth_global_b, 0);
+
+        // The only position earns the rest, minus rounding.
+        let fees = simulator.collect_fees(&mut position).unwrap();
+        assert_eq!(
+            fees,
+            CollectFeesQuote {
+                fee_owed_a: 2699,
+                fee_owed_b: 0
+            }
+        );
+        assert_eq!(position.fee_owed_a, 0);
+        assert_eq!(simulator.collect_fees(&mut position).unwrap(), CollectFeesQuote::default());
+
+        assert_eq!(
+            simulator.collect_protocol_fees(),
+            CollectFeesQuote {
+                fee_owed_a: 300,
+                fee_owed_b: 0
+            }
+        );
+        assert_eq!(simulator.protocol_fees_owed(), CollectFeesQuote::default());
+    }
+
+    #[test]
+    fn test_swap_crosses_ticks() {
+        let (mut simulator, _) = test_simulator_with_liquidity();
+        let mut position = simulator.open_position(-128, 128).unwrap();
+        simulator.increase_liquidity(&mut position, 1_000_000_000).unwrap();
+        assert_eq!(simulator.fusion_pool().liquidity, 2_000_000_000);
+
+        let result = simulator.swap(100_000_000, 0, true, true).unwrap();
+        assert_eq!(result.ticks_crossed, 1);
+        assert!(simulator.fusion_pool().tick_current_index < -128);
+        assert_eq!(simulator.fusion_pool().liquidity, 1_000_000_000);
+
+        // Out of its range, the position doesn't earn fees anymore.
+        let fees = simulator.collect_fees(&mut position).unwrap();
+        assert!(fees.fee_owed_a > 0);
+        simulator.swap(100_000_000, 0, true, true).unwrap();
+        assert_eq!(simulator.collect_fees(&mut position).unwrap(), CollectFeesQuote::default());
+    }
+
+    #[test]
+    fn test_decrease_liquidity_releases_ticks() {
+        let (mut simulator, mut position) = test_simulator_with_liquidity();
+        simulator.swap(1_000_000, 0, true, true).unwrap();
+
+        let liquidity = position.liquidity;
+        let quote = simulator.decrease_liquidity(&mut position, liquidity).unwrap();
+        assert!(quote.token_est_a > 0 && quote.token_est_b > 0);
+        assert_eq!(position.liquidity, 0);
+        assert_eq!(position.fee_owed_a, 2699);
+        assert_eq!(simulator.fusion_pool().liquidity, 0);
+        assert!(!simulator.tick(-TICK_ARRAY_SPAN).unwrap().initialized);
+        assert!(!simulator.tick(TICK_ARRAY_SPAN).unwrap().initialized);
+
+        assert_eq!(simulator.decrease_liquidity(&mut position, 1), Err(LIQUIDITY_DELTA_EXCEEDS_POSITION_LIQUIDITY));
+        assert_eq!(simulator.increase_liquidity(&mut position, 0), Err(ZERO_LIQUIDITY_DELTA));
+    }
+
+    #[test]
+    fn test_open_limit_order() {
+        let (mut simulator, _) = test_simulator_with_liquidity();
+
+        assert_eq!(simulator.open_limit_order(1_000_000, -640, true), Err(TAKER_ORDER_NOT_SUPPORTED));
+        assert_eq!(simulator.open_limit_order(1_000_000, 640, false), Err(TAKER_ORDER_NOT_SUPPORTED));
+        assert_eq!(simulator.open_limit_order(1_000_000, 641, true), Err(INVALID_TICK_INDEX));
+        assert_eq!(simulator.open_limit_order(0, 640, true), Err(ZERO_TRADABLE_AMOUNT));
+
+        let order = simulator.open_limit_order(1_000_000, 640, true).unwrap();
+        assert_eq!(
+            order,
+            LimitOrderFacade {
+                tick_index: 640,
+                amount: 1_000_000,
+                a_to_b: true,
+                age: 0,
+            }
+        );
+        simulator.open_limit_order(2_000_000, -640, false).unwrap();
+
+        let fusion_pool = simulator.fusion_pool();
+        assert_eq!(fusion_pool.orders_total_amount_a, 1_000_000);
+        assert_eq!(fusion_pool.orders_total_amount_b, 2_000_000);
+
+        let tick = simulator.tick(640).unwrap();
+        assert!(tick.initialized);
+        assert_eq!(tick.open_orders_input, 1_000_000);
+        assert_eq!(tick.liquidity_gross, 0);
+    }
+
+    #[test]
+    fn test_partially_filled_limit_orders() {
+        let (mut simulator, _) = test_simulator_with_liquidity();
+        let mut order_1 = simulator.open_limit_order(1_000_000, 640, true).unwrap();
+        let mut order_2 = simulator.open_limit_order(3_000_000, 640, true).unwrap();
+
+        let expected = expected_swap(&simulator, 35_000_000, false, true);
+        let result = simulator.swap(35_000_000, 0, false, true).unwrap();
+        assert_eq!(result.token_a, expected.token_a);
+        assert!(result.limit_order_amount_out > 0 && result.limit_order_amount_out < 4_000_000);
+        assert_eq!(simulator.fusion_pool().tick_current_index, 640);
+
+        let tick = simulator.tick(640).unwrap();
+        assert_eq!(tick.age, 1);
+        assert_eq!(tick.open_orders_input, 0);
+        assert_eq!(tick.part_filled_orders_input, 4_000_000);
+        assert_eq!(tick.part_filled_orders_remaining_input, 4_000_000 - result.limit_order_amount_out);
+        assert_eq!(simulator.fusion_pool().orders_filled_amount_a, result.limit_order_amount_out);
+        assert!(simulator.fusion_pool().olp_fee_owed_b > 0);
+
+        // The withdrawn amounts are the ones quoted against the current state.
+        for order in [&mut order_1, &mut order_2] {
+            let amount = order.amount;
+            let expected_quote =
+                decrease_limit_order_quote(simulator.fusion_pool(), *order, simulator.tick(640).unwrap(), amount, None, None).unwrap();
+            let quote = simulator.decrease_limit_order(order, amount).unwrap();
+            assert_eq!(quote, expected_quote);
+            assert!(quote.amount_out_a > 0 && quote.amount_out_b > 0);
+            assert_eq!(order.amount, 0);
+        }
+
+        let fusion_pool = simulator.fusion_pool();
+        assert_eq!(fusion_pool.orders_total_amount_a, 0);
+        assert_eq!(fusion_pool.orders_filled_amount_a, 0);
+        let tick = simulator.tick(640).unwrap();
+        assert_eq!(tick.part_filled_orders_input, 0);
+        assert_eq!(tick.part_filled_orders_remaining_input, 0);
+        assert!(!tick.initialized);
+        assert_eq!(tick.age, 1);
+    }
+
+    #[test]
+    fn test_fulfilled_limit_orders() {
+        let (mut simulator, _) = test_simulator_with_liquidity();
+        let mut order = simulator.open_limit_order(1_000_000, -640, false).unwrap();
+
+        let result = simulator.swap(100_000_000, 0, true, true).unwrap();
+        assert_eq!(result.limit_order_amount_out, 1_000_000);
+        assert!(simulator.fusion_pool().tick_current_index < -640);
+
+        let tick = simulator.tick(-640).unwrap();
+        assert_eq!(tick.age, 2);
+        assert_eq!(tick.open_orders_input, 0);
+        assert_eq!(tick.fulfilled_b_to_a_orders_input, 1_000_000);
+        assert_eq!(simulator.fusion_pool().orders_filled_amount_b, 1_000_000);
+
+        // A new order on the same tick is open, while the fulfilled one can be decreased partially.
+        let new_order = simulator.open_limit_order(500_000, -640, true).unwrap();
+        assert_eq!(new_order.age, 2);
+        assert_eq!(simulator.tick(-640).unwrap().open_orders_input, 500_000);
+
+        let quote = simulator.decrease_limit_order(&mut order, 400_000).unwrap();
+        assert_eq!(quote.amount_out_b, 0);
+        assert!(quote.amount_out_a > 400_000 && quote.reward_a > 0);
+        assert_eq!(order.amount, 600_000);
+        assert_eq!(simulator.tick(-640).unwrap().fulfilled_b_to_a_orders_input, 600_000);
+
+        assert_eq!(simulator.decrease_limit_order(&mut order, 600_001), Err(AMOUNT_EXCEEDS_LIMIT_ORDER_INPUT_AMOUNT));
+        simulator.decrease_limit_order(&mut order, 600_000).unwrap();
+
+        let fusion_pool = simulator.fusion_pool();
+        assert_eq!(fusion_pool.orders_total_amount_b, 0);
+        assert_eq!(fusion_pool.orders_filled_amount_b, 0);
+        assert_eq!(fusion_pool.olp_fee_owed_a, 0);
+    }
+
+    #[test]
+    fn test_failed_action_leaves_state_unchanged() {
+        let (mut simulator, mut position) = test_simulator_with_liquidity();
+        let before = simulator.clone();
+
+        // The swap runs out of tick arrays.
+        assert_eq!(simulator.swap(u64::MAX / 2, 0, true, true), Err(INVALID_TICK_ARRAY_SEQUENCE));
+        assert_eq!(simulator, before);
+
+        // The tick array of the upper tick is not loaded.
+        let mut out_of_range_position = simulator.open_position(0, 2 * TICK_ARRAY_SPAN).unwrap();
+        assert_eq!(simulator.increase_liquidity(&mut out_of_range_position, 1_000), Err(TICK_INDEX_OUT_OF_BOUNDS));
+        assert_eq!(simulator, before);
+        assert_eq!(out_of_range_position.liquidity, 0);
+
+        let liquidity = position.liquidity + 1;
+        assert_eq!(simulator.decrease_liquidity(&mut position, liquidity), Err(LIQUIDITY_DELTA_EXCEEDS_POSITION_LIQUIDITY));
+        assert_eq!(simulator, before);
+    }
+}
@@ -0,0 +1,114 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::{CoreError, ARITHMETIC_OVERFLOW, INVALID_DECIMAL_PRICE};
+use core::fmt;
+use core::str::FromStr;
+
+/// A fixed-point decimal price equal to `mantissa / 10^scale`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct DecimalPrice {
+    pub mantissa: u128,
+    /// The number of fractional digits.
+    pub scale: u8,
+}
+
+impl DecimalPrice {
+    pub fn new(mantissa: u128, scale: u8) -> Self {
+        Self { mantissa, scale }
+    }
+
+    /// Returns the price with a different number of fractional digits. The price is rounded down if digits are dropped.
+    pub fn with_scale(&self, scale: u8) -> Result<Self, CoreError> {
+        let mut mantissa = self.mantissa;
+        for _ in scale..self.scale {
+            mantissa /= 10;
+        }
+        for _ in self.scale..scale {
+            mantissa = mantissa.checked_mul(10).ok_or(ARITHMETIC_OVERFLOW)?;
+        }
+        Ok(Self { mantissa, scale })
+    }
+}
+
+impl FromStr for DecimalPrice {
+    type Err = CoreError;
+
+    /// Parses a non-negative decimal number such as `"123.456"`. The scale is the number of fractional digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (integer_part, fractional_part) = s.split_once('.').unwrap_or((s, ""));
+        if integer_part.is_empty() && fractional_part.is_empty() {
+            return Err(INVALID_DECIMAL_PRICE);
+        }
+
+        let scale: u8 = fractional_part.len().try_into().map_err(|_| INVALID_DECIMAL_PRICE)?;
+        let mut mantissa = 0u128;
+        for c in integer_part.chars().chain(fractional_part.chars()) {
+            let digit = c.to_digit(10).ok_or(INVALID_DECIMAL_PRICE)?;
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(digit as u128))
+                .ok_or(ARITHMETIC_OVERFLOW)?;
+        }
+
+        Ok(Self { mantissa, scale })
+    }
+}
+
+impl fmt::Display for DecimalPrice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let divisor = 10u128.checked_pow(self.scale as u32);
+        match divisor {
+            Some(divisor) if self.scale > 0 => {
+                write!(f, "{}.{:0width$}", self.mantissa / divisor, self.mantissa % divisor, width = self.scale as usize)
+            }
+            Some(_) => write!(f, "{}", self.mantissa),
+            // The scale exceeds the number of digits of u128, so the integer part is zero.
+            None => write!(f, "0.{:0>width$}", self.mantissa, width = self.scale as usize),
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("123.456".parse(), Ok(DecimalPrice::new(123456, 3)));
+        assert_eq!("0.000100".parse(), Ok(DecimalPrice::new(100, 6)));
+        assert_eq!("42".parse(), Ok(DecimalPrice::new(42, 0)));
+        assert_eq!(".5".parse(), Ok(DecimalPrice::new(5, 1)));
+        assert_eq!("7.".parse(), Ok(DecimalPrice::new(7, 0)));
+        assert_eq!("".parse::<DecimalPrice>(), Err(INVALID_DECIMAL_PRICE));
+        assert_eq!(".".parse::<DecimalPrice>(), Err(INVALID_DECIMAL_PRICE));
+        assert_eq!("-1.5".parse::<DecimalPrice>(), Err(INVALID_DECIMAL_PRICE));
+        assert_eq!("1e5".parse::<DecimalPrice>(), Err(INVALID_DECIMAL_PRICE));
+        assert_eq!("1.2.3".parse::<DecimalPrice>(), Err(INVALID_DECIMAL_PRICE));
+        assert_eq!("1000000000000000000000000000000000000000".parse::<DecimalPrice>(), Err(ARITHMETIC_OVERFLOW));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(DecimalPrice::new(123456, 3).to_string(), "123.456");
+        assert_eq!(DecimalPrice::new(100, 6).to_string(), "0.000100");
+        assert_eq!(DecimalPrice::new(42, 0).to_string(), "42");
+        assert_eq!(DecimalPrice::new(5, 40).to_string(), "0.0000000000000000000000000000000000000005");
+        for s in ["123.456", "0.000100", "42", "0.0000000000000000000000000000000000000005"] {
+            assert_eq!(s.parse::<DecimalPrice>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_with_scale() {
+        let price = DecimalPrice::new(123456, 3);
+        assert_eq!(price.with_scale(5), Ok(DecimalPrice::new(12345600, 5)));
+        assert_eq!(price.with_scale(1), Ok(DecimalPrice::new(1234, 1)));
+        assert_eq!(price.with_scale(0), Ok(DecimalPrice::new(123, 0)));
+        assert_eq!(price.with_scale(60), Err(ARITHMETIC_OVERFLOW));
+    }
+}
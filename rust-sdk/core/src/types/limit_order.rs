@@ -25,3 +25,28 @@ pub struct LimitOrderDecreaseQuote {
     pub reward_a: u64,
     pub reward_b: u64,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub enum LimitOrderStatus {
+    Open,
+    PartiallyFilled,
+    Filled,
+    OutOfSync,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct LimitOrderFillQuote {
+    pub status: LimitOrderStatus,
+    /// The filled part of the limit order input amount.
+    pub filled_amount: u64,
+    /// The filled part of the limit order input amount in basis points.
+    pub filled_bps: u16,
+    /// The unfilled input amount returned to the owner if the order is closed.
+    pub claimable_input: u64,
+    /// The output amount of the filled part of the order, reward excluded.
+    pub claimable_output: u64,
+    /// The pending reward paid in the output token.
+    pub reward: u64,
+}
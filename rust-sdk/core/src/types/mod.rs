@@ -8,6 +8,7 @@
 // See the LICENSE file in the project root for license information.
 //
 
+mod decimal_price;
 mod fees;
 mod limit_order;
 mod liquidity;
@@ -21,6 +22,7 @@ mod token;
 #[cfg(feature = "wasm")]
 mod u64;
 
+pub use decimal_price::*;
 pub use fees::*;
 pub use limit_order::*;
 pub use liquidity::*;
@@ -30,4 +30,6 @@ pub struct FusionPoolFacade {
     pub orders_filled_amount_b: u64,
     pub olp_fee_owed_a: u64,
     pub olp_fee_owed_b: u64,
+    pub ma_sqrt_price: u128,
+    pub last_swap_timestamp: u64,
 }
@@ -40,3 +40,26 @@ pub struct PositionFacade {
     pub fee_growth_checkpoint_b: u128,
     pub fee_owed_b: u64,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct FeeGrowthSnapshot {
+    pub fee_growth_inside_a: u128,
+    pub fee_growth_inside_b: u128,
+    pub timestamp: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct PositionAnalytics {
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub value_in_a: u128,
+    pub value_in_b: u128,
+    pub fee_owed_a: u64,
+    pub fee_owed_b: u64,
+    pub hold_amount_a: u64,
+    pub hold_amount_b: u64,
+    pub hold_value_in_b: u128,
+    pub impermanent_loss_bps: i64,
+}
@@ -13,6 +13,12 @@
 #[cfg(feature = "wasm")]
 use fusionamm_macros::wasm_expose;
 
+/// A quote for a swap with an exact input amount.
+///
+/// Besides the amounts, the quote breaks down how the swap executes. `execution_sqrt_price` is the average price of the
+/// amounts the user pays and receives, including the trade fee and transfer fees, and `price_impact_bps` is its deviation
+/// from `spot_sqrt_price`. The `liquidity_*` and `limit_order_*` amounts split the swap between concentrated liquidity
+/// and limit orders and exclude the trade fee.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub struct ExactInSwapQuote {
@@ -21,8 +27,19 @@ pub struct ExactInSwapQuote {
     pub token_min_out: u64,
     pub trade_fee: u64,
     pub next_sqrt_price: u128,
+    pub spot_sqrt_price: u128,
+    pub execution_sqrt_price: u128,
+    pub price_impact_bps: u32,
+    pub liquidity_amount_in: u64,
+    pub liquidity_amount_out: u64,
+    pub limit_order_amount_in: u64,
+    pub limit_order_amount_out: u64,
+    pub ticks_crossed: u32,
+    pub first_tick_array_start_index: i32,
+    pub last_tick_array_start_index: i32,
 }
 
+/// A quote for a swap with an exact output amount. The execution breakdown is described on `ExactInSwapQuote`.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub struct ExactOutSwapQuote {
@@ -31,4 +48,14 @@ pub struct ExactOutSwapQuote {
     pub token_max_in: u64,
     pub trade_fee: u64,
     pub next_sqrt_price: u128,
+    pub spot_sqrt_price: u128,
+    pub execution_sqrt_price: u128,
+    pub price_impact_bps: u32,
+    pub liquidity_amount_in: u64,
+    pub liquidity_amount_out: u64,
+    pub limit_order_amount_in: u64,
+    pub limit_order_amount_out: u64,
+    pub ticks_crossed: u32,
+    pub first_tick_array_start_index: i32,
+    pub last_tick_array_start_index: i32,
 }
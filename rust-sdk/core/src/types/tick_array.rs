@@ -9,6 +9,7 @@
 //
 
 use crate::types::TickArrayFacade;
+use alloc::{vec, vec::Vec};
 
 #[cfg(not(feature = "wasm"))]
 #[derive(Debug, Clone, PartialEq, Eq)]
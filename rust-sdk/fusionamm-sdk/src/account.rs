@@ -13,8 +13,6 @@ use serde_json::from_value;
 use solana_account_decoder::UiAccountData;
 use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
 use solana_program::pubkey::Pubkey;
-use solana_program::rent::Rent;
-use solana_program::sysvar::SysvarId;
 use std::{error::Error, str::FromStr};
 
 #[derive(Debug, Clone)]
@@ -73,9 +71,3 @@ pub(crate) async fn get_token_accounts_for_owner(
     }
     Ok(token_accounts)
 }
-
-pub(crate) async fn get_rent(rpc: &RpcClient) -> Result<Rent, Box<dyn Error>> {
-    let rent = rpc.get_account(&Rent::id()).await?;
-    let rent: Rent = bincode::deserialize(&rent.data)?;
-    Ok(rent)
-}
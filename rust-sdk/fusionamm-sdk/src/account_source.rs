@@ -0,0 +1,361 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::OfflineState;
+use solana_account::Account;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::sysvar::SysvarId;
+use std::{collections::HashMap, error::Error, future::Future, sync::Mutex};
+
+/// A source of on-chain state used by the instruction builders.
+///
+/// The SDK is generic over this trait, so instructions can be built from a live RPC node,
+/// from an in-memory snapshot of indexed state or through a cache that avoids redundant round-trips.
+pub trait AccountSource: Sync {
+    /// Fetches a single account. Returns an error if the account does not exist.
+    fn get_account(&self, address: &Pubkey) -> impl Future<Output = Result<Account, Box<dyn Error>>> + Send;
+
+    /// Fetches multiple accounts. Accounts that do not exist are returned as `None`.
+    fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> impl Future<Output = Result<Vec<Option<Account>>, Box<dyn Error>>> + Send;
+
+    /// Fetches the current epoch.
+    fn get_epoch(&self) -> impl Future<Output = Result<u64, Box<dyn Error>>> + Send;
+
+    /// Fetches the rent sysvar.
+    fn get_rent(&self) -> impl Future<Output = Result<Rent, Box<dyn Error>>> + Send;
+
+    /// Fetches the current slot.
+    fn get_slot(&self) -> impl Future<Output = Result<u64, Box<dyn Error>>> + Send;
+}
+
+impl AccountSource for RpcClient {
+    async fn get_account(&self, address: &Pubkey) -> Result<Account, Box<dyn Error>> {
+        Ok(RpcClient::get_account(self, address).await?)
+    }
+
+    async fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>, Box<dyn Error>> {
+        Ok(RpcClient::get_multiple_accounts(self, addresses).await?)
+    }
+
+    async fn get_epoch(&self) -> Result<u64, Box<dyn Error>> {
+        Ok(self.get_epoch_info().await?.epoch)
+    }
+
+    async fn get_rent(&self) -> Result<Rent, Box<dyn Error>> {
+        let rent = RpcClient::get_account(self, &Rent::id()).await?;
+        let rent: Rent = bincode::deserialize(&rent.data)?;
+        Ok(rent)
+    }
+
+    async fn get_slot(&self) -> Result<u64, Box<dyn Error>> {
+        Ok(RpcClient::get_slot(self).await?)
+    }
+}
+
+impl<T: AccountSource + ?Sized> AccountSource for &T {
+    async fn get_account(&self, address: &Pubkey) -> Result<Account, Box<dyn Error>> {
+        (**self).get_account(address).await
+    }
+
+    async fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>, Box<dyn Error>> {
+        (**self).get_multiple_accounts(addresses).await
+    }
+
+    async fn get_epoch(&self) -> Result<u64, Box<dyn Error>> {
+        (**self).get_epoch().await
+    }
+
+    async fn get_rent(&self) -> Result<Rent, Box<dyn Error>> {
+        (**self).get_rent().await
+    }
+
+    async fn get_slot(&self) -> Result<u64, Box<dyn Error>> {
+        (**self).get_slot().await
+    }
+}
+
+/// An in-memory snapshot of on-chain state.
+///
+/// Useful for building instructions from indexed state and for deterministic tests.
+#[derive(Debug, Clone, Default)]
+pub struct AccountSnapshot {
+    /// The accounts of the snapshot by address.
+    pub accounts: HashMap<Pubkey, Account>,
+
+    /// The epoch of the snapshot.
+    pub epoch: u64,
+
+    /// The slot of the snapshot.
+    pub slot: u64,
+
+    /// The rent sysvar of the snapshot.
+    pub rent: Rent,
+}
+
+impl AccountSnapshot {
+    /// Creates an empty snapshot.
+    pub fn new(epoch: u64, slot: u64, rent: Rent) -> Self {
+        Self {
+            accounts: HashMap::new(),
+            epoch,
+            slot,
+            rent,
+        }
+    }
+
+    /// Adds an account to the snapshot, returning the account previously stored at the address.
+    pub fn insert(&mut self, address: Pubkey, account: Account) -> Option<Account> {
+        self.accounts.insert(address, account)
+    }
+
+    /// Captures the given accounts together with the epoch, slot and rent of another source.
+    /// Accounts that do not exist are left out of the snapshot.
+    pub async fn fetch(source: &impl AccountSource, addresses: &[Pubkey]) -> Result<Self, Box<dyn Error>> {
+        let accounts = source.get_multiple_accounts(addresses).await?;
+        let mut snapshot = Self::new(source.get_epoch().await?, source.get_slot().await?, source.get_rent().await?);
+        for (address, account) in addresses.iter().zip(accounts) {
+            if let Some(account) = account {
+                snapshot.insert(*address, account);
+            }
+        }
+        Ok(snapshot)
+    }
+}
+
+impl AccountSource for AccountSnapshot {
+    async fn get_account(&self, address: &Pubkey) -> Result<Account, Box<dyn Error>> {
+        Ok(self.accounts.get(address).cloned().ok_or(format!("Account {} not found", address))?)
+    }
+
+    async fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>, Box<dyn Error>> {
+        Ok(addresses.iter().map(|address| self.accounts.get(address).cloned()).collect())
+    }
+
+    async fn get_epoch(&self) -> Result<u64, Box<dyn Error>> {
+        Ok(self.epoch)
+    }
+
+    async fn get_rent(&self) -> Result<Rent, Box<dyn Error>> {
+        Ok(self.rent)
+    }
+
+    async fn get_slot(&self) -> Result<u64, Box<dyn Error>> {
+        Ok(self.slot)
+    }
+}
+
+/// An account source that caches the accounts, epoch and rent fetched from another source.
+///
+/// The slot is always fetched from the underlying source. The cache is never refreshed on its own,
+/// so it is meant to be short-lived, e.g. for building a batch of instructions.
+#[derive(Debug)]
+pub struct CachedAccountSource<S: AccountSource> {
+    source: S,
+    accounts: Mutex<HashMap<Pubkey, Option<Account>>>,
+    epoch: Mutex<Option<u64>>,
+    rent: Mutex<Option<Rent>>,
+}
+
+impl<S: AccountSource> CachedAccountSource<S> {
+    /// Creates an empty cache on top of a source.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            accounts: Mutex::new(HashMap::new()),
+            epoch: Mutex::new(None),
+            rent: Mutex::new(None),
+        }
+    }
+
+    /// Removes an account from the cache so that it is fetched again on the next access.
+    pub fn invalidate(&self, address: &Pubkey) -> Result<(), Box<dyn Error>> {
+        self.accounts.lock().map_err(|_| "Account cache is poisoned")?.remove(address);
+        Ok(())
+    }
+
+    /// Removes all accounts, the epoch and the rent from the cache.
+    pub fn clear(&self) -> Result<(), Box<dyn Error>> {
+        self.accounts.lock().map_err(|_| "Account cache is poisoned")?.clear();
+        *self.epoch.lock().map_err(|_| "Account cache is poisoned")? = None;
+        *self.rent.lock().map_err(|_| "Account cache is poisoned")? = None;
+        Ok(())
+    }
+
+    /// Consumes the cache and returns the accounts, epoch and rent fetched through it as an `OfflineState`.
+    pub fn into_offline_state(self) -> Result<OfflineState, Box<dyn Error>> {
+        Ok(OfflineState {
+            accounts: self.accounts.into_inner().map_err(|_| "Account cache is poisoned")?,
+            epoch: self.epoch.into_inner().map_err(|_| "Account cache is poisoned")?,
+            slot: None,
+            rent: self.rent.into_inner().map_err(|_| "Account cache is poisoned")?,
+        })
+    }
+
+    fn cached_account(&self, address: &Pubkey) -> Result<Option<Option<Account>>, Box<dyn Error>> {
+        Ok(self.accounts.lock().map_err(|_| "Account cache is poisoned")?.get(address).cloned())
+    }
+}
+
+impl<S: AccountSource> AccountSource for CachedAccountSource<S> {
+    async fn get_account(&self, address: &Pubkey) -> Result<Account, Box<dyn Error>> {
+        let cached_account = self.cached_account(address)?;
+        match cached_account {
+            Some(Some(account)) => Ok(account),
+            Some(None) => Err(format!("Account {} not found", address).into()),
+            None => {
+                let account = self.source.get_account(address).await?;
+                self.accounts
+                    .lock()
+                    .map_err(|_| "Account cache is poisoned")?
+                    .insert(*address, Some(account.clone()));
+                Ok(account)
+            }
+        }
+    }
+
+    async fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>, Box<dyn Error>> {
+        let missing_addresses: Vec<Pubkey> = {
+            let accounts = self.accounts.lock().map_err(|_| "Account cache is poisoned")?;
+            addresses.iter().filter(|address| !accounts.contains_key(address)).copied().collect()
+        };
+
+        if !missing_addresses.is_empty() {
+            let missing_accounts = self.source.get_multiple_accounts(&missing_addresses).await?;
+            let mut accounts = self.accounts.lock().map_err(|_| "Account cache is poisoned")?;
+            for (address, account) in missing_addresses.into_iter().zip(missing_accounts) {
+                accounts.insert(address, account);
+            }
+        }
+
+        let accounts = self.accounts.lock().map_err(|_| "Account cache is poisoned")?;
+        Ok(addresses.iter().map(|address| accounts.get(address).cloned().flatten()).collect())
+    }
+
+    async fn get_epoch(&self) -> Result<u64, Box<dyn Error>> {
+        if let Some(epoch) = *self.epoch.lock().map_err(|_| "Account cache is poisoned")? {
+            return Ok(epoch);
+        }
+        let epoch = self.source.get_epoch().await?;
+        *self.epoch.lock().map_err(|_| "Account cache is poisoned")? = Some(epoch);
+        Ok(epoch)
+    }
+
+    async fn get_rent(&self) -> Result<Rent, Box<dyn Error>> {
+        if let Some(rent) = *self.rent.lock().map_err(|_| "Account cache is poisoned")? {
+            return Ok(rent);
+        }
+        let rent = self.source.get_rent().await?;
+        *self.rent.lock().map_err(|_| "Account cache is poisoned")? = Some(rent);
+        Ok(rent)
+    }
+
+    async fn get_slot(&self) -> Result<u64, Box<dyn Error>> {
+        self.source.get_slot().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program_test::tokio;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingSource {
+        snapshot: AccountSnapshot,
+        requests: AtomicUsize,
+    }
+
+    impl AccountSource for CountingSource {
+        async fn get_account(&self, address: &Pubkey) -> Result<Account, Box<dyn Error>> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.snapshot.get_account(address).await
+        }
+
+        async fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>, Box<dyn Error>> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.snapshot.get_multiple_accounts(addresses).await
+        }
+
+        async fn get_epoch(&self) -> Result<u64, Box<dyn Error>> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.snapshot.get_epoch().await
+        }
+
+        async fn get_rent(&self) -> Result<Rent, Box<dyn Error>> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.snapshot.get_rent().await
+        }
+
+        async fn get_slot(&self) -> Result<u64, Box<dyn Error>> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.snapshot.get_slot().await
+        }
+    }
+
+    fn test_account(lamports: u64) -> Account {
+        Account {
+            lamports,
+            ..Account::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_account_snapshot() {
+        let address = Pubkey::new_unique();
+        let missing_address = Pubkey::new_unique();
+        let mut snapshot = AccountSnapshot::new(100, 1000, Rent::default());
+        snapshot.insert(address, test_account(5));
+
+        assert_eq!(snapshot.get_account(&address).await.unwrap().lamports, 5);
+        assert!(snapshot.get_account(&missing_address).await.is_err());
+        let accounts = snapshot.get_multiple_accounts(&[missing_address, address]).await.unwrap();
+        assert!(accounts[0].is_none());
+        assert_eq!(accounts[1].as_ref().unwrap().lamports, 5);
+        assert_eq!(snapshot.get_epoch().await.unwrap(), 100);
+        assert_eq!(snapshot.get_slot().await.unwrap(), 1000);
+
+        let copy = AccountSnapshot::fetch(&snapshot, &[address, missing_address]).await.unwrap();
+        assert_eq!(copy.accounts.len(), 1);
+        assert_eq!(copy.epoch, 100);
+    }
+
+    #[tokio::test]
+    async fn test_cached_account_source() {
+        let address = Pubkey::new_unique();
+        let missing_address = Pubkey::new_unique();
+        let mut snapshot = AccountSnapshot::new(100, 1000, Rent::default());
+        snapshot.insert(address, test_account(5));
+        let source = CountingSource {
+            snapshot,
+            requests: AtomicUsize::new(0),
+        };
+        let cache = CachedAccountSource::new(&source);
+
+        cache.get_multiple_accounts(&[address, missing_address]).await.unwrap();
+        cache.get_multiple_accounts(&[address, missing_address]).await.unwrap();
+        assert_eq!(cache.get_account(&address).await.unwrap().lamports, 5);
+        assert!(cache.get_account(&missing_address).await.is_err());
+        assert_eq!(source.requests.load(Ordering::SeqCst), 1);
+
+        cache.get_epoch().await.unwrap();
+        cache.get_epoch().await.unwrap();
+        cache.get_rent().await.unwrap();
+        cache.get_rent().await.unwrap();
+        assert_eq!(source.requests.load(Ordering::SeqCst), 3);
+
+        cache.invalidate(&address).unwrap();
+        cache.get_account(&address).await.unwrap();
+        assert_eq!(source.requests.load(Ordering::SeqCst), 4);
+
+        cache.clear().unwrap();
+        cache.get_multiple_accounts(&[address]).await.unwrap();
+        cache.get_epoch().await.unwrap();
+        assert_eq!(source.requests.load(Ordering::SeqCst), 6);
+    }
+}
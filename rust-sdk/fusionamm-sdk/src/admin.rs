@@ -0,0 +1,821 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy};
+use crate::{AccountSource, FUNDER};
+use fusionamm_client::{
+    get_fusion_pools_config_address, get_token_badge_address, CollectProtocolFees, CollectProtocolFeesInstructionArgs, DeleteTokenBadge, FusionPool,
+    FusionPoolsConfig, InitializeConfig, InitializeConfigInstructionArgs, InitializeTokenBadge, ResetPoolPrice, ResetPoolPriceInstructionArgs,
+    SetCollectProtocolFeesAuthority, SetDefaultProtocolFeeRate, SetDefaultProtocolFeeRateInstructionArgs, SetFeeAuthority, SetFeeRate,
+    SetFeeRateInstructionArgs, SetProtocolFeeRate, SetProtocolFeeRateInstructionArgs, SetTokenBadgeAuthority,
+};
+use fusionamm_core::{price_to_sqrt_price, try_apply_transfer_fee, MAX_FEE_RATE, MAX_PROTOCOL_FEE_RATE, MAX_SQRT_PRICE, MIN_SQRT_PRICE};
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Mint;
+use std::error::Error;
+
+/// The authorities stored in the `FusionPoolsConfig` account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigAuthority {
+    /// Can change fee rates, reset pool prices and change the fee and token badge authorities.
+    Fee,
+    /// Can collect the protocol fees and change the collect protocol fees authority.
+    CollectProtocolFees,
+    /// Can initialize and delete token badges.
+    TokenBadge,
+}
+
+/// Errors returned by the administration instruction builders before anything is sent.
+///
+/// The errors are returned boxed as `Box<dyn Error>` and can be recovered with `downcast_ref::<AdminError>()`.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AdminError {
+    /// The signer is not the authority required by the instruction.
+    #[error("{signer} is not the {authority:?} authority ({expected})")]
+    InvalidAuthority {
+        authority: ConfigAuthority,
+        expected: Pubkey,
+        signer: Pubkey,
+    },
+
+    /// The fee rate exceeds `MAX_FEE_RATE`.
+    #[error("Fee rate {0} exceeds the maximum of {MAX_FEE_RATE}")]
+    FeeRateMaxExceeded(u16),
+
+    /// The protocol fee rate exceeds `MAX_PROTOCOL_FEE_RATE`.
+    #[error("Protocol fee rate {0} exceeds the maximum of {MAX_PROTOCOL_FEE_RATE}")]
+    ProtocolFeeRateMaxExceeded(u16),
+
+    /// The sqrt price is out of the `[MIN_SQRT_PRICE, MAX_SQRT_PRICE]` range.
+    #[error("Sqrt price {0} is out of bounds")]
+    SqrtPriceOutOfBounds(u128),
+
+    /// The FusionPoolsConfig account is already initialized.
+    #[error("FusionPoolsConfig is already initialized")]
+    ConfigAlreadyInitialized,
+}
+
+/// Represents the instructions and amounts for collecting the protocol fees of a pool.
+#[derive(Debug)]
+pub struct CollectProtocolFeesInstruction {
+    /// The protocol fees of token A owed by the pool.
+    pub protocol_fee_owed_a: u64,
+
+    /// The protocol fees of token B owed by the pool.
+    pub protocol_fee_owed_b: u64,
+
+    /// The amount of token A received after the transfer fee is applied.
+    pub amount_a: u64,
+
+    /// The amount of token B received after the transfer fee is applied.
+    pub amount_b: u64,
+
+    /// A vector of `Instruction` objects required to collect the protocol fees.
+    pub instructions: Vec<Instruction>,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+}
+
+fn get_authority(authority: Option<Pubkey>) -> Result<Pubkey, Box<dyn Error>> {
+    let authority = authority.unwrap_or(*FUNDER.try_lock()?);
+    if authority == Pubkey::default() {
+        return Err("Authority must be provided".into());
+    }
+    Ok(authority)
+}
+
+fn validate_fee_rate(fee_rate: u16) -> Result<(), AdminError> {
+    if fee_rate > MAX_FEE_RATE {
+        return Err(AdminError::FeeRateMaxExceeded(fee_rate));
+    }
+    Ok(())
+}
+
+fn validate_protocol_fee_rate(protocol_fee_rate: u16) -> Result<(), AdminError> {
+    if protocol_fee_rate > MAX_PROTOCOL_FEE_RATE {
+        return Err(AdminError::ProtocolFeeRateMaxExceeded(protocol_fee_rate));
+    }
+    Ok(())
+}
+
+fn validate_authority(config: &FusionPoolsConfig, authority: ConfigAuthority, signer: Pubkey) -> Result<(), AdminError> {
+    let expected = match authority {
+        ConfigAuthority::Fee => config.fee_authority,
+        ConfigAuthority::CollectProtocolFees => config.collect_protocol_fees_authority,
+        ConfigAuthority::TokenBadge => config.token_badge_authority,
+    };
+    if expected != signer {
+        return Err(AdminError::InvalidAuthority { authority, expected, signer });
+    }
+    Ok(())
+}
+
+/// Fetches the `FusionPoolsConfig` account and checks that `signer` is its `authority`.
+async fn fetch_config_for_authority(
+    rpc: &impl AccountSource,
+    authority: ConfigAuthority,
+    signer: Pubkey,
+) -> Result<(Pubkey, FusionPoolsConfig), Box<dyn Error>> {
+    let config_address = get_fusion_pools_config_address()?.0;
+    let config_info = rpc.get_account(&config_address).await?;
+    let config = FusionPoolsConfig::from_bytes(&config_info.data)?;
+    validate_authority(&config, authority, signer)?;
+    Ok((config_address, config))
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to initialize the `FusionPoolsConfig` account.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `fee_authority` - The authority who can change the fee rates.
+/// * `collect_protocol_fees_authority` - The authority who can collect the protocol fees.
+/// * `token_badge_authority` - The authority who can initialize and delete token badges.
+/// * `default_protocol_fee_rate` - The protocol fee rate of new pools, in basis points.
+/// * `funder` - An optional public key of the funder. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the vector of `Instruction` objects required to initialize the config.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `funder` account is invalid or missing.
+/// - The config is already initialized or the protocol fee rate is too high (`AdminError`).
+/// - Any RPC request to the blockchain fails.
+pub async fn initialize_config_instructions(
+    rpc: &impl AccountSource,
+    fee_authority: Pubkey,
+    collect_protocol_fees_authority: Pubkey,
+    token_badge_authority: Pubkey,
+    default_protocol_fee_rate: u16,
+    funder: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let funder = get_authority(funder)?;
+    validate_protocol_fee_rate(default_protocol_fee_rate)?;
+
+    let config_address = get_fusion_pools_config_address()?.0;
+    if rpc.get_multiple_accounts(&[config_address]).await?[0].is_some() {
+        return Err(AdminError::ConfigAlreadyInitialized.into());
+    }
+
+    Ok(vec![InitializeConfig {
+        fusion_pools_config: config_address,
+        funder,
+        system_program: solana_program::system_program::id(),
+    }
+    .instruction(InitializeConfigInstructionArgs {
+        fee_authority,
+        collect_protocol_fees_authority,
+        token_badge_authority,
+        default_protocol_fee_rate,
+    })])
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to set the fee rate of a pool.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `pool_address` - The public key of the pool.
+/// * `fee_rate` - The new fee rate, in hundredths of a basis point. The maximum value is `MAX_FEE_RATE`.
+/// * `authority` - An optional public key of the fee authority. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the vector of `Instruction` objects required to set the fee rate.
+/// The vector is empty if the pool already has the requested fee rate.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` is missing or isn't the fee authority (`AdminError`).
+/// - The fee rate is too high (`AdminError`).
+/// - The config or pool accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn set_fee_rate_instructions(
+    rpc: &impl AccountSource,
+    pool_address: Pubkey,
+    fee_rate: u16,
+    authority: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let authority = get_authority(authority)?;
+    validate_fee_rate(fee_rate)?;
+    let (config_address, _) = fetch_config_for_authority(rpc, ConfigAuthority::Fee, authority).await?;
+
+    let pool_info = rpc.get_account(&pool_address).await?;
+    let pool = FusionPool::from_bytes(&pool_info.data)?;
+    if pool.fee_rate == fee_rate {
+        return Ok(vec![]);
+    }
+
+    Ok(vec![SetFeeRate {
+        fusion_pools_config: config_address,
+        fusion_pool: pool_address,
+        fee_authority: authority,
+    }
+    .instruction(SetFeeRateInstructionArgs { fee_rate })])
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to set the protocol fee rate of a pool.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `pool_address` - The public key of the pool.
+/// * `protocol_fee_rate` - The new protocol fee rate, in basis points. The maximum value is `MAX_PROTOCOL_FEE_RATE`.
+/// * `authority` - An optional public key of the fee authority. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the vector of `Instruction` objects required to set the protocol fee rate.
+/// The vector is empty if the pool already has the requested protocol fee rate.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` is missing or isn't the fee authority (`AdminError`).
+/// - The protocol fee rate is too high (`AdminError`).
+/// - The config or pool accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn set_protocol_fee_rate_instructions(
+    rpc: &impl AccountSource,
+    pool_address: Pubkey,
+    protocol_fee_rate: u16,
+    authority: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let authority = get_authority(authority)?;
+    validate_protocol_fee_rate(protocol_fee_rate)?;
+    let (config_address, _) = fetch_config_for_authority(rpc, ConfigAuthority::Fee, authority).await?;
+
+    let pool_info = rpc.get_account(&pool_address).await?;
+    let pool = FusionPool::from_bytes(&pool_info.data)?;
+    if pool.protocol_fee_rate == protocol_fee_rate {
+        return Ok(vec![]);
+    }
+
+    Ok(vec![SetProtocolFeeRate {
+        fusion_pools_config: config_address,
+        fusion_pool: pool_address,
+        fee_authority: authority,
+    }
+    .instruction(SetProtocolFeeRateInstructionArgs { protocol_fee_rate })])
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to set the protocol fee rate of new pools.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `default_protocol_fee_rate` - The new default protocol fee rate, in basis points. The maximum value is `MAX_PROTOCOL_FEE_RATE`.
+/// * `authority` - An optional public key of the fee authority. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the vector of `Instruction` objects required to set the default protocol fee rate.
+/// The vector is empty if the config already has the requested default protocol fee rate.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` is missing or isn't the fee authority (`AdminError`).
+/// - The protocol fee rate is too high (`AdminError`).
+/// - The config account is not found or has invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn set_default_protocol_fee_rate_instructions(
+    rpc: &impl AccountSource,
+    default_protocol_fee_rate: u16,
+    authority: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let authority = get_authority(authority)?;
+    validate_protocol_fee_rate(default_protocol_fee_rate)?;
+    let (config_address, config) = fetch_config_for_authority(rpc, ConfigAuthority::Fee, authority).await?;
+    if config.default_protocol_fee_rate == default_protocol_fee_rate {
+        return Ok(vec![]);
+    }
+
+    Ok(vec![SetDefaultProtocolFeeRate {
+        fusion_pools_config: config_address,
+        fee_authority: authority,
+    }
+    .instruction(SetDefaultProtocolFeeRateInstructionArgs { default_protocol_fee_rate })])
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to transfer the fee authority.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `new_fee_authority` - The public key of the new fee authority.
+/// * `authority` - An optional public key of the current fee authority. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the vector of `Instruction` objects required to set the fee authority.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` is missing or isn't the fee authority (`AdminError`).
+/// - The config account is not found or has invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn set_fee_authority_instructions(
+    rpc: &impl AccountSource,
+    new_fee_authority: Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let authority = get_authority(authority)?;
+    let (config_address, _) = fetch_config_for_authority(rpc, ConfigAuthority::Fee, authority).await?;
+
+    Ok(vec![SetFeeAuthority {
+        fusion_pools_config: config_address,
+        fee_authority: authority,
+        new_fee_authority,
+    }
+    .instruction()])
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to transfer the collect protocol fees authority.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `new_collect_protocol_fees_authority` - The public key of the new collect protocol fees authority.
+/// * `authority` - An optional public key of the current collect protocol fees authority. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the vector of `Instruction` objects required to set the collect protocol fees authority.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` is missing or isn't the collect protocol fees authority (`AdminError`).
+/// - The config account is not found or has invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn set_collect_protocol_fees_authority_instructions(
+    rpc: &impl AccountSource,
+    new_collect_protocol_fees_authority: Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let authority = get_authority(authority)?;
+    let (config_address, _) = fetch_config_for_authority(rpc, ConfigAuthority::CollectProtocolFees, authority).await?;
+
+    Ok(vec![SetCollectProtocolFeesAuthority {
+        fusion_pools_config: config_address,
+        collect_protocol_fees_authority: authority,
+        new_collect_protocol_fees_authority,
+    }
+    .instruction()])
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to transfer the token badge authority.
+///
+/// The token badge authority is changed by the fee authority.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `new_token_badge_authority` - The public key of the new token badge authority.
+/// * `authority` - An optional public key of the fee authority. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the vector of `Instruction` objects required to set the token badge authority.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` is missing or isn't the fee authority (`AdminError`).
+/// - The config account is not found or has invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn set_token_badge_authority_instructions(
+    rpc: &impl AccountSource,
+    new_token_badge_authority: Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let authority = get_authority(authority)?;
+    let (config_address, _) = fetch_config_for_authority(rpc, ConfigAuthority::Fee, authority).await?;
+
+    Ok(vec![SetTokenBadgeAuthority {
+        fusion_pools_config: config_address,
+        fee_authority: authority,
+        new_token_badge_authority,
+    }
+    .instruction()])
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to initialize a token badge, which allows pools to be created for a token
+/// mint with extensions that require it.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `token_mint` - The public key of the token mint.
+/// * `authority` - An optional public key of the token badge authority, who also funds the token badge account. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the vector of `Instruction` objects required to initialize the token badge.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` is missing or isn't the token badge authority (`AdminError`).
+/// - The config account is not found or has invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn initialize_token_badge_instructions(
+    rpc: &impl AccountSource,
+    token_mint: Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let authority = get_authority(authority)?;
+    let (config_address, _) = fetch_config_for_authority(rpc, ConfigAuthority::TokenBadge, authority).await?;
+
+    Ok(vec![InitializeTokenBadge {
+        fusion_pools_config: config_address,
+        token_badge_authority: authority,
+        token_mint,
+        token_badge: get_token_badge_address(&token_mint)?.0,
+        funder: authority,
+        system_program: solana_program::system_program::id(),
+    }
+    .instruction()])
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to delete a token badge.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `token_mint` - The public key of the token mint.
+/// * `authority` - An optional public key of the token badge authority, who also receives the rent. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the vector of `Instruction` objects required to delete the token badge.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` is missing or isn't the token badge authority (`AdminError`).
+/// - The config account is not found or has invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn delete_token_badge_instructions(
+    rpc: &impl AccountSource,
+    token_mint: Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let authority = get_authority(authority)?;
+    let (config_address, _) = fetch_config_for_authority(rpc, ConfigAuthority::TokenBadge, authority).await?;
+
+    Ok(vec![DeleteTokenBadge {
+        fusion_pools_config: config_address,
+        token_badge_authority: authority,
+        token_mint,
+        token_badge: get_token_badge_address(&token_mint)?.0,
+        receiver: authority,
+    }
+    .instruction()])
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to reset the price of a pool that has no liquidity.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `pool_address` - The public key of the pool.
+/// * `price` - The new price of token A in terms of token B.
+/// * `authority` - An optional public key of the fee authority. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the vector of `Instruction` objects required to reset the pool price.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` is missing or isn't the fee authority (`AdminError`).
+/// - The price is out of bounds (`AdminError`).
+/// - The pool has liquidity.
+/// - The config, pool or token mint accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn reset_pool_price_instructions(
+    rpc: &impl AccountSource,
+    pool_address: Pubkey,
+    price: f64,
+    authority: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let authority = get_authority(authority)?;
+    let (config_address, _) = fetch_config_for_authority(rpc, ConfigAuthority::Fee, authority).await?;
+
+    let pool_info = rpc.get_account(&pool_address).await?;
+    let pool = FusionPool::from_bytes(&pool_info.data)?;
+    if pool.liquidity != 0 {
+        return Err("The pool price can only be reset if the pool has no liquidity".into());
+    }
+
+    let mint_infos = rpc.get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b]).await?;
+    let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
+    let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
+    let mint_a = StateWithExtensions::<Mint>::unpack(&mint_a_info.data)?;
+    let mint_b = StateWithExtensions::<Mint>::unpack(&mint_b_info.data)?;
+
+    let sqrt_price = price_to_sqrt_price(price, mint_a.base.decimals, mint_b.base.decimals);
+    if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&sqrt_price) {
+        return Err(AdminError::SqrtPriceOutOfBounds(sqrt_price).into());
+    }
+
+    Ok(vec![ResetPoolPrice {
+        fee_authority: authority,
+        fusion_pools_config: config_address,
+        token_vault_a: pool.token_vault_a,
+        token_vault_b: pool.token_vault_b,
+        fusion_pool: pool_address,
+    }
+    .instruction(ResetPoolPriceInstructionArgs { sqrt_price })])
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to collect the protocol fees of a pool.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `pool_address` - The public key of the pool.
+/// * `authority` - An optional public key of the collect protocol fees authority, who receives the fees. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `CollectProtocolFeesInstruction` on success:
+///
+/// * `protocol_fee_owed_a` - The protocol fees of token A owed by the pool.
+/// * `protocol_fee_owed_b` - The protocol fees of token B owed by the pool.
+/// * `amount_a` - The amount of token A received after the transfer fee is applied.
+/// * `amount_b` - The amount of token B received after the transfer fee is applied.
+/// * `instructions` - A vector of `Instruction` objects required to collect the protocol fees.
+/// * `additional_signers` - A vector of `Keypair` objects representing additional signers required for the instructions.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` is missing or isn't the collect protocol fees authority (`AdminError`).
+/// - The config, pool or token mint accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn collect_protocol_fees_instructions(
+    rpc: &impl AccountSource,
+    pool_address: Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<CollectProtocolFeesInstruction, Box<dyn Error>> {
+    let authority = get_authority(authority)?;
+    let (config_address, _) = fetch_config_for_authority(rpc, ConfigAuthority::CollectProtocolFees, authority).await?;
+
+    let pool_info = rpc.get_account(&pool_address).await?;
+    let pool = FusionPool::from_bytes(&pool_info.data)?;
+
+    let mint_infos = rpc.get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b]).await?;
+    let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
+    let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
+
+    let current_epoch = rpc.get_epoch().await?;
+    let amount_a = match get_current_transfer_fee(Some(mint_a_info), current_epoch) {
+        Some(transfer_fee) => try_apply_transfer_fee(pool.protocol_fee_owed_a, transfer_fee)?,
+        None => pool.protocol_fee_owed_a,
+    };
+    let amount_b = match get_current_transfer_fee(Some(mint_b_info), current_epoch) {
+        Some(transfer_fee) => try_apply_transfer_fee(pool.protocol_fee_owed_b, transfer_fee)?,
+        None => pool.protocol_fee_owed_b,
+    };
+
+    let token_accounts = prepare_token_accounts_instructions(
+        rpc,
+        authority,
+        vec![
+            TokenAccountStrategy::WithoutBalance(pool.token_mint_a),
+            TokenAccountStrategy::WithoutBalance(pool.token_mint_b),
+        ],
+    )
+    .await?;
+
+    let token_destination_a = token_accounts
+        .token_account_addresses
+        .get(&pool.token_mint_a)
+        .ok_or("Token A destination account not found")?;
+    let token_destination_b = token_accounts
+        .token_account_addresses
+        .get(&pool.token_mint_b)
+        .ok_or("Token B destination account not found")?;
+
+    let mut instructions: Vec<Instruction> = Vec::new();
+    instructions.extend(token_accounts.create_instructions);
+
+    instructions.push(
+        CollectProtocolFees {
+            fusion_pools_config: config_address,
+            fusion_pool: pool_address,
+            collect_protocol_fees_authority: authority,
+            token_mint_a: pool.token_mint_a,
+            token_mint_b: pool.token_mint_b,
+            token_vault_a: pool.token_vault_a,
+            token_vault_b: pool.token_vault_b,
+            token_destination_a: *token_destination_a,
+            token_destination_b: *token_destination_b,
+            token_program_a: mint_a_info.owner,
+            token_program_b: mint_b_info.owner,
+            memo_program: spl_memo::ID,
+        }
+        .instruction(CollectProtocolFeesInstructionArgs {
+            remaining_accounts_info: None,
+        }),
+    );
+
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(CollectProtocolFeesInstruction {
+        protocol_fee_owed_a: pool.protocol_fee_owed_a,
+        protocol_fee_owed_b: pool.protocol_fee_owed_b,
+        amount_a,
+        amount_b,
+        instructions,
+        additional_signers: token_accounts.additional_signers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_authority, validate_fee_rate, validate_protocol_fee_rate};
+    use crate::{
+        collect_protocol_fees_instructions, initialize_config_instructions, set_default_protocol_fee_rate_instructions,
+        set_fee_authority_instructions, set_fee_rate_instructions, set_protocol_fee_rate_instructions,
+        tests::{setup_ata_with_amount, setup_fusion_pool, setup_mint_with_decimals, RpcContext},
+        AdminError, ConfigAuthority,
+    };
+    use fusionamm_client::{FusionPool, FusionPoolsConfig};
+    use fusionamm_core::{MAX_FEE_RATE, MAX_PROTOCOL_FEE_RATE};
+    use serial_test::serial;
+    use solana_program_test::tokio;
+    use solana_pubkey::Pubkey;
+    use solana_signer::Signer;
+
+    fn test_config(authority: Pubkey) -> FusionPoolsConfig {
+        FusionPoolsConfig {
+            discriminator: [0; 8],
+            version: 0,
+            fee_authority: authority,
+            collect_protocol_fees_authority: Pubkey::new_unique(),
+            token_badge_authority: Pubkey::new_unique(),
+            default_protocol_fee_rate: 0,
+            unused_0: 0,
+            unused_1: 0,
+            reserved: [0; 170],
+        }
+    }
+
+    #[test]
+    fn test_validate_fee_rates() {
+        assert_eq!(validate_fee_rate(MAX_FEE_RATE), Ok(()));
+        assert_eq!(validate_fee_rate(MAX_FEE_RATE + 1), Err(AdminError::FeeRateMaxExceeded(MAX_FEE_RATE + 1)));
+        assert_eq!(validate_protocol_fee_rate(MAX_PROTOCOL_FEE_RATE), Ok(()));
+        assert_eq!(
+            validate_protocol_fee_rate(MAX_PROTOCOL_FEE_RATE + 1),
+            Err(AdminError::ProtocolFeeRateMaxExceeded(MAX_PROTOCOL_FEE_RATE + 1))
+        );
+    }
+
+    #[test]
+    fn test_validate_authority() {
+        let authority = Pubkey::new_unique();
+        let config = test_config(authority);
+        assert_eq!(validate_authority(&config, ConfigAuthority::Fee, authority), Ok(()));
+        assert_eq!(
+            validate_authority(&config, ConfigAuthority::CollectProtocolFees, authority),
+            Err(AdminError::InvalidAuthority {
+                authority: ConfigAuthority::CollectProtocolFees,
+                expected: config.collect_protocol_fees_authority,
+                signer: authority,
+            })
+        );
+    }
+
+    async fn setup_pool(ctx: &RpcContext) -> Pubkey {
+        let mint_1 = setup_mint_with_decimals(ctx, 9).await.unwrap();
+        let mint_2 = setup_mint_with_decimals(ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_1 < mint_2 { (mint_1, mint_2) } else { (mint_2, mint_1) };
+        setup_ata_with_amount(ctx, mint_a, 1_000_000_000).await.unwrap();
+        setup_ata_with_amount(ctx, mint_b, 1_000_000_000).await.unwrap();
+        setup_fusion_pool(ctx, mint_a, mint_b, 64, 300).await.unwrap()
+    }
+
+    async fn fetch_pool(ctx: &RpcContext, pool: Pubkey) -> FusionPool {
+        let info = ctx.rpc.get_account(&pool).await.unwrap();
+        FusionPool::from_bytes(&info.data).unwrap()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_set_fee_rates() {
+        let ctx = RpcContext::new().await;
+        let pool = setup_pool(&ctx).await;
+
+        let ix = set_fee_rate_instructions(&ctx.rpc, pool, 1000, Some(ctx.signer.pubkey())).await.unwrap();
+        ctx.send_transaction(ix).await.unwrap();
+        let ix = set_protocol_fee_rate_instructions(&ctx.rpc, pool, 500, Some(ctx.signer.pubkey()))
+            .await
+            .unwrap();
+        ctx.send_transaction(ix).await.unwrap();
+
+        let pool_after = fetch_pool(&ctx, pool).await;
+        assert_eq!(pool_after.fee_rate, 1000);
+        assert_eq!(pool_after.protocol_fee_rate, 500);
+
+        // Nothing to update.
+        let ix = set_fee_rate_instructions(&ctx.rpc, pool, 1000, Some(ctx.signer.pubkey())).await.unwrap();
+        assert!(ix.is_empty());
+
+        let error = set_fee_rate_instructions(&ctx.rpc, pool, MAX_FEE_RATE + 1, Some(ctx.signer.pubkey()))
+            .await
+            .unwrap_err();
+        assert_eq!(error.downcast_ref::<AdminError>(), Some(&AdminError::FeeRateMaxExceeded(MAX_FEE_RATE + 1)));
+
+        let ix = set_default_protocol_fee_rate_instructions(&ctx.rpc, 200, Some(ctx.signer.pubkey()))
+            .await
+            .unwrap();
+        ctx.send_transaction(ix).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_invalid_authority() {
+        let ctx = RpcContext::new().await;
+        let pool = setup_pool(&ctx).await;
+        let other = Pubkey::new_unique();
+
+        let error = set_fee_rate_instructions(&ctx.rpc, pool, 1000, Some(other)).await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<AdminError>(),
+            Some(&AdminError::InvalidAuthority {
+                authority: ConfigAuthority::Fee,
+                expected: ctx.signer.pubkey(),
+                signer: other,
+            })
+        );
+
+        let error = collect_protocol_fees_instructions(&ctx.rpc, pool, Some(other)).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<AdminError>(), Some(AdminError::InvalidAuthority { .. })));
+
+        let ix = set_fee_authority_instructions(&ctx.rpc, other, Some(ctx.signer.pubkey())).await.unwrap();
+        ctx.send_transaction(ix).await.unwrap();
+        let error = set_fee_rate_instructions(&ctx.rpc, pool, 1000, Some(ctx.signer.pubkey()))
+            .await
+            .unwrap_err();
+        assert!(matches!(error.downcast_ref::<AdminError>(), Some(AdminError::InvalidAuthority { .. })));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_initialize_config_already_initialized() {
+        let ctx = RpcContext::new().await;
+        let signer = ctx.signer.pubkey();
+        let error = initialize_config_instructions(&ctx.rpc, signer, signer, signer, 100, Some(signer))
+            .await
+            .unwrap_err();
+        assert_eq!(error.downcast_ref::<AdminError>(), Some(&AdminError::ConfigAlreadyInitialized));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_collect_protocol_fees() {
+        let ctx = RpcContext::new().await;
+        let pool = setup_pool(&ctx).await;
+
+        let result = collect_protocol_fees_instructions(&ctx.rpc, pool, Some(ctx.signer.pubkey()))
+            .await
+            .unwrap();
+        assert_eq!(result.protocol_fee_owed_a, 0);
+        assert_eq!(result.protocol_fee_owed_b, 0);
+        assert_eq!(result.amount_a, 0);
+        assert_eq!(result.amount_b, 0);
+        ctx.send_transaction_with_signers(result.instructions, result.additional_signers.iter().collect())
+            .await
+            .unwrap();
+    }
+}
@@ -14,7 +14,6 @@ use fusionamm_client::FusionPool;
 use fusionamm_client::{get_fusion_pool_address, get_fusion_pools_config_address, get_token_badge_address};
 use fusionamm_client::{InitializePool, InitializePoolInstructionArgs};
 use fusionamm_core::price_to_sqrt_price;
-use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_keypair::Keypair;
 use solana_program::rent::Rent;
 use solana_program::sysvar::SysvarId;
@@ -24,7 +23,7 @@ use solana_signer::Signer;
 use spl_token_2022::extension::StateWithExtensions;
 use spl_token_2022::state::Mint;
 
-use crate::{get_account_data_size, get_rent, order_mints, FUNDER};
+use crate::{get_account_data_size, order_mints, AccountSource, FUNDER};
 
 /// Represents the instructions and metadata for creating a pool.
 pub struct CreatePoolInstructions {
@@ -109,7 +108,7 @@ pub struct CreatePoolInstructions {
 /// }
 /// ```
 pub async fn create_fusion_pool_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     token_a: Pubkey,
     token_b: Pubkey,
     tick_spacing: u16,
@@ -126,7 +125,7 @@ pub async fn create_fusion_pool_instructions(
         return Err("Token order needs to be flipped to match the canonical ordering (i.e. sorted on the byte repr. of the mint pubkeys)".into());
     }
 
-    let rent = get_rent(rpc).await?;
+    let rent = rpc.get_rent().await?;
 
     let account_infos = rpc.get_multiple_accounts(&[token_a, token_b]).await?;
     let mint_a_info = account_infos[0].as_ref().ok_or(format!("Mint {} not found", token_a))?;
@@ -214,6 +213,7 @@ mod tests {
 
     use super::*;
     use serial_test::serial;
+    use solana_client::nonblocking::rpc_client::RpcClient;
 
     async fn fetch_pool(rpc: &RpcClient, pool_address: Pubkey) -> Result<FusionPool, Box<dyn Error>> {
         let account = rpc.get_account(&pool_address).await?;
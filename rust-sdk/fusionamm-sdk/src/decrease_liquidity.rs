@@ -9,16 +9,16 @@
 //
 
 use crate::{
+    lock::ensure_position_unlocked,
     token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy},
-    FUNDER, SLIPPAGE_TOLERANCE_BPS,
+    AccountSource, FUNDER, SLIPPAGE_TOLERANCE_BPS,
 };
-use fusionamm_client::{get_position_address, get_tick_array_address, FusionPool, Position, TickArray};
+use fusionamm_client::{get_position_address, get_position_lock_address, get_tick_array_address, FusionPool, Position, TickArray};
 use fusionamm_client::{ClosePosition, CollectFees, CollectFeesInstructionArgs, DecreaseLiquidity, DecreaseLiquidityInstructionArgs};
 use fusionamm_core::{
     collect_fees_quote, decrease_liquidity_quote, decrease_liquidity_quote_a, decrease_liquidity_quote_b, get_tick_array_start_tick_index,
     get_tick_index_in_array, CollectFeesQuote, DecreaseLiquidityQuote,
 };
-use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_instruction::Instruction;
 use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
@@ -82,6 +82,7 @@ pub struct DecreaseLiquidityInstruction {
 ///
 /// This function will return an error if:
 /// - The `authority` account is invalid or missing.
+/// - The position is locked (`PositionLockError::PositionLocked`).
 /// - The position or token mint accounts are not found or have invalid data.
 /// - Any RPC request to the blockchain fails.
 ///
@@ -114,7 +115,7 @@ pub struct DecreaseLiquidityInstruction {
 /// }
 /// ```
 pub async fn decrease_liquidity_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     position_mint_address: Pubkey,
     param: DecreaseLiquidityParam,
     slippage_tolerance_bps: Option<u16>,
@@ -133,15 +134,17 @@ pub async fn decrease_liquidity_instructions(
     let pool_info = rpc.get_account(&position.fusion_pool).await?;
     let pool = FusionPool::from_bytes(&pool_info.data)?;
 
+    let position_lock_address = get_position_lock_address(&position_mint_address)?.0;
     let mint_infos = rpc
-        .get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b, position_mint_address])
+        .get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b, position_mint_address, position_lock_address])
         .await?;
 
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
     let position_mint_info = mint_infos[2].as_ref().ok_or("Position mint info not found")?;
+    ensure_position_unlocked(position_mint_address, mint_infos[3].as_ref())?;
 
-    let current_epoch = rpc.get_epoch_info().await?.epoch;
+    let current_epoch = rpc.get_epoch().await?;
     let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
     let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
 
@@ -290,6 +293,7 @@ pub struct ClosePositionInstruction {
 ///
 /// This function will return an error if:
 /// - The `authority` account is invalid or missing.
+/// - The position is locked (`PositionLockError::PositionLocked`).
 /// - The position, token mint, or reward accounts are not found or have invalid data.
 /// - Any RPC request to the blockchain fails.
 ///
@@ -324,7 +328,7 @@ pub struct ClosePositionInstruction {
 /// }
 /// ```
 pub async fn close_position_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     position_mint_address: Pubkey,
     slippage_tolerance_bps: Option<u16>,
     authority: Option<Pubkey>,
@@ -342,15 +346,17 @@ pub async fn close_position_instructions(
     let pool_info = rpc.get_account(&position.fusion_pool).await?;
     let pool = FusionPool::from_bytes(&pool_info.data)?;
 
+    let position_lock_address = get_position_lock_address(&position_mint_address)?.0;
     let mint_infos = rpc
-        .get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b, position_mint_address])
+        .get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b, position_mint_address, position_lock_address])
         .await?;
 
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
     let position_mint_info = mint_infos[2].as_ref().ok_or("Position mint info not found")?;
+    ensure_position_unlocked(position_mint_address, mint_infos[3].as_ref())?;
 
-    let current_epoch = rpc.get_epoch_info().await?.epoch;
+    let current_epoch = rpc.get_epoch().await?;
     let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
     let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
 
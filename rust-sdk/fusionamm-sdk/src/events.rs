@@ -0,0 +1,797 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use fusionamm_client::{decode_transaction, CompiledInnerInstructions, DecodedInstruction, FusionAmmInstruction, FusionPool, FUSIONAMM_ID};
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_message::v0::LoadedAddresses;
+use solana_message::VersionedMessage;
+use solana_pubkey::Pubkey;
+use std::{collections::HashMap, error::Error};
+
+const TRANSFER_TAG: u8 = 3;
+const TRANSFER_CHECKED_TAG: u8 = 12;
+
+/// The balance of a token account before or after a transaction, as reported by the transaction status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBalance {
+    /// The index of the token account in the account keys of the transaction.
+    pub account_index: u8,
+
+    /// The mint of the token account.
+    pub mint: Pubkey,
+
+    /// The owner of the token account, if reported.
+    pub owner: Option<Pubkey>,
+
+    /// The raw token amount.
+    pub amount: u64,
+}
+
+/// A confirmed transaction together with the parts of its status needed to extract FusionAMM events.
+#[derive(Debug, Clone)]
+pub struct ConfirmedTransaction {
+    /// The message of the transaction.
+    pub message: VersionedMessage,
+
+    /// The addresses loaded from address lookup tables.
+    pub loaded_addresses: LoadedAddresses,
+
+    /// The inner instructions of the transaction, or `None` if they were not recorded.
+    pub inner_instructions: Option<Vec<CompiledInnerInstructions>>,
+
+    /// The token balances before the transaction.
+    pub pre_token_balances: Vec<TokenBalance>,
+
+    /// The token balances after the transaction.
+    pub post_token_balances: Vec<TokenBalance>,
+}
+
+/// An amount of tokens of a mint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenAmount {
+    /// The mint of the tokens.
+    pub mint: Pubkey,
+
+    /// The raw token amount.
+    pub amount: u64,
+}
+
+/// A swap through a fusion pool. A two-hop swap produces one event per pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapEvent {
+    /// The index of the top-level instruction in the transaction.
+    pub instruction_index: usize,
+
+    /// The index of the instruction within the inner instructions, or `None` for a top-level instruction.
+    pub inner_instruction_index: Option<usize>,
+
+    /// The address of the fusion pool.
+    pub fusion_pool: Pubkey,
+
+    /// The authority of the swapped token accounts.
+    pub user: Pubkey,
+
+    /// The direction of the swap.
+    pub a_to_b: bool,
+
+    /// The tokens paid into the pool.
+    pub input: TokenAmount,
+
+    /// The tokens paid out by the pool.
+    pub output: TokenAmount,
+
+    /// The sqrt price of the pool before the swap, if known. See `set_swap_sqrt_prices`.
+    pub pre_sqrt_price: Option<u128>,
+
+    /// The sqrt price of the pool after the swap, if known. See `set_swap_sqrt_prices`.
+    pub post_sqrt_price: Option<u128>,
+}
+
+/// The direction of a liquidity change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidityChangeKind {
+    Increase,
+    Decrease,
+}
+
+/// A deposit of liquidity into or a withdrawal of liquidity from a position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityChangeEvent {
+    /// The index of the top-level instruction in the transaction.
+    pub instruction_index: usize,
+
+    /// The index of the instruction within the inner instructions, or `None` for a top-level instruction.
+    pub inner_instruction_index: Option<usize>,
+
+    /// The direction of the change.
+    pub kind: LiquidityChangeKind,
+
+    /// The address of the fusion pool.
+    pub fusion_pool: Pubkey,
+
+    /// The address of the position.
+    pub position: Pubkey,
+
+    /// The authority of the position.
+    pub user: Pubkey,
+
+    /// The amount of liquidity added or removed.
+    pub liquidity_amount: u128,
+
+    /// The token A deposited or withdrawn.
+    pub token_a: TokenAmount,
+
+    /// The token B deposited or withdrawn.
+    pub token_b: TokenAmount,
+}
+
+/// The kind of a limit order event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitOrderEventKind {
+    Open { tick_index: i32, a_to_b: bool },
+    Increase,
+    Decrease,
+    Close,
+}
+
+/// A change of a limit order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitOrderEvent {
+    /// The index of the top-level instruction in the transaction.
+    pub instruction_index: usize,
+
+    /// The index of the instruction within the inner instructions, or `None` for a top-level instruction.
+    pub inner_instruction_index: Option<usize>,
+
+    /// The kind of the change.
+    pub kind: LimitOrderEventKind,
+
+    /// The address of the limit order.
+    pub limit_order: Pubkey,
+
+    /// The address of the fusion pool. Closing a limit order does not reference the pool, so it is `None` then.
+    pub fusion_pool: Option<Pubkey>,
+
+    /// The owner or authority of the limit order.
+    pub user: Pubkey,
+
+    /// The tokens deposited into (`Increase`) or withdrawn from (`Decrease`) the limit order. Empty for other kinds.
+    pub amounts: Vec<TokenAmount>,
+}
+
+/// A collection of position fees or protocol fees.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeCollectionEvent {
+    /// The index of the top-level instruction in the transaction.
+    pub instruction_index: usize,
+
+    /// The index of the instruction within the inner instructions, or `None` for a top-level instruction.
+    pub inner_instruction_index: Option<usize>,
+
+    /// The address of the fusion pool.
+    pub fusion_pool: Pubkey,
+
+    /// The position whose fees were collected, or `None` for protocol fees.
+    pub position: Option<Pubkey>,
+
+    /// The authority of the position or the protocol fees.
+    pub user: Pubkey,
+
+    /// The collected token A fees.
+    pub token_a: TokenAmount,
+
+    /// The collected token B fees.
+    pub token_b: TokenAmount,
+}
+
+/// An event extracted from a FusionAMM instruction of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FusionAmmEvent {
+    Swap(SwapEvent),
+    LiquidityChange(LiquidityChangeEvent),
+    LimitOrder(LimitOrderEvent),
+    FeeCollection(FeeCollectionEvent),
+}
+
+/// Extracts swap, liquidity, limit order and fee collection events from a confirmed transaction.
+///
+/// The FusionAMM program does not emit events, so they are derived from the decoded instructions and the token movements of the pool vaults.
+/// If the transaction has inner instructions, the amounts of each instruction are read from the token transfers it invoked,
+/// so with transfer fee extensions the amounts paid into the pool include the withheld fee.
+/// Otherwise, the amounts are the changes of the vault token balances. This only works if no other FusionAMM instruction
+/// of the transaction moves tokens of the same vaults.
+///
+/// # Arguments
+///
+/// * `transaction` - The confirmed transaction with its inner instructions and token balances.
+///
+/// # Returns
+///
+/// A `Result` containing the events in the order of execution. Instructions that do not move tokens or change orders, e.g. admin instructions, produce no events.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The transaction can't be decoded.
+/// - The transaction has no inner instructions and several FusionAMM instructions move tokens of the same vault.
+///
+/// # Example
+///
+/// ```rust
+/// use fusionamm_sdk::{extract_events, ConfirmedTransaction, FusionAmmEvent};
+///
+/// fn print_swaps(transaction: &ConfirmedTransaction) {
+///     for event in extract_events(transaction).unwrap() {
+///         if let FusionAmmEvent::Swap(swap) = event {
+///             println!("{} swapped {} for {}", swap.user, swap.input.amount, swap.output.amount);
+///         }
+///     }
+/// }
+/// ```
+pub fn extract_events(transaction: &ConfirmedTransaction) -> Result<Vec<FusionAmmEvent>, Box<dyn Error>> {
+    let inner_instructions = transaction.inner_instructions.as_deref().unwrap_or_default();
+    let decoded_instructions = decode_transaction(&transaction.message, &transaction.loaded_addresses, inner_instructions)?;
+
+    let account_keys: Vec<Pubkey> = transaction
+        .message
+        .static_account_keys()
+        .iter()
+        .chain(&transaction.loaded_addresses.writable)
+        .chain(&transaction.loaded_addresses.readonly)
+        .copied()
+        .collect();
+
+    let mut vault_usage: HashMap<Pubkey, usize> = HashMap::new();
+    for decoded in &decoded_instructions {
+        for vault in token_vaults(&decoded.instruction) {
+            *vault_usage.entry(vault).or_default() += 1;
+        }
+    }
+
+    let mut events = Vec::new();
+    for decoded in &decoded_instructions {
+        let vaults = token_vaults(&decoded.instruction);
+        let deltas = match &transaction.inner_instructions {
+            Some(inner_instructions) => transfer_deltas(&account_keys, inner_instructions, decoded, &vaults)?,
+            None => {
+                if let Some(vault) = vaults.iter().find(|vault| vault_usage[*vault] > 1) {
+                    return Err(format!("Vault {} is used by several instructions and the transaction has no inner instructions", vault).into());
+                }
+                balance_deltas(&account_keys, transaction, &vaults)
+            }
+        };
+        events.extend(instruction_events(decoded, &deltas));
+    }
+
+    Ok(events)
+}
+
+/// Sets the sqrt prices of swap events from the states of their pools before and after the transaction.
+///
+/// The pre sqrt price is set on the first swap through each pool and the post sqrt price on the last one.
+/// Prices between several swaps through the same pool can't be derived and stay `None`.
+///
+/// # Arguments
+///
+/// * `events` - The events of a single transaction, as returned by `extract_events`.
+/// * `pre_fusion_pools` - The states of the fusion pools before the transaction, e.g. from a `PoolStateTracker`.
+/// * `post_fusion_pools` - The states of the fusion pools after the transaction.
+pub fn set_swap_sqrt_prices(
+    events: &mut [FusionAmmEvent],
+    pre_fusion_pools: &HashMap<Pubkey, FusionPool>,
+    post_fusion_pools: &HashMap<Pubkey, FusionPool>,
+) {
+    let mut first_swaps: HashMap<Pubkey, usize> = HashMap::new();
+    let mut last_swaps: HashMap<Pubkey, usize> = HashMap::new();
+    for (index, event) in events.iter().enumerate() {
+        if let FusionAmmEvent::Swap(swap) = event {
+            first_swaps.entry(swap.fusion_pool).or_insert(index);
+            last_swaps.insert(swap.fusion_pool, index);
+        }
+    }
+
+    for (fusion_pool, index) in first_swaps {
+        if let (FusionAmmEvent::Swap(swap), Some(pool)) = (&mut events[index], pre_fusion_pools.get(&fusion_pool)) {
+            swap.pre_sqrt_price = Some(pool.sqrt_price);
+        }
+    }
+    for (fusion_pool, index) in last_swaps {
+        if let (FusionAmmEvent::Swap(swap), Some(pool)) = (&mut events[index], post_fusion_pools.get(&fusion_pool)) {
+            swap.post_sqrt_price = Some(pool.sqrt_price);
+        }
+    }
+}
+
+/// The net token amounts moved into (positive) or out of (negative) the vaults of an instruction.
+struct VaultDeltas(HashMap<Pubkey, i128>);
+
+impl VaultDeltas {
+    fn inflow(&self, vault: &Pubkey, mint: Pubkey) -> TokenAmount {
+        let delta = self.0.get(vault).copied().unwrap_or_default();
+        TokenAmount {
+            mint,
+            amount: delta.max(0) as u64,
+        }
+    }
+
+    fn outflow(&self, vault: &Pubkey, mint: Pubkey) -> TokenAmount {
+        let delta = self.0.get(vault).copied().unwrap_or_default();
+        TokenAmount {
+            mint,
+            amount: (-delta).max(0) as u64,
+        }
+    }
+}
+
+fn token_vaults(instruction: &FusionAmmInstruction) -> Vec<Pubkey> {
+    match instruction {
+        FusionAmmInstruction::Swap { accounts, .. } => vec![accounts.token_vault_a, accounts.token_vault_b],
+        FusionAmmInstruction::TwoHopSwap { accounts, .. } => vec![
+            accounts.token_vault_one_input,
+            accounts.token_vault_one_intermediate,
+            accounts.token_vault_two_intermediate,
+            accounts.token_vault_two_output,
+        ],
+        FusionAmmInstruction::IncreaseLiquidity { accounts, .. } => vec![accounts.token_vault_a, accounts.token_vault_b],
+        FusionAmmInstruction::DecreaseLiquidity { accounts, .. } => vec![accounts.token_vault_a, accounts.token_vault_b],
+        FusionAmmInstruction::CollectFees { accounts, .. } => vec![accounts.token_vault_a, accounts.token_vault_b],
+        FusionAmmInstruction::CollectProtocolFees { accounts, .. } => vec![accounts.token_vault_a, accounts.token_vault_b],
+        FusionAmmInstruction::IncreaseLimitOrder { accounts, .. } => vec![accounts.token_vault],
+        FusionAmmInstruction::DecreaseLimitOrder { accounts, .. } => vec![accounts.token_vault_a, accounts.token_vault_b],
+        _ => vec![],
+    }
+}
+
+fn transfer_deltas(
+    account_keys: &[Pubkey],
+    inner_instructions: &[CompiledInnerInstructions],
+    decoded: &DecodedInstruction,
+    vaults: &[Pubkey],
+) -> Result<VaultDeltas, Box<dyn Error>> {
+    let mut deltas = HashMap::new();
+
+    // The token transfers of an instruction are the inner instructions following it until the next FusionAMM instruction.
+    let start = decoded.inner_instruction_index.map_or(0, |index| index + 1);
+    let instructions = inner_instructions
+        .iter()
+        .filter(|inner| inner.index as usize == decoded.instruction_index)
+        .flat_map(|inner| &inner.instructions)
+        .skip(start);
+
+    for instruction in instructions {
+        let program_id = account_key(account_keys, instruction.program_id_index)?;
+        if program_id == FUSIONAMM_ID {
+            break;
+        }
+        if program_id != spl_token::ID && program_id != spl_token_2022::ID {
+            continue;
+        }
+        if let Some((source, destination, amount)) = decode_transfer(account_keys, instruction)? {
+            if vaults.contains(&source) {
+                *deltas.entry(source).or_default() -= amount as i128;
+            }
+            if vaults.contains(&destination) {
+                *deltas.entry(destination).or_default() += amount as i128;
+            }
+        }
+    }
+
+    Ok(VaultDeltas(deltas))
+}
+
+fn balance_deltas(account_keys: &[Pubkey], transaction: &ConfirmedTransaction, vaults: &[Pubkey]) -> VaultDeltas {
+    let mut deltas = HashMap::new();
+
+    let mut add_balances = |balances: &[TokenBalance], sign: i128| {
+        for balance in balances {
+            if let Some(address) = account_keys
+                .get(balance.account_index as usize)
+                .filter(|address| vaults.contains(*address))
+            {
+                *deltas.entry(*address).or_default() += sign * balance.amount as i128;
+            }
+        }
+    };
+    add_balances(&transaction.pre_token_balances, -1);
+    add_balances(&transaction.post_token_balances, 1);
+
+    VaultDeltas(deltas)
+}
+
+fn decode_transfer(account_keys: &[Pubkey], instruction: &CompiledInstruction) -> Result<Option<(Pubkey, Pubkey, u64)>, Box<dyn Error>> {
+    let destination_position = match instruction.data.first() {
+        Some(&TRANSFER_TAG) => 1,
+        Some(&TRANSFER_CHECKED_TAG) => 2,
+        _ => return Ok(None),
+    };
+    let amount = instruction
+        .data
+        .get(1..9)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or("Token transfer data is too short")?;
+    let (Some(source), Some(destination)) = (instruction.accounts.first(), instruction.accounts.get(destination_position)) else {
+        return Err("Token transfer is missing accounts".into());
+    };
+    Ok(Some((account_key(account_keys, *source)?, account_key(account_keys, *destination)?, amount)))
+}
+
+fn account_key(account_keys: &[Pubkey], index: u8) -> Result<Pubkey, Box<dyn Error>> {
+    account_keys
+        .get(index as usize)
+        .copied()
+        .ok_or(format!("Account index {} is out of bounds of the transaction accounts", index).into())
+}
+
+fn instruction_events(decoded: &DecodedInstruction, deltas: &VaultDeltas) -> Vec<FusionAmmEvent> {
+    let instruction_index = decoded.instruction_index;
+    let inner_instruction_index = decoded.inner_instruction_index;
+
+    let event = match &decoded.instruction {
+        FusionAmmInstruction::Swap { accounts, args } => {
+            let (input_vault, input_mint, output_vault, output_mint) = if args.a_to_b {
+                (&accounts.token_vault_a, accounts.token_mint_a, &accounts.token_vault_b, accounts.token_mint_b)
+            } else {
+                (&accounts.token_vault_b, accounts.token_mint_b, &accounts.token_vault_a, accounts.token_mint_a)
+            };
+            FusionAmmEvent::Swap(SwapEvent {
+                instruction_index,
+                inner_instruction_index,
+                fusion_pool: accounts.fusion_pool,
+                user: accounts.token_authority,
+                a_to_b: args.a_to_b,
+                input: deltas.inflow(input_vault, input_mint),
+                output: deltas.outflow(output_vault, output_mint),
+                pre_sqrt_price: None,
+                post_sqrt_price: None,
+            })
+        }
+        FusionAmmInstruction::TwoHopSwap { accounts, args } => {
+            let swap_one = FusionAmmEvent::Swap(SwapEvent {
+                instruction_index,
+                inner_instruction_index,
+                fusion_pool: accounts.fusion_pool_one,
+                user: accounts.token_authority,
+                a_to_b: args.a_to_b_one,
+                input: deltas.inflow(&accounts.token_vault_one_input, accounts.token_mint_input),
+                output: deltas.outflow(&accounts.token_vault_one_intermediate, accounts.token_mint_intermediate),
+                pre_sqrt_price: None,
+                post_sqrt_price: None,
+            });
+            let swap_two = FusionAmmEvent::Swap(SwapEvent {
+                instruction_index,
+                inner_instruction_index,
+                fusion_pool: accounts.fusion_pool_two,
+                user: accounts.token_authority,
+                a_to_b: args.a_to_b_two,
+                input: deltas.inflow(&accounts.token_vault_two_intermediate, accounts.token_mint_intermediate),
+                output: deltas.outflow(&accounts.token_vault_two_output, accounts.token_mint_output),
+                pre_sqrt_price: None,
+                post_sqrt_price: None,
+            });
+            return vec![swap_one, swap_two];
+        }
+        FusionAmmInstruction::IncreaseLiquidity { accounts, args } => FusionAmmEvent::LiquidityChange(LiquidityChangeEvent {
+            instruction_index,
+            inner_instruction_index,
+            kind: LiquidityChangeKind::Increase,
+            fusion_pool: accounts.fusion_pool,
+            position: accounts.position,
+            user: accounts.position_authority,
+            liquidity_amount: args.liquidity_amount,
+            token_a: deltas.inflow(&accounts.token_vault_a, accounts.token_mint_a),
+            token_b: deltas.inflow(&accounts.token_vault_b, accounts.token_mint_b),
+        }),
+        FusionAmmInstruction::DecreaseLiquidity { accounts, args } => FusionAmmEvent::LiquidityChange(LiquidityChangeEvent {
+            instruction_index,
+            inner_instruction_index,
+            kind: LiquidityChangeKind::Decrease,
+            fusion_pool: accounts.fusion_pool,
+            position: accounts.position,
+            user: accounts.position_authority,
+            liquidity_amount: args.liquidity_amount,
+            token_a: deltas.outflow(&accounts.token_vault_a, accounts.token_mint_a),
+            token_b: deltas.outflow(&accounts.token_vault_b, accounts.token_mint_b),
+        }),
+        FusionAmmInstruction::CollectFees { accounts, .. } => FusionAmmEvent::FeeCollection(FeeCollectionEvent {
+            instruction_index,
+            inner_instruction_index,
+            fusion_pool: accounts.fusion_pool,
+            position: Some(accounts.position),
+            user: accounts.position_authority,
+            token_a: deltas.outflow(&accounts.token_vault_a, accounts.token_mint_a),
+            token_b: deltas.outflow(&accounts.token_vault_b, accounts.token_mint_b),
+        }),
+        FusionAmmInstruction::CollectProtocolFees { accounts, .. } => FusionAmmEvent::FeeCollection(FeeCollectionEvent {
+            instruction_index,
+            inner_instruction_index,
+            fusion_pool: accounts.fusion_pool,
+            position: None,
+            user: accounts.collect_protocol_fees_authority,
+            token_a: deltas.outflow(&accounts.token_vault_a, accounts.token_mint_a),
+            token_b: deltas.outflow(&accounts.token_vault_b, accounts.token_mint_b),
+        }),
+        FusionAmmInstruction::OpenLimitOrder { accounts, args } => FusionAmmEvent::LimitOrder(LimitOrderEvent {
+            instruction_index,
+            inner_instruction_index,
+            kind: LimitOrderEventKind::Open {
+                tick_index: args.tick_index,
+                a_to_b: args.a_to_b,
+            },
+            limit_order: accounts.limit_order,
+            fusion_pool: Some(accounts.fusion_pool),
+            user: accounts.owner,
+            amounts: vec![],
+        }),
+        FusionAmmInstruction::IncreaseLimitOrder { accounts, .. } => FusionAmmEvent::LimitOrder(LimitOrderEvent {
+            instruction_index,
+            inner_instruction_index,
+            kind: LimitOrderEventKind::Increase,
+            limit_order: accounts.limit_order,
+            fusion_pool: Some(accounts.fusion_pool),
+            user: accounts.limit_order_authority,
+            amounts: vec![deltas.inflow(&accounts.token_vault, accounts.token_mint)],
+        }),
+        FusionAmmInstruction::DecreaseLimitOrder { accounts, .. } => FusionAmmEvent::LimitOrder(LimitOrderEvent {
+            instruction_index,
+            inner_instruction_index,
+            kind: LimitOrderEventKind::Decrease,
+            limit_order: accounts.limit_order,
+            fusion_pool: Some(accounts.fusion_pool),
+            user: accounts.limit_order_authority,
+            amounts: vec![
+                deltas.outflow(&accounts.token_vault_a, accounts.token_mint_a),
+                deltas.outflow(&accounts.token_vault_b, accounts.token_mint_b),
+            ],
+        }),
+        FusionAmmInstruction::CloseLimitOrder { accounts } => FusionAmmEvent::LimitOrder(LimitOrderEvent {
+            instruction_index,
+            inner_instruction_index,
+            kind: LimitOrderEventKind::Close,
+            limit_order: accounts.limit_order,
+            fusion_pool: None,
+            user: accounts.limit_order_authority,
+            amounts: vec![],
+        }),
+        _ => return vec![],
+    };
+
+    vec![event]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fusionamm_client::{CollectFees, CollectFeesInstructionArgs, Swap, SwapInstructionArgs};
+    use solana_instruction::Instruction;
+    use solana_message::v0;
+    use solana_program::hash::Hash;
+
+    struct TestPool {
+        fusion_pool: Pubkey,
+        token_mint_a: Pubkey,
+        token_mint_b: Pubkey,
+        token_vault_a: Pubkey,
+        token_vault_b: Pubkey,
+        user: Pubkey,
+        token_owner_account_a: Pubkey,
+        token_owner_account_b: Pubkey,
+    }
+
+    impl TestPool {
+        fn new() -> Self {
+            Self {
+                fusion_pool: Pubkey::new_unique(),
+                token_mint_a: Pubkey::new_unique(),
+                token_mint_b: Pubkey::new_unique(),
+                token_vault_a: Pubkey::new_unique(),
+                token_vault_b: Pubkey::new_unique(),
+                user: Pubkey::new_unique(),
+                token_owner_account_a: Pubkey::new_unique(),
+                token_owner_account_b: Pubkey::new_unique(),
+            }
+        }
+
+        fn swap(&self, a_to_b: bool) -> Instruction {
+            Swap {
+                token_program_a: spl_token::ID,
+                token_program_b: spl_token::ID,
+                memo_program: spl_memo::ID,
+                token_authority: self.user,
+                fusion_pool: self.fusion_pool,
+                token_mint_a: self.token_mint_a,
+                token_mint_b: self.token_mint_b,
+                token_owner_account_a: self.token_owner_account_a,
+                token_owner_account_b: self.token_owner_account_b,
+                token_vault_a: self.token_vault_a,
+                token_vault_b: self.token_vault_b,
+                tick_array0: Pubkey::new_unique(),
+                tick_array1: Pubkey::new_unique(),
+                tick_array2: Pubkey::new_unique(),
+            }
+            .instruction(SwapInstructionArgs {
+                amount: 1000,
+                other_amount_threshold: 0,
+                sqrt_price_limit: 0,
+                amount_specified_is_input: true,
+                a_to_b,
+                remaining_accounts_info: None,
+            })
+        }
+
+        fn collect_fees(&self, position: Pubkey) -> Instruction {
+            CollectFees {
+                fusion_pool: self.fusion_pool,
+                position_authority: self.user,
+                position,
+                position_token_account: Pubkey::new_unique(),
+                token_mint_a: self.token_mint_a,
+                token_mint_b: self.token_mint_b,
+                token_owner_account_a: self.token_owner_account_a,
+                token_owner_account_b: self.token_owner_account_b,
+                token_vault_a: self.token_vault_a,
+                token_vault_b: self.token_vault_b,
+                token_program_a: spl_token::ID,
+                token_program_b: spl_token::ID,
+                memo_program: spl_memo::ID,
+            }
+            .instruction(CollectFeesInstructionArgs {
+                remaining_accounts_info: None,
+            })
+        }
+
+        fn transfer(&self, source: &Pubkey, mint: &Pubkey, destination: &Pubkey, authority: &Pubkey, amount: u64) -> Instruction {
+            spl_token_2022::instruction::transfer_checked(&spl_token::ID, source, mint, destination, authority, &[], amount, 6).unwrap()
+        }
+    }
+
+    fn compile(message: &VersionedMessage, instruction: &Instruction) -> CompiledInstruction {
+        let account_index = |address: &Pubkey| message.static_account_keys().iter().position(|key| key == address).unwrap() as u8;
+        CompiledInstruction {
+            program_id_index: account_index(&instruction.program_id),
+            accounts: instruction.accounts.iter().map(|account| account_index(&account.pubkey)).collect(),
+            data: instruction.data.clone(),
+        }
+    }
+
+    fn token_balance(message: &VersionedMessage, address: &Pubkey, mint: Pubkey, amount: u64) -> TokenBalance {
+        TokenBalance {
+            account_index: message.static_account_keys().iter().position(|key| key == address).unwrap() as u8,
+            mint,
+            owner: None,
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_extract_events_from_transfers() {
+        let pool = TestPool::new();
+        let position = Pubkey::new_unique();
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(&pool.user, &[pool.swap(true), pool.collect_fees(position)], &[], Hash::default()).unwrap(),
+        );
+        let inner_instructions = vec![
+            CompiledInnerInstructions {
+                index: 0,
+                instructions: vec![
+                    compile(&message, &pool.transfer(&pool.token_owner_account_a, &pool.token_mint_a, &pool.token_vault_a, &pool.user, 1000)),
+                    compile(&message, &pool.transfer(&pool.token_vault_b, &pool.token_mint_b, &pool.token_owner_account_b, &pool.fusion_pool, 990)),
+                ],
+            },
+            CompiledInnerInstructions {
+                index: 1,
+                instructions: vec![
+                    compile(&message, &pool.transfer(&pool.token_vault_a, &pool.token_mint_a, &pool.token_owner_account_a, &pool.fusion_pool, 5)),
+                    compile(&message, &pool.transfer(&pool.token_vault_b, &pool.token_mint_b, &pool.token_owner_account_b, &pool.fusion_pool, 7)),
+                ],
+            },
+        ];
+        let mut transaction = ConfirmedTransaction {
+            message,
+            loaded_addresses: LoadedAddresses::default(),
+            inner_instructions: Some(inner_instructions),
+            pre_token_balances: vec![],
+            post_token_balances: vec![],
+        };
+
+        let events = extract_events(&transaction).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                FusionAmmEvent::Swap(SwapEvent {
+                    instruction_index: 0,
+                    inner_instruction_index: None,
+                    fusion_pool: pool.fusion_pool,
+                    user: pool.user,
+                    a_to_b: true,
+                    input: TokenAmount {
+                        mint: pool.token_mint_a,
+                        amount: 1000
+                    },
+                    output: TokenAmount {
+                        mint: pool.token_mint_b,
+                        amount: 990
+                    },
+                    pre_sqrt_price: None,
+                    post_sqrt_price: None,
+                }),
+                FusionAmmEvent::FeeCollection(FeeCollectionEvent {
+                    instruction_index: 1,
+                    inner_instruction_index: None,
+                    fusion_pool: pool.fusion_pool,
+                    position: Some(position),
+                    user: pool.user,
+                    token_a: TokenAmount {
+                        mint: pool.token_mint_a,
+                        amount: 5
+                    },
+                    token_b: TokenAmount {
+                        mint: pool.token_mint_b,
+                        amount: 7
+                    },
+                }),
+            ]
+        );
+
+        // Without inner instructions, the balance changes of the shared vaults can't be split between the instructions.
+        transaction.inner_instructions = None;
+        assert!(extract_events(&transaction).is_err());
+    }
+
+    #[test]
+    fn test_extract_events_from_balances() {
+        let pool = TestPool::new();
+        let message = VersionedMessage::V0(v0::Message::try_compile(&pool.user, &[pool.swap(false)], &[], Hash::default()).unwrap());
+        let transaction = ConfirmedTransaction {
+            pre_token_balances: vec![
+                token_balance(&message, &pool.token_vault_a, pool.token_mint_a, 10_000),
+                token_balance(&message, &pool.token_vault_b, pool.token_mint_b, 20_000),
+            ],
+            post_token_balances: vec![
+                token_balance(&message, &pool.token_vault_a, pool.token_mint_a, 9_000),
+                token_balance(&message, &pool.token_vault_b, pool.token_mint_b, 21_100),
+            ],
+            message,
+            loaded_addresses: LoadedAddresses::default(),
+            inner_instructions: None,
+        };
+
+        let mut events = extract_events(&transaction).unwrap();
+        assert_eq!(events.len(), 1);
+
+        let mut pre_fusion_pool = FusionPool::from_bytes(&[0; FusionPool::LEN]).unwrap();
+        pre_fusion_pool.sqrt_price = 1 << 64;
+        let mut post_fusion_pool = pre_fusion_pool.clone();
+        post_fusion_pool.sqrt_price = 1 << 63;
+        set_swap_sqrt_prices(
+            &mut events,
+            &HashMap::from([(pool.fusion_pool, pre_fusion_pool)]),
+            &HashMap::from([(pool.fusion_pool, post_fusion_pool)]),
+        );
+
+        let FusionAmmEvent::Swap(swap) = &events[0] else {
+            panic!("Expected a swap event");
+        };
+        assert!(!swap.a_to_b);
+        assert_eq!(
+            swap.input,
+            TokenAmount {
+                mint: pool.token_mint_b,
+                amount: 1100
+            }
+        );
+        assert_eq!(
+            swap.output,
+            TokenAmount {
+                mint: pool.token_mint_a,
+                amount: 1000
+            }
+        );
+        assert_eq!(swap.pre_sqrt_price, Some(1 << 64));
+        assert_eq!(swap.post_sqrt_price, Some(1 << 63));
+    }
+}
@@ -10,12 +10,11 @@
 
 use crate::{
     token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy},
-    FUNDER,
+    AccountSource, FUNDER,
 };
 use fusionamm_client::{get_position_address, get_tick_array_address, FusionPool, Position, TickArray};
 use fusionamm_client::{CollectFees, CollectFeesInstructionArgs, UpdateFees};
 use fusionamm_core::{collect_fees_quote, get_tick_array_start_tick_index, get_tick_index_in_array, CollectFeesQuote};
-use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_instruction::Instruction;
 use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
@@ -95,7 +94,7 @@ pub struct HarvestPositionInstruction {
 /// }
 /// ```
 pub async fn harvest_position_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     position_mint_address: Pubkey,
     authority: Option<Pubkey>,
 ) -> Result<HarvestPositionInstruction, Box<dyn Error>> {
@@ -119,7 +118,7 @@ pub async fn harvest_position_instructions(
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
     let position_mint_info = mint_infos[2].as_ref().ok_or("Position mint info not found")?;
 
-    let current_epoch = rpc.get_epoch_info().await?.epoch;
+    let current_epoch = rpc.get_epoch().await?;
     let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
     let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
 
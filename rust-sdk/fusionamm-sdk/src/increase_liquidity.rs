@@ -8,7 +8,7 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use crate::get_rent;
+use crate::AccountSource;
 use crate::{
     token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy},
     FUNDER, SLIPPAGE_TOLERANCE_BPS,
@@ -23,7 +23,6 @@ use fusionamm_core::{
     increase_liquidity_quote_b, order_tick_indexes, price_to_tick_index, IncreaseLiquidityQuote, TransferFee,
 };
 use solana_account::Account;
-use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_instruction::Instruction;
 use solana_keypair::Keypair;
 use solana_program::program_pack::Pack;
@@ -40,7 +39,7 @@ pub enum PriceOrTickIndex {
 
 // TODO: support transfer hooks
 
-fn get_increase_liquidity_quote(
+pub(crate) fn get_increase_liquidity_quote(
     param: IncreaseLiquidityParam,
     slippage_tolerance_bps: u16,
     pool: &FusionPool,
@@ -179,7 +178,7 @@ pub struct IncreaseLiquidityInstruction {
 /// }
 /// ```
 pub async fn increase_liquidity_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     position_mint_address: Pubkey,
     param: IncreaseLiquidityParam,
     slippage_tolerance_bps: Option<u16>,
@@ -206,7 +205,7 @@ pub async fn increase_liquidity_instructions(
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
     let position_mint_info = mint_infos[2].as_ref().ok_or("Position mint info not found")?;
 
-    let current_epoch = rpc.get_epoch_info().await?.epoch;
+    let current_epoch = rpc.get_epoch().await?;
     let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
     let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
 
@@ -311,7 +310,7 @@ pub struct OpenPositionInstruction {
 
 #[allow(clippy::too_many_arguments)]
 async fn internal_open_position(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     pool_address: Pubkey,
     fusion_pool: FusionPool,
     param: IncreaseLiquidityParam,
@@ -324,7 +323,7 @@ async fn internal_open_position(
 ) -> Result<OpenPositionInstruction, Box<dyn Error>> {
     let funder = funder.unwrap_or(*FUNDER.try_lock()?);
     let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
-    let rent = get_rent(rpc).await?;
+    let rent = rpc.get_rent().await?;
     if funder == Pubkey::default() {
         return Err("Funder must be provided".into());
     }
@@ -339,7 +338,7 @@ async fn internal_open_position(
     let mut non_refundable_rent: u64 = 0;
     let mut additional_signers: Vec<Keypair> = Vec::new();
 
-    let epoch = rpc.get_epoch_info().await?.epoch;
+    let epoch = rpc.get_epoch().await?;
     let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), epoch);
     let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), epoch);
 
@@ -537,7 +536,7 @@ async fn internal_open_position(
 /// }
 /// ```
 pub async fn open_full_range_position_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     pool_address: Pubkey,
     param: IncreaseLiquidityParam,
     slippage_tolerance_bps: Option<u16>,
@@ -632,7 +631,7 @@ pub async fn open_full_range_position_instructions(
 /// }
 /// ```
 pub async fn open_position_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     pool_address: Pubkey,
     lower_price_or_tick_index: PriceOrTickIndex,
     upper_price_or_tick_index: PriceOrTickIndex,
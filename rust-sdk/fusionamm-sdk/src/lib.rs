@@ -9,16 +9,25 @@
 //
 
 mod account;
+mod account_source;
+mod admin;
 mod config;
 mod create_pool;
 mod decrease_liquidity;
+mod events;
 mod harvest;
 mod increase_liquidity;
 mod limit_order;
+mod lock;
+mod offline;
 mod pool;
 mod position;
+mod position_bundle;
+mod rebalance;
+mod router;
 mod swap;
 mod token;
+mod tracker;
 
 #[cfg(test)]
 mod e2e;
@@ -27,13 +36,22 @@ mod e2e;
 mod tests;
 
 pub use account::*;
+pub use account_source::*;
+pub use admin::*;
 pub use config::*;
 pub use create_pool::*;
 pub use decrease_liquidity::*;
+pub use events::*;
 pub use harvest::*;
 pub use increase_liquidity::*;
 pub use limit_order::*;
+pub use lock::*;
+pub use offline::*;
 pub use pool::*;
 pub use position::*;
+pub use position_bundle::*;
+pub use rebalance::*;
+pub use router::*;
 pub use swap::*;
 pub use token::*;
+pub use tracker::*;
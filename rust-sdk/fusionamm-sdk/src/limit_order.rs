@@ -1,5 +1,6 @@
-use crate::account::get_rent;
+use crate::account::get_token_accounts_for_owner;
 use crate::token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy};
+use crate::AccountSource;
 use crate::{PriceOrTickIndex, FUNDER};
 use fusionamm_client::{
     get_limit_order_address, get_tick_array_address, CloseLimitOrder, DecreaseLimitOrder, DecreaseLimitOrderInstructionArgs, FusionPool,
@@ -7,10 +8,11 @@ use fusionamm_client::{
     OpenLimitOrderInstructionArgs, TickArray, FP_NFT_UPDATE_AUTH,
 };
 use fusionamm_core::{
-    decrease_limit_order_quote, get_initializable_tick_index, get_tick_array_start_tick_index, price_to_tick_index, try_reverse_apply_transfer_fee,
-    LimitOrderDecreaseQuote,
+    decrease_limit_order_quote, get_initializable_tick_index, get_tick_array_start_tick_index, get_tick_index_in_array, limit_order_fill_quote,
+    price_to_tick_index, try_reverse_apply_transfer_fee, LimitOrderDecreaseQuote, LimitOrderFillQuote,
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::TokenAccountsFilter;
 use solana_keypair::Keypair;
 use solana_program::instruction::Instruction;
 use solana_program::program_pack::Pack;
@@ -18,6 +20,7 @@ use solana_program::pubkey::Pubkey;
 use solana_signer::Signer;
 use spl_associated_token_account::get_associated_token_address_with_program_id;
 use spl_token_2022::state::Mint;
+use std::collections::HashMap;
 use std::error::Error;
 
 #[derive(Debug)]
@@ -68,6 +71,164 @@ pub struct DecreaseLimitOrderInstruction {
     pub additional_signers: Vec<Keypair>,
 }
 
+/// Represents a single LimitOrder account held by a wallet.
+///
+/// This struct contains the address of the limit order, its decoded data, the token program
+/// associated with the limit order NFT and the fill progress of the order.
+#[derive(Debug)]
+pub struct HydratedLimitOrder {
+    /// The public key of the LimitOrder account.
+    pub address: Pubkey,
+
+    /// The decoded `LimitOrder` account data.
+    pub data: LimitOrder,
+
+    /// The public key of the token program associated with the limit order NFT.
+    pub token_program: Pubkey,
+
+    /// The status of the limit order with the amounts claimable by its owner,
+    /// or `None` if the pool or the tick array of the order could not be found.
+    pub fill_quote: Option<LimitOrderFillQuote>,
+}
+
+/// Finds the mints of the tokens a wallet holds that may be limit order NFTs.
+///
+/// This is the discovery step of `fetch_limit_orders_for_owner`. It returns the mints of the Token 2022 accounts
+/// owned by the wallet that hold exactly one token. It relies on `getTokenAccountsByOwner`, so it needs an RPC client.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `owner` - The public key of the wallet whose limit orders should be found.
+///
+/// # Returns
+///
+/// A `Result` containing the mints of the potential limit order NFTs.
+///
+/// # Errors
+///
+/// This function will return an error if the token accounts cannot be fetched.
+pub async fn fetch_limit_order_mints_for_owner(rpc: &RpcClient, owner: Pubkey) -> Result<Vec<Pubkey>, Box<dyn Error>> {
+    let token_accounts = get_token_accounts_for_owner(rpc, owner, TokenAccountsFilter::ProgramId(spl_token_2022::ID)).await?;
+    Ok(token_accounts.into_iter().filter(|x| x.amount == 1).map(|x| x.mint).collect())
+}
+
+/// Fetches the limit orders held by a wallet in the FusionAMM pools.
+///
+/// For each mint that is a limit order NFT, this function fetches the limit order, the pool and the tick
+/// the order rests on and computes the order status and claimable amounts. Mints that are not limit order NFTs are skipped.
+/// Orders whose pool or tick array is missing are returned without a fill quote.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to an account source, e.g. the Solana RPC client.
+/// * `limit_order_mints` - The mints of the potential limit order NFTs held by the wallet, e.g. from `fetch_limit_order_mints_for_owner`.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `HydratedLimitOrder` objects.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - Limit order or tick array addresses cannot be derived.
+/// - The pool or the tick array of a limit order has invalid data.
+/// - Any request to the account source fails.
+///
+/// # Example
+/// ```rust
+/// use fusionamm_sdk::{fetch_limit_order_mints_for_owner, fetch_limit_orders_for_owner};
+/// use solana_client::nonblocking::rpc_client::RpcClient;
+/// use solana_pubkey::pubkey;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rpc = RpcClient::new("https://api.devnet.solana.com".to_string());
+///     let owner = pubkey!("FTEV6CnregJCqU8s8hGR3VAYCrPKHfekXLsJaKHbPBxp");
+///
+///     let limit_order_mints = fetch_limit_order_mints_for_owner(&rpc, owner).await.unwrap();
+///     let limit_orders = fetch_limit_orders_for_owner(&rpc, &limit_order_mints)
+///         .await
+///         .unwrap();
+///
+///     for limit_order in limit_orders {
+///         println!("{}: {:?}", limit_order.address, limit_order.fill_quote.map(|x| x.status));
+///     }
+/// }
+/// ```
+pub async fn fetch_limit_orders_for_owner(rpc: &impl AccountSource, limit_order_mints: &[Pubkey]) -> Result<Vec<HydratedLimitOrder>, Box<dyn Error>> {
+    let limit_order_addresses: Vec<Pubkey> = limit_order_mints
+        .iter()
+        .map(|x| get_limit_order_address(x).map(|x| x.0))
+        .collect::<Result<Vec<Pubkey>, _>>()?;
+
+    let limit_order_infos = rpc.get_multiple_accounts(&limit_order_addresses).await?;
+
+    let limit_orders: Vec<(usize, LimitOrder)> = limit_order_infos
+        .iter()
+        .enumerate()
+        .filter_map(|(i, x)| x.as_ref().and_then(|x| LimitOrder::from_bytes(&x.data).ok()).map(|x| (i, x)))
+        .collect();
+
+    let mut fusion_pool_addresses: Vec<Pubkey> = limit_orders.iter().map(|x| x.1.fusion_pool).collect();
+    fusion_pool_addresses.sort();
+    fusion_pool_addresses.dedup();
+
+    let fusion_pool_infos = rpc.get_multiple_accounts(&fusion_pool_addresses).await?;
+    let mut fusion_pools: HashMap<Pubkey, FusionPool> = HashMap::new();
+    for (address, info) in fusion_pool_addresses.iter().zip(fusion_pool_infos) {
+        if let Some(info) = info {
+            fusion_pools.insert(*address, FusionPool::from_bytes(&info.data)?);
+        }
+    }
+
+    let mut tick_array_addresses: Vec<Option<Pubkey>> = Vec::new();
+    for (_, limit_order) in &limit_orders {
+        let Some(fusion_pool) = fusion_pools.get(&limit_order.fusion_pool) else {
+            tick_array_addresses.push(None);
+            continue;
+        };
+        let tick_array_start_index = get_tick_array_start_tick_index(limit_order.tick_index, fusion_pool.tick_spacing);
+        tick_array_addresses.push(Some(get_tick_array_address(&limit_order.fusion_pool, tick_array_start_index)?.0));
+    }
+    let mut unique_tick_array_addresses: Vec<Pubkey> = tick_array_addresses.iter().flatten().copied().collect();
+    unique_tick_array_addresses.sort();
+    unique_tick_array_addresses.dedup();
+
+    let tick_array_infos = rpc.get_multiple_accounts(&unique_tick_array_addresses).await?;
+    let mut tick_arrays: HashMap<Pubkey, TickArray> = HashMap::new();
+    for (address, info) in unique_tick_array_addresses.iter().zip(tick_array_infos) {
+        if let Some(info) = info {
+            tick_arrays.insert(*address, TickArray::from_bytes(&info.data)?);
+        }
+    }
+
+    let mut hydrated_limit_orders: Vec<HydratedLimitOrder> = Vec::new();
+
+    for ((i, limit_order), tick_array_address) in limit_orders.into_iter().zip(tick_array_addresses) {
+        let fusion_pool = fusion_pools.get(&limit_order.fusion_pool);
+        let tick_array = tick_array_address.and_then(|x| tick_arrays.get(&x));
+
+        let fill_quote = match (fusion_pool, tick_array) {
+            (Some(fusion_pool), Some(tick_array)) => {
+                let tick_index_in_array = get_tick_index_in_array(limit_order.tick_index, tick_array.start_tick_index, fusion_pool.tick_spacing)?;
+                let tick = &tick_array.ticks[tick_index_in_array as usize];
+                Some(limit_order_fill_quote(fusion_pool.clone().into(), limit_order.clone().into(), tick.clone().into())?)
+            }
+            _ => None,
+        };
+
+        hydrated_limit_orders.push(HydratedLimitOrder {
+            address: limit_order_addresses[i],
+            data: limit_order,
+            token_program: spl_token_2022::ID,
+            fill_quote,
+        });
+    }
+
+    Ok(hydrated_limit_orders)
+}
+
 #[cfg(not(doctest))]
 /// Opens a limit order in a liquidity pool at a specific tick index.
 ///
@@ -132,7 +293,7 @@ pub struct DecreaseLimitOrderInstruction {
 /// }
 /// ```
 pub async fn open_limit_order_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     pool_address: Pubkey,
     amount: u64,
     price_or_tick_index: PriceOrTickIndex,
@@ -140,7 +301,7 @@ pub async fn open_limit_order_instructions(
     funder: Option<Pubkey>,
 ) -> Result<OpenLimitOrderInstruction, Box<dyn Error>> {
     let funder = funder.unwrap_or(*FUNDER.try_lock()?);
-    let rent = get_rent(rpc).await?;
+    let rent = rpc.get_rent().await?;
     if funder == Pubkey::default() {
         return Err("Funder must be provided".into());
     }
@@ -179,7 +340,7 @@ pub async fn open_limit_order_instructions(
 
     let initializable_tick_index = get_initializable_tick_index(tick_index, fusion_pool.tick_spacing, Some(false));
 
-    let epoch = rpc.get_epoch_info().await?.epoch;
+    let epoch = rpc.get_epoch().await?;
     let transfer_fee = get_current_transfer_fee(Some(mint_info), epoch);
     let amount_with_fee = if transfer_fee.is_some() {
         try_reverse_apply_transfer_fee(amount, transfer_fee.unwrap_or_default())?
@@ -298,7 +459,7 @@ pub async fn open_limit_order_instructions(
 /// - The pool or token mint accounts are not found or invalid.
 /// - Any RPC request fails.
 pub async fn increase_limit_order_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     limit_order_mint: Pubkey,
     amount: u64,
     authority: Option<Pubkey>,
@@ -332,7 +493,7 @@ pub async fn increase_limit_order_instructions(
     let limit_order_token_account_address = get_associated_token_address_with_program_id(&funder, &limit_order_mint, &spl_token_2022::ID);
     let tick_array_address = get_tick_array_address(&limit_order.fusion_pool, tick_array_start_index)?.0;
 
-    let epoch = rpc.get_epoch_info().await?.epoch;
+    let epoch = rpc.get_epoch().await?;
     let transfer_fee = get_current_transfer_fee(Some(mint_info), epoch);
     let amount_with_fee = if transfer_fee.is_some() {
         try_reverse_apply_transfer_fee(amount, transfer_fee.unwrap_or_default())?
@@ -437,7 +598,7 @@ pub async fn increase_limit_order_instructions(
 /// }
 /// ```
 pub async fn close_limit_order_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     limit_order_mint: Pubkey,
     authority: Option<Pubkey>,
 ) -> Result<DecreaseLimitOrderInstruction, Box<dyn Error>> {
@@ -502,7 +663,7 @@ pub async fn close_limit_order_instructions(
 /// }
 /// ```
 pub async fn decrease_limit_order_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     limit_order_mint: Pubkey,
     amount: u64,
     authority: Option<Pubkey>,
@@ -511,7 +672,7 @@ pub async fn decrease_limit_order_instructions(
 }
 
 async fn internal_decrease_and_close_limit_order_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     limit_order_mint: Pubkey,
     amount: Option<u64>,
     authority: Option<Pubkey>,
@@ -548,7 +709,7 @@ async fn internal_decrease_and_close_limit_order_instructions(
         Some(amount) => amount,
     };
 
-    let current_epoch = rpc.get_epoch_info().await?.epoch;
+    let current_epoch = rpc.get_epoch().await?;
     let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
     let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
 
@@ -622,7 +783,8 @@ async fn internal_decrease_and_close_limit_order_instructions(
 #[cfg(test)]
 mod tests {
     use crate::{
-        close_limit_order_instructions, decrease_limit_order_instructions, increase_limit_order_instructions, open_limit_order_instructions,
+        close_limit_order_instructions, decrease_limit_order_instructions, fetch_limit_orders_for_owner, increase_limit_order_instructions,
+        open_limit_order_instructions,
         tests::{
             setup_ata_te, setup_ata_with_amount, setup_fusion_pool, setup_mint_te, setup_mint_te_fee, setup_mint_with_decimals, RpcContext,
             SetupAtaConfig,
@@ -630,6 +792,7 @@ mod tests {
         DecreaseLimitOrderInstruction, IncreaseLimitOrderInstruction, OpenLimitOrderInstruction, PriceOrTickIndex,
     };
     use fusionamm_client::{get_limit_order_address, LimitOrder};
+    use fusionamm_core::LimitOrderStatus;
     use rstest::rstest;
     use serial_test::serial;
     use solana_client::nonblocking::rpc_client::RpcClient;
@@ -933,4 +1096,49 @@ mod tests {
                 .unwrap();
         });
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fetch_limit_orders_for_owner() {
+        let ctx = RpcContext::new().await;
+        let minted = setup_all_mints(&ctx).await.unwrap();
+        setup_all_atas(&ctx, &minted).await.unwrap();
+
+        let (mint_a, mint_b) = (minted["A"], minted["B"]);
+        let (token_a, token_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        let pool_pubkey = setup_fusion_pool(&ctx, token_a, token_b, 64, 300).await.unwrap();
+
+        let mut limit_order_mints: Vec<Pubkey> = Vec::new();
+        for (amount, tick_index, a_to_b) in [(2_000_000, 128, true), (1_000_000, -128, false)] {
+            let open_ix =
+                open_limit_order_instructions(&ctx.rpc, pool_pubkey, amount, PriceOrTickIndex::Tick(tick_index), a_to_b, Some(ctx.signer.pubkey()))
+                    .await
+                    .unwrap();
+            let signers: Vec<&Keypair> = open_ix.additional_signers.iter().collect();
+            ctx.send_transaction_with_signers(open_ix.instructions.clone(), signers).await.unwrap();
+            limit_order_mints.push(open_ix.limit_order_mint);
+        }
+
+        // Mints that are not limit order NFTs are skipped.
+        let limit_orders = fetch_limit_orders_for_owner(&ctx.rpc, &[limit_order_mints[0], mint_a, limit_order_mints[1], Pubkey::new_unique()])
+            .await
+            .unwrap();
+
+        assert_eq!(limit_orders.len(), 2);
+        for (limit_order, (limit_order_mint, amount)) in limit_orders
+            .iter()
+            .zip([(limit_order_mints[0], 2_000_000), (limit_order_mints[1], 1_000_000)])
+        {
+            assert_eq!(limit_order.address, get_limit_order_address(&limit_order_mint).unwrap().0);
+            assert_eq!(limit_order.data.fusion_pool, pool_pubkey);
+            assert_eq!(limit_order.token_program, TOKEN_2022_PROGRAM_ID);
+
+            let fill_quote = limit_order.fill_quote.unwrap();
+            assert_eq!(fill_quote.status, LimitOrderStatus::Open);
+            assert_eq!(fill_quote.filled_amount, 0);
+            assert_eq!(fill_quote.filled_bps, 0);
+            assert_eq!(fill_quote.claimable_input, amount);
+            assert_eq!(fill_quote.claimable_output, 0);
+        }
+    }
 }
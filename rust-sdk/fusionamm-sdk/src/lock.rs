@@ -0,0 +1,244 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::{AccountSource, FUNDER};
+use fusionamm_client::{
+    get_position_address, get_position_lock_address, LockPosition, LockPositionInstructionArgs, Position, PositionLock, PositionLockType,
+};
+use solana_account::Account;
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::error::Error;
+
+/// Errors returned when building instructions for a position that is (or can't be) locked.
+///
+/// The errors are returned boxed as `Box<dyn Error>` and can be recovered with `downcast_ref::<PositionLockError>()`.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PositionLockError {
+    /// The position is locked, so its liquidity can't be decreased and it can't be closed.
+    #[error("Position {position_mint} is locked ({lock_type:?}) since {locked_timestamp}")]
+    PositionLocked {
+        position_mint: Pubkey,
+        lock_type: PositionLockType,
+        locked_timestamp: u64,
+    },
+
+    /// The position is already locked.
+    #[error("Position {0} is already locked")]
+    PositionAlreadyLocked(Pubkey),
+
+    /// The position can't be locked because it has no liquidity.
+    #[error("Position {0} is empty and can't be locked")]
+    PositionNotLockable(Pubkey),
+}
+
+/// Represents the instructions for locking a position.
+#[derive(Debug)]
+pub struct LockPositionInstruction {
+    /// The public key of the position lock account.
+    pub position_lock: Pubkey,
+
+    /// A vector of `Instruction` objects required to lock the position.
+    pub instructions: Vec<Instruction>,
+
+    /// The cost of initializing the position lock account, measured in lamports.
+    pub initialization_cost: u64,
+}
+
+// Returns an error if the position lock account exists, i.e. the position is locked.
+pub(crate) fn ensure_position_unlocked(position_mint: Pubkey, position_lock_info: Option<&Account>) -> Result<(), Box<dyn Error>> {
+    if let Some(position_lock_info) = position_lock_info {
+        let position_lock = PositionLock::from_bytes(&position_lock_info.data)?;
+        return Err(Box::new(PositionLockError::PositionLocked {
+            position_mint,
+            lock_type: position_lock.lock_type,
+            locked_timestamp: position_lock.locked_timestamp,
+        }));
+    }
+    Ok(())
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to lock a position.
+///
+/// A locked position can't be closed and its liquidity can't be decreased, but the fees can still be harvested.
+/// Only positions with liquidity can be locked.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client for fetching accounts.
+/// * `position_mint_address` - The public key of the NFT mint address representing the position to be locked.
+/// * `lock_type` - The type of the lock.
+/// * `authority` - An optional public key of the position owner, who also funds the position lock account. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `LockPositionInstruction` on success:
+///
+/// * `position_lock` - The public key of the position lock account.
+/// * `instructions` - A vector of `Instruction` objects required to lock the position.
+/// * `initialization_cost` - The cost of initializing the position lock account, measured in lamports.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` account is invalid or missing.
+/// - The position is already locked or is empty (`PositionLockError`).
+/// - The position or position mint accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+///
+/// # Example
+///
+/// ```rust
+/// use fusionamm_client::PositionLockType;
+/// use fusionamm_sdk::lock_position_instructions;
+/// use solana_client::nonblocking::rpc_client::RpcClient;
+/// use solana_pubkey::pubkey;
+/// use solana_keypair::Keypair;
+/// use solana_signer::Signer;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rpc = RpcClient::new("https://api.mainnet.solana.com".to_string());
+///     let wallet = Keypair::new(); // Load your wallet here
+///
+///     let position_mint_address = pubkey!("HqoV7Qv27REUtmd9UKSJGGmCRNx3531t33bDG1BUfo9K");
+///
+///     let result = lock_position_instructions(&rpc, position_mint_address, PositionLockType::Permanent, Some(wallet.pubkey()))
+///         .await
+///         .unwrap();
+///
+///     println!("Position lock: {}", result.position_lock);
+/// }
+/// ```
+pub async fn lock_position_instructions(
+    rpc: &impl AccountSource,
+    position_mint_address: Pubkey,
+    lock_type: PositionLockType,
+    authority: Option<Pubkey>,
+) -> Result<LockPositionInstruction, Box<dyn Error>> {
+    let authority = authority.unwrap_or(*FUNDER.try_lock()?);
+    if authority == Pubkey::default() {
+        return Err("Authority must be provided".into());
+    }
+    let rent = rpc.get_rent().await?;
+
+    let position_address = get_position_address(&position_mint_address)?.0;
+    let position_lock_address = get_position_lock_address(&position_mint_address)?.0;
+
+    let infos = rpc
+        .get_multiple_accounts(&[position_address, position_mint_address, position_lock_address])
+        .await?;
+
+    let position_info = infos[0].as_ref().ok_or("Position info not found")?;
+    let position = Position::from_bytes(&position_info.data)?;
+    let position_mint_info = infos[1].as_ref().ok_or("Position mint info not found")?;
+
+    if infos[2].is_some() {
+        return Err(Box::new(PositionLockError::PositionAlreadyLocked(position_mint_address)));
+    }
+    if position.liquidity == 0 {
+        return Err(Box::new(PositionLockError::PositionNotLockable(position_mint_address)));
+    }
+    if position_mint_info.owner != spl_token_2022::ID {
+        return Err("Unsupported token program".into());
+    }
+
+    let position_token_account_address = get_associated_token_address_with_program_id(&authority, &position_mint_address, &position_mint_info.owner);
+
+    let instruction = LockPosition {
+        funder: authority,
+        position_authority: authority,
+        position: position_address,
+        position_mint: position_mint_address,
+        position_token_account: position_token_account_address,
+        position_lock: position_lock_address,
+        fusion_pool: position.fusion_pool,
+        token2022_program: spl_token_2022::ID,
+        system_program: solana_program::system_program::id(),
+    }
+    .instruction(LockPositionInstructionArgs { lock_type });
+
+    Ok(LockPositionInstruction {
+        position_lock: position_lock_address,
+        instructions: vec![instruction],
+        initialization_cost: rent.minimum_balance(PositionLock::LEN),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fusionamm_client::{PositionLock, PositionLockType};
+    use serial_test::serial;
+    use solana_program_test::tokio;
+    use solana_pubkey::Pubkey;
+    use solana_signer::Signer;
+
+    use crate::{
+        close_position_instructions, decrease_liquidity_instructions, harvest_position_instructions, increase_liquidity_instructions,
+        lock_position_instructions,
+        tests::{setup_ata_with_amount, setup_fusion_pool, setup_mint_with_decimals, setup_position, RpcContext},
+        DecreaseLiquidityParam, IncreaseLiquidityParam, PositionLockError,
+    };
+
+    async fn setup_pool(ctx: &RpcContext) -> Pubkey {
+        let mint_1 = setup_mint_with_decimals(ctx, 9).await.unwrap();
+        let mint_2 = setup_mint_with_decimals(ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_1 < mint_2 { (mint_1, mint_2) } else { (mint_2, mint_1) };
+        setup_ata_with_amount(ctx, mint_a, 1_000_000_000).await.unwrap();
+        setup_ata_with_amount(ctx, mint_b, 1_000_000_000).await.unwrap();
+        setup_fusion_pool(ctx, mint_a, mint_b, 64, 300).await.unwrap()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_lock_position() {
+        let ctx = RpcContext::new().await;
+        let pool = setup_pool(&ctx).await;
+        let position_mint = setup_position(&ctx, pool, Some((-192, 192)), None).await.unwrap();
+
+        let error = lock_position_instructions(&ctx.rpc, position_mint, PositionLockType::Permanent, Some(ctx.signer.pubkey()))
+            .await
+            .unwrap_err();
+        assert_eq!(error.downcast_ref::<PositionLockError>(), Some(&PositionLockError::PositionNotLockable(position_mint)));
+
+        let ix = increase_liquidity_instructions(&ctx.rpc, position_mint, IncreaseLiquidityParam::Liquidity(1_000_000), Some(100), None)
+            .await
+            .unwrap();
+        ctx.send_transaction_with_signers(ix.instructions, ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+
+        let ix = lock_position_instructions(&ctx.rpc, position_mint, PositionLockType::Permanent, Some(ctx.signer.pubkey()))
+            .await
+            .unwrap();
+        assert!(ix.initialization_cost > 0);
+        ctx.send_transaction(ix.instructions).await.unwrap();
+
+        let position_lock_info = ctx.rpc.get_account(&ix.position_lock).await.unwrap();
+        let position_lock = PositionLock::from_bytes(&position_lock_info.data).unwrap();
+        assert_eq!(position_lock.lock_type, PositionLockType::Permanent);
+        assert_eq!(position_lock.position_mint, position_mint);
+
+        let error = lock_position_instructions(&ctx.rpc, position_mint, PositionLockType::Permanent, Some(ctx.signer.pubkey()))
+            .await
+            .unwrap_err();
+        assert_eq!(error.downcast_ref::<PositionLockError>(), Some(&PositionLockError::PositionAlreadyLocked(position_mint)));
+
+        let error = decrease_liquidity_instructions(&ctx.rpc, position_mint, DecreaseLiquidityParam::Liquidity(1), Some(100), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(error.downcast_ref::<PositionLockError>(), Some(PositionLockError::PositionLocked { .. })));
+
+        let error = close_position_instructions(&ctx.rpc, position_mint, Some(100), None).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<PositionLockError>(), Some(PositionLockError::PositionLocked { .. })));
+
+        // Fees can still be harvested from a locked position.
+        harvest_position_instructions(&ctx.rpc, position_mint, None).await.unwrap();
+    }
+}
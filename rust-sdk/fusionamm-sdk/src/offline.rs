@@ -0,0 +1,348 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::{AccountSource, CachedAccountSource};
+use borsh::BorshSerialize;
+use fusionamm_client::{FusionPool, LimitOrder, Position, TickArray, FUSIONAMM_ID};
+use solana_account::Account;
+use solana_program::program_pack::Pack;
+use solana_program::rent::Rent;
+use solana_pubkey::Pubkey;
+use spl_token_2022::state::{Account as TokenAccount, AccountState};
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+use std::{collections::HashMap, error::Error};
+
+/// The on-chain state needed to build instructions without network access.
+///
+/// Unlike `AccountSnapshot`, an offline state distinguishes accounts that are known not to exist from
+/// accounts that were never captured. Reading an account, the epoch or the rent that is not part of the
+/// state is an error, so a builder can never silently produce different instructions than it would online.
+#[derive(Debug, Clone, Default)]
+pub struct OfflineState {
+    /// The captured accounts by address. `None` means the account is known not to exist.
+    pub accounts: HashMap<Pubkey, Option<Account>>,
+
+    /// The captured epoch, used for the transfer fees of Token-2022 mints.
+    pub epoch: Option<u64>,
+
+    /// The captured slot.
+    pub slot: Option<u64>,
+
+    /// The captured rent sysvar.
+    pub rent: Option<Rent>,
+}
+
+impl OfflineState {
+    /// Creates an empty offline state with the given epoch and rent.
+    pub fn new(epoch: u64, rent: Rent) -> Self {
+        Self {
+            accounts: HashMap::new(),
+            epoch: Some(epoch),
+            slot: None,
+            rent: Some(rent),
+        }
+    }
+
+    /// Returns the addresses of all captured accounts, including the ones known not to exist, in canonical byte order.
+    pub fn addresses(&self) -> Vec<Pubkey> {
+        let mut addresses: Vec<Pubkey> = self.accounts.keys().copied().collect();
+        addresses.sort();
+        addresses
+    }
+
+    /// Adds a raw account, such as a token mint with its extensions. Pass `None` to record that the account does not exist.
+    pub fn insert_account(&mut self, address: Pubkey, account: Option<Account>) {
+        self.accounts.insert(address, account);
+    }
+
+    /// Adds a decoded fusion pool.
+    pub fn insert_fusion_pool(&mut self, address: Pubkey, fusion_pool: &FusionPool) -> Result<(), Box<dyn Error>> {
+        self.insert_program_account(address, fusion_pool)
+    }
+
+    /// Adds a decoded tick array.
+    pub fn insert_tick_array(&mut self, address: Pubkey, tick_array: &TickArray) -> Result<(), Box<dyn Error>> {
+        self.insert_program_account(address, tick_array)
+    }
+
+    /// Adds a decoded position.
+    pub fn insert_position(&mut self, address: Pubkey, position: &Position) -> Result<(), Box<dyn Error>> {
+        self.insert_program_account(address, position)
+    }
+
+    /// Adds a decoded limit order.
+    pub fn insert_limit_order(&mut self, address: Pubkey, limit_order: &LimitOrder) -> Result<(), Box<dyn Error>> {
+        self.insert_program_account(address, limit_order)
+    }
+
+    /// Adds an initialized token account holding `amount` tokens of `mint`.
+    ///
+    /// The instruction builders only read the balance of token accounts, so the account is stored without extensions.
+    pub fn insert_token_account(
+        &mut self,
+        address: Pubkey,
+        token_program: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let token_account = TokenAccount {
+            mint,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        };
+        let mut data = vec![0; TokenAccount::LEN];
+        TokenAccount::pack(token_account, &mut data)?;
+        self.insert_account(address, Some(self.new_account(token_program, data)));
+        Ok(())
+    }
+
+    /// Runs an instruction builder against this state and returns its result.
+    ///
+    /// The builder must only read from the state passed to it, which never waits on the network,
+    /// so the builder completes synchronously.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let swap = offline_state.build(|state| swap_instructions(state, fusion_pool_address, 1_000_000, mint, SwapType::ExactIn, Some(100), Some(signer)))?;
+    /// ```
+    pub fn build<'a, T, F>(&'a self, builder: impl FnOnce(&'a Self) -> F) -> Result<T, Box<dyn Error>>
+    where
+        F: Future<Output = Result<T, Box<dyn Error>>>,
+    {
+        let mut future = pin!(builder(self));
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(result) => result,
+            Poll::Pending => Err("The instruction builder waited on a source other than the offline state".into()),
+        }
+    }
+
+    fn insert_program_account(&mut self, address: Pubkey, value: &impl BorshSerialize) -> Result<(), Box<dyn Error>> {
+        let data = value.try_to_vec()?;
+        self.insert_account(address, Some(self.new_account(FUSIONAMM_ID, data)));
+        Ok(())
+    }
+
+    fn new_account(&self, owner: Pubkey, data: Vec<u8>) -> Account {
+        Account {
+            lamports: self.rent.as_ref().map(|rent| rent.minimum_balance(data.len())).unwrap_or_default(),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn captured_account(&self, address: &Pubkey) -> Result<Option<Account>, Box<dyn Error>> {
+        self.accounts
+            .get(address)
+            .cloned()
+            .ok_or_else(|| format!("Account {} is not part of the offline state", address).into())
+    }
+}
+
+impl AccountSource for OfflineState {
+    async fn get_account(&self, address: &Pubkey) -> Result<Account, Box<dyn Error>> {
+        Ok(self.captured_account(address)?.ok_or(format!("Account {} not found", address))?)
+    }
+
+    async fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>, Box<dyn Error>> {
+        addresses.iter().map(|address| self.captured_account(address)).collect()
+    }
+
+    async fn get_epoch(&self) -> Result<u64, Box<dyn Error>> {
+        Ok(self.epoch.ok_or("Epoch is not part of the offline state")?)
+    }
+
+    async fn get_rent(&self) -> Result<Rent, Box<dyn Error>> {
+        Ok(self.rent.ok_or("Rent is not part of the offline state")?)
+    }
+
+    async fn get_slot(&self) -> Result<u64, Box<dyn Error>> {
+        Ok(self.slot.ok_or("Slot is not part of the offline state")?)
+    }
+}
+
+/// Captures the on-chain state an instruction builder reads, so that the same builder can run on an offline host.
+///
+/// The builder is run once against the online source. Every account, including the ones that do not exist,
+/// as well as the epoch and the rent it reads are recorded in the returned `OfflineState`.
+///
+/// # Arguments
+///
+/// * `source` - The online account source, e.g. a reference to an RPC client.
+/// * `builder` - The instruction builder to capture the state for, called with the recording source.
+///
+/// # Returns
+///
+/// A `Result` containing the `OfflineState` to transfer to the offline host.
+///
+/// # Errors
+///
+/// This function will return an error if the builder fails against the online source.
+///
+/// # Example
+///
+/// ```ignore
+/// let offline_state = fetch_offline_state(&rpc, async |source| {
+///     swap_instructions(source, fusion_pool_address, 1_000_000, mint, SwapType::ExactIn, Some(100), Some(signer)).await
+/// })
+/// .await?;
+/// ```
+pub async fn fetch_offline_state<S: AccountSource, T>(
+    source: S,
+    builder: impl AsyncFnOnce(&CachedAccountSource<S>) -> Result<T, Box<dyn Error>>,
+) -> Result<OfflineState, Box<dyn Error>> {
+    let cache = CachedAccountSource::new(source);
+    builder(&cache).await?;
+    cache.into_offline_state()
+}
+
+/// Lists the accounts an instruction builder reads, i.e. the accounts the online host must fetch for an action.
+///
+/// Which accounts are needed depends on on-chain state (e.g. the tick arrays around the current pool price),
+/// so the builder is dry-run against the online source, the same way as in `fetch_offline_state`.
+///
+/// # Arguments
+///
+/// * `source` - The online account source, e.g. a reference to an RPC client.
+/// * `builder` - The instruction builder to list the accounts for, called with the recording source.
+///
+/// # Returns
+///
+/// A `Result` containing the addresses of the accounts in canonical byte order.
+///
+/// # Errors
+///
+/// This function will return an error if the builder fails against the online source.
+pub async fn required_accounts<S: AccountSource, T>(
+    source: S,
+    builder: impl AsyncFnOnce(&CachedAccountSource<S>) -> Result<T, Box<dyn Error>>,
+) -> Result<Vec<Pubkey>, Box<dyn Error>> {
+    Ok(fetch_offline_state(source, builder).await?.addresses())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{setup_ata_with_amount, setup_fusion_pool, setup_mint_with_decimals, setup_position, RpcContext};
+    use crate::{increase_liquidity_instructions, swap_instructions, AccountSnapshot, IncreaseLiquidityParam, SwapType};
+    use fusionamm_client::{get_tick_array_address, FusionPool};
+    use fusionamm_core::get_tick_array_start_tick_index;
+    use serial_test::serial;
+    use solana_program_test::tokio;
+    use solana_signer::Signer;
+
+    #[tokio::test]
+    async fn test_offline_state() {
+        let present = Pubkey::new_unique();
+        let missing = Pubkey::new_unique();
+        let unknown = Pubkey::new_unique();
+        let account = Account {
+            lamports: 1,
+            data: vec![1, 2, 3],
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let mut snapshot = AccountSnapshot::new(7, 100, Rent::default());
+        snapshot.insert(present, account.clone());
+
+        let state = fetch_offline_state(&snapshot, async |source| {
+            source.get_multiple_accounts(&[present, missing]).await?;
+            source.get_epoch().await
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(state.addresses(), {
+            let mut addresses = vec![present, missing];
+            addresses.sort();
+            addresses
+        });
+        assert_eq!(state.epoch, Some(7));
+        assert_eq!(state.rent, None);
+
+        assert_eq!(state.build(|state| state.get_account(&present)).unwrap(), account);
+        assert_eq!(state.build(|state| state.get_multiple_accounts(&[missing])).unwrap(), vec![None]);
+        assert!(state.build(|state| state.get_account(&missing)).is_err());
+        assert!(state.build(|state| state.get_multiple_accounts(&[present, unknown])).is_err());
+        assert!(state.build(|state| state.get_rent()).is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_offline_swap_instructions() {
+        let ctx = RpcContext::new().await;
+        let mint_1 = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_2 = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        setup_ata_with_amount(&ctx, mint_1, 1_000_000_000).await.unwrap();
+        setup_ata_with_amount(&ctx, mint_2, 1_000_000_000).await.unwrap();
+
+        let (mint_a, mint_b) = if mint_1 < mint_2 { (mint_1, mint_2) } else { (mint_2, mint_1) };
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await.unwrap();
+        let position_mint = setup_position(&ctx, pool_address, Some((-192, 192)), None).await.unwrap();
+        let liquidity_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            position_mint,
+            IncreaseLiquidityParam::Liquidity(1_000_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+        ctx.send_transaction_with_signers(liquidity_ix.instructions, liquidity_ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+
+        let signer = ctx.signer.pubkey();
+        let online = swap_instructions(&ctx.rpc, pool_address, 1000, mint_a, SwapType::ExactIn, Some(100), Some(signer))
+            .await
+            .unwrap();
+
+        let offline_state = fetch_offline_state(&ctx.rpc, async |source| {
+            swap_instructions(source, pool_address, 1000, mint_a, SwapType::ExactIn, Some(100), Some(signer)).await
+        })
+        .await
+        .unwrap();
+
+        let pool_account = ctx.rpc.get_account(&pool_address).await.unwrap();
+        let pool = FusionPool::from_bytes(&pool_account.data).unwrap();
+        let tick_array_address = get_tick_array_address(&pool_address, get_tick_array_start_tick_index(pool.tick_current_index, 64))
+            .unwrap()
+            .0;
+        let addresses = offline_state.addresses();
+        assert!(addresses.contains(&pool_address));
+        assert!(addresses.contains(&tick_array_address));
+        assert!(addresses.contains(&mint_a));
+        assert!(addresses.contains(&mint_b));
+
+        let mut decoded_state = offline_state.clone();
+        decoded_state.insert_fusion_pool(pool_address, &pool).unwrap();
+        assert_eq!(decoded_state.accounts[&pool_address].as_ref().unwrap().data, pool_account.data[..FusionPool::LEN]);
+
+        for state in [&offline_state, &decoded_state] {
+            let offline = state
+                .build(|state| swap_instructions(state, pool_address, 1000, mint_a, SwapType::ExactIn, Some(100), Some(signer)))
+                .unwrap();
+            assert_eq!(offline.instructions, online.instructions);
+            assert_eq!(format!("{:?}", offline.quote), format!("{:?}", online.quote));
+        }
+
+        let mut incomplete_state = offline_state.clone();
+        incomplete_state.accounts.remove(&tick_array_address);
+        assert!(incomplete_state
+            .build(|state| swap_instructions(state, pool_address, 1000, mint_a, SwapType::ExactIn, Some(100), Some(signer)))
+            .is_err());
+    }
+}
@@ -8,12 +8,14 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use fusionamm_client::{fetch_all_fusion_pool_with_filter, get_fusion_pool_address, DecodedAccount, FusionPool, FusionPoolFilter};
+use fusionamm_client::{
+    fetch_all_fusion_pool_with_filter, get_fusion_pool_address, DecodedAccount, FusionPool, FusionPoolFilter, FUSIONAMM_ID, FUSION_POOL_DISCRIMINATOR,
+};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_program::pubkey::Pubkey;
 use std::error::Error;
 
-use crate::order_mints;
+use crate::{order_mints, AccountSource};
 
 #[cfg(not(doctest))]
 /// Fetches the details of a specific Concentrated Liquidity Pool.
@@ -40,7 +42,7 @@ use crate::order_mints;
 /// - Any required account or mint information cannot be fetched.
 /// - The pool or its configuration details are invalid.
 pub async fn fetch_fusion_pool_by_token_pair_and_tick_spacing(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     token_1: Pubkey,
     token_2: Pubkey,
     tick_spacing: u16,
@@ -59,11 +61,10 @@ pub async fn fetch_fusion_pool_by_token_pair_and_tick_spacing(
 }
 
 #[cfg(not(doctest))]
-/// Fetches all possible liquidity pools between two token mints in fusion pools.
+/// Finds the addresses of all fusion pools between two token mints.
 ///
-/// This function retrieves information about all pools between the specified token mints,
-/// including both initialized and uninitialized pools. If a pool does not exist, it creates
-/// a placeholder account for the uninitialized pool with default configuration details.
+/// This is the discovery step of `fetch_fusion_pools_by_token_pair`. It relies on `getProgramAccounts`,
+/// so it needs an RPC client; the pools themselves can then be fetched from any `AccountSource`.
 ///
 /// # Arguments
 ///
@@ -73,20 +74,46 @@ pub async fn fetch_fusion_pool_by_token_pair_and_tick_spacing(
 ///
 /// # Returns
 ///
-/// A `Result` containing a `Vec<PoolInfo>`:
-/// * `PoolInfo::Initialized` for initialized pools, including pool state and price.
-/// * `PoolInfo::Uninitialized` for uninitialized pools, including configuration details.
+/// A `Result` containing the addresses of the fusion pools of the token pair.
+///
+/// # Errors
+///
+/// This function will return an error if the RPC request fails.
+pub async fn fetch_fusion_pool_addresses_by_token_pair(rpc: &RpcClient, token_1: Pubkey, token_2: Pubkey) -> Result<Vec<Pubkey>, Box<dyn Error>> {
+    let [token_a, token_b] = order_mints(token_1, token_2);
+    let fusion_pools =
+        fetch_all_fusion_pool_with_filter(rpc, vec![FusionPoolFilter::TokenMintA(token_a), FusionPoolFilter::TokenMintB(token_b)]).await?;
+    Ok(fusion_pools.into_iter().map(|x| x.address).collect())
+}
+
+#[cfg(not(doctest))]
+/// Fetches the liquidity pools between two token mints in fusion pools.
+///
+/// The pools are looked up among the given candidate addresses, e.g. the ones returned by
+/// `fetch_fusion_pool_addresses_by_token_pair` or known from an indexer. Candidates that don't exist,
+/// are not fusion pools or belong to another token pair are skipped.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to an account source, e.g. the Solana RPC client.
+/// * `token_1` - The public key of the first token mint in the pool.
+/// * `token_2` - The public key of the second token mint in the pool.
+/// * `fusion_pools` - The addresses of the candidate fusion pools.
+///
+/// # Returns
+///
+/// A `Result` containing the fusion pools of the token pair.
 ///
 /// # Errors
 ///
 /// This function will return an error if:
 /// - Any required account or mint information cannot be fetched.
-/// - The pool or its configuration details are invalid.
-///
+/// - The pool data is invalid.
 pub async fn fetch_fusion_pools_by_token_pair(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     token_1: Pubkey,
     token_2: Pubkey,
+    fusion_pools: &[Pubkey],
 ) -> Result<Vec<DecodedAccount<FusionPool>>, Box<dyn Error>> {
     let [token_a, token_b] = order_mints(token_1, token_2);
 
@@ -95,12 +122,36 @@ pub async fn fetch_fusion_pools_by_token_pair(
     account_infos[0].as_ref().ok_or(format!("Mint {} not found", token_a))?;
     account_infos[1].as_ref().ok_or(format!("Mint {} not found", token_b))?;
 
-    let fusion_pools =
-        fetch_all_fusion_pool_with_filter(rpc, vec![FusionPoolFilter::TokenMintA(token_a), FusionPoolFilter::TokenMintB(token_b)]).await?;
+    let fusion_pools = fetch_fusion_pools(rpc, fusion_pools).await?;
+
+    Ok(fusion_pools.into_iter().filter(|x| is_token_pair(&x.data, token_a, token_b)).collect())
+}
+
+/// Fetches the fusion pools at the given addresses. Addresses of missing accounts or other account types are skipped.
+pub(crate) async fn fetch_fusion_pools(rpc: &impl AccountSource, addresses: &[Pubkey]) -> Result<Vec<DecodedAccount<FusionPool>>, Box<dyn Error>> {
+    let account_infos = rpc.get_multiple_accounts(addresses).await?;
+
+    let mut fusion_pools: Vec<DecodedAccount<FusionPool>> = Vec::new();
+    for (address, account) in addresses.iter().zip(account_infos) {
+        let Some(account) = account.filter(|x| x.owner == FUSIONAMM_ID && x.data.starts_with(&FUSION_POOL_DISCRIMINATOR)) else {
+            continue;
+        };
+        let data = FusionPool::from_bytes(&account.data)?;
+        fusion_pools.push(DecodedAccount {
+            address: *address,
+            account,
+            data,
+        });
+    }
 
     Ok(fusion_pools)
 }
 
+/// Returns true if the fusion pool trades the two token mints, in any order.
+pub(crate) fn is_token_pair(fusion_pool: &FusionPool, token_1: Pubkey, token_2: Pubkey) -> bool {
+    order_mints(token_1, token_2) == [fusion_pool.token_mint_a, fusion_pool.token_mint_b]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,4 +204,25 @@ mod tests {
         assert_eq!(pool.data.fee_rate, 300);
         assert_eq!(pool.data.protocol_fee_rate, 0);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fetch_fusion_pools_by_token_pair() {
+        let test_ctx = TestContext::new().await.unwrap();
+        let mint_c = setup_mint_with_decimals(&test_ctx.ctx, 9).await.unwrap();
+        let [token_a, token_c] = order_mints(test_ctx.mint_a, mint_c);
+        let other_pool = setup_fusion_pool(&test_ctx.ctx, token_a, token_c, 64, 300).await.unwrap();
+
+        let candidates = [other_pool, test_ctx.concentrated_pool, test_ctx.mint_a, Pubkey::new_unique()];
+        let pools = fetch_fusion_pools_by_token_pair(&test_ctx.ctx.rpc, test_ctx.mint_b, test_ctx.mint_a, &candidates)
+            .await
+            .unwrap();
+
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].address, test_ctx.concentrated_pool);
+        assert_eq!(pools[0].data.tick_spacing, 64);
+
+        let missing_mint = fetch_fusion_pools_by_token_pair(&test_ctx.ctx.rpc, test_ctx.mint_a, Pubkey::new_unique(), &candidates).await;
+        assert!(missing_mint.is_err());
+    }
 }
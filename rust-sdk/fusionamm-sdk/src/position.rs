@@ -9,16 +9,16 @@
 //
 
 use fusionamm_client::{
-    fetch_all_position_with_filter, get_bundled_position_address, get_position_address, get_position_bundle_address, DecodedAccount, Position,
-    PositionBundle, PositionFilter,
+    fetch_all_position_with_filter, get_bundled_position_address, get_position_address, get_position_bundle_address, get_position_lock_address,
+    get_tick_array_address, DecodedAccount, FusionPool, Position, PositionBundle, PositionFilter, PositionLock, PositionLockType, TickArray,
 };
-use fusionamm_core::POSITION_BUNDLE_SIZE;
+use fusionamm_core::{get_tick_array_start_tick_index, get_tick_index_in_array, position_analytics, PositionAnalytics, POSITION_BUNDLE_SIZE};
 use solana_account::Account;
 use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
 use solana_pubkey::Pubkey;
 use std::{collections::HashMap, error::Error};
 
-use crate::{get_token_accounts_for_owner, ParsedTokenAccount};
+use crate::{get_token_accounts_for_owner, token::get_current_transfer_fee, AccountSource, ParsedTokenAccount};
 
 /// Represents a single Position account.
 ///
@@ -35,6 +35,29 @@ pub struct HydratedPosition {
 
     /// The public key of the token program associated with the position NFT (either SPL Token or Token 2022).
     pub token_program: Pubkey,
+
+    /// The lock of the position, if the position is locked.
+    pub lock: Option<HydratedPositionLock>,
+}
+
+impl HydratedPosition {
+    /// Returns `true` if the position is locked.
+    pub fn is_locked(&self) -> bool {
+        self.lock.is_some()
+    }
+}
+
+/// Represents the lock of a locked position.
+#[derive(Debug)]
+pub struct HydratedPositionLock {
+    /// The public key of the PositionLock account.
+    pub address: Pubkey,
+
+    /// The type of the lock.
+    pub lock_type: PositionLockType,
+
+    /// The unix timestamp at which the position was locked.
+    pub locked_timestamp: u64,
 }
 
 /// Represents a single bundled position within a `PositionBundle` account.
@@ -102,7 +125,7 @@ fn get_position_in_bundle_addresses(position_bundle: &PositionBundle) -> Vec<Pub
 /// This function retrieves token accounts owned by the wallet, using both the SPL Token Program
 /// and Token 2022 Program. It identifies accounts holding exactly one token, which represent
 /// either a position or a position bundle. For each of these accounts, it fetches the corresponding
-/// position or bundle data, including any bundled positions, and returns them.
+/// position or bundle data, including any bundled positions and the locks of standalone positions, and returns them.
 ///
 /// # Arguments
 ///
@@ -168,6 +191,18 @@ pub async fn fetch_positions_for_owner(rpc: &RpcClient, owner: Pubkey) -> Result
         .map(|x| x.as_ref().and_then(|x| Position::from_bytes(&x.data).ok()))
         .collect();
 
+    let position_lock_addresses: Vec<Pubkey> = potiential_tokens
+        .iter()
+        .map(|x| get_position_lock_address(&x.mint).map(|x| x.0))
+        .collect::<Result<Vec<Pubkey>, _>>()?;
+
+    let position_lock_infos = rpc.get_multiple_accounts(&position_lock_addresses).await?;
+
+    let position_locks: Vec<Option<PositionLock>> = position_lock_infos
+        .iter()
+        .map(|x| x.as_ref().and_then(|x| PositionLock::from_bytes(&x.data).ok()))
+        .collect();
+
     let position_bundle_infos = rpc.get_multiple_accounts(&position_bundle_addresses).await?;
 
     let position_bundles: Vec<Option<PositionBundle>> = position_bundle_infos
@@ -209,6 +244,11 @@ pub async fn fetch_positions_for_owner(rpc: &RpcClient, owner: Pubkey) -> Result
                 address: position_address,
                 data: position.clone(),
                 token_program: token_account.token_program,
+                lock: position_locks[i].as_ref().map(|lock| HydratedPositionLock {
+                    address: position_lock_addresses[i],
+                    lock_type: lock.lock_type,
+                    locked_timestamp: lock.locked_timestamp,
+                }),
             }));
         }
 
@@ -284,6 +324,92 @@ pub async fn fetch_positions_in_fusion_pool(rpc: &RpcClient, fusion_pool: Pubkey
     fetch_all_position_with_filter(rpc, filters).await
 }
 
+/// Fetches the state required to calculate the analytics of a position and calculates them.
+///
+/// The analytics include the current token amounts and value of the position, its uncollected fees
+/// and the impermanent loss versus holding the tokens deposited at the given entry price.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `position` - The decoded position data, e.g. `HydratedPosition::data`.
+/// * `entry_sqrt_price` - The sqrt price at which the position was opened.
+///
+/// # Returns
+///
+/// A `Result` containing the `PositionAnalytics` of the position.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - RPC calls fail when fetching the pool, mint or tick array accounts.
+/// - The analytics calculation overflows.
+///
+/// # Example
+/// ```rust
+/// use fusionamm_sdk::{fetch_position_analytics, fetch_positions_for_owner, PositionOrBundle};
+/// use solana_client::nonblocking::rpc_client::RpcClient;
+/// use solana_pubkey::pubkey;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rpc = RpcClient::new("https://api.devnet.solana.com".to_string());
+///     let owner = pubkey!("FTEV6CnregJCqU8s8hGR3VAYCrPKHfekXLsJaKHbPBxp");
+///
+///     for position in fetch_positions_for_owner(&rpc, owner).await.unwrap() {
+///         if let PositionOrBundle::Position(position) = position {
+///             let analytics = fetch_position_analytics(&rpc, &position.data, 1 << 64).await.unwrap();
+///             println!("Position analytics: {:?}", analytics);
+///         }
+///     }
+/// }
+/// ```
+pub async fn fetch_position_analytics(
+    rpc: &impl AccountSource,
+    position: &Position,
+    entry_sqrt_price: u128,
+) -> Result<PositionAnalytics, Box<dyn Error>> {
+    let pool_info = rpc.get_account(&position.fusion_pool).await?;
+    let pool = FusionPool::from_bytes(&pool_info.data)?;
+
+    let lower_tick_array_start_index = get_tick_array_start_tick_index(position.tick_lower_index, pool.tick_spacing);
+    let upper_tick_array_start_index = get_tick_array_start_tick_index(position.tick_upper_index, pool.tick_spacing);
+    let lower_tick_array_address = get_tick_array_address(&position.fusion_pool, lower_tick_array_start_index)?.0;
+    let upper_tick_array_address = get_tick_array_address(&position.fusion_pool, upper_tick_array_start_index)?.0;
+
+    let account_infos = rpc
+        .get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b, lower_tick_array_address, upper_tick_array_address])
+        .await?;
+
+    let current_epoch = rpc.get_epoch().await?;
+    let mint_a_info = account_infos[0].as_ref().ok_or("Token A mint info not found")?;
+    let mint_b_info = account_infos[1].as_ref().ok_or("Token B mint info not found")?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
+
+    let lower_tick_array_info = account_infos[2].as_ref().ok_or("Lower tick array info not found")?;
+    let lower_tick_array = TickArray::from_bytes(&lower_tick_array_info.data)?;
+    let lower_tick =
+        &lower_tick_array.ticks[get_tick_index_in_array(position.tick_lower_index, lower_tick_array_start_index, pool.tick_spacing)? as usize];
+
+    let upper_tick_array_info = account_infos[3].as_ref().ok_or("Upper tick array info not found")?;
+    let upper_tick_array = TickArray::from_bytes(&upper_tick_array_info.data)?;
+    let upper_tick =
+        &upper_tick_array.ticks[get_tick_index_in_array(position.tick_upper_index, upper_tick_array_start_index, pool.tick_spacing)? as usize];
+
+    let analytics = position_analytics(
+        pool.into(),
+        position.clone().into(),
+        lower_tick.clone().into(),
+        upper_tick.clone().into(),
+        entry_sqrt_price,
+        transfer_fee_a,
+        transfer_fee_b,
+    )?;
+
+    Ok(analytics)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,860 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::{
+    get_increase_liquidity_quote,
+    token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy},
+    AccountSource, ClosePositionInstruction, IncreaseLiquidityParam, PriceOrTickIndex, FUNDER, SLIPPAGE_TOLERANCE_BPS,
+};
+use fusionamm_client::{
+    get_bundled_position_address, get_position_bundle_address, get_tick_array_address, CloseBundledPosition, CloseBundledPositionInstructionArgs,
+    CollectFees, CollectFeesInstructionArgs, DecreaseLiquidity, DecreaseLiquidityInstructionArgs, DeletePositionBundle, FusionPool,
+    IncreaseLiquidity, IncreaseLiquidityInstructionArgs, InitializePositionBundle, InitializePositionBundleWithMetadata, InitializeTickArray,
+    InitializeTickArrayInstructionArgs, OpenBundledPosition, OpenBundledPositionInstructionArgs, Position, PositionBundle, TickArray,
+    FP_NFT_UPDATE_AUTH,
+};
+use fusionamm_core::{
+    collect_fees_quote, decrease_liquidity_quote, first_unoccupied_position_in_bundle, get_initializable_tick_index, get_tick_array_start_tick_index,
+    get_tick_index_in_array, is_position_bundle_empty, order_tick_indexes, price_to_tick_index, IncreaseLiquidityQuote, POSITION_BUNDLE_SIZE,
+};
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_program::program_pack::Pack;
+use solana_pubkey::{pubkey, Pubkey};
+use solana_signer::Signer;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::state::Mint;
+use std::error::Error;
+
+// TODO: support transfer hooks
+
+const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Represents the instructions for initializing a position bundle.
+#[derive(Debug)]
+pub struct InitializePositionBundleInstruction {
+    /// The public key of the position bundle NFT mint.
+    pub position_bundle_mint: Pubkey,
+
+    /// The public key of the PositionBundle account.
+    pub position_bundle: Pubkey,
+
+    /// A vector of `Instruction` objects required to initialize the position bundle.
+    pub instructions: Vec<Instruction>,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+}
+
+/// Represents the instructions and quote for opening a position in a position bundle.
+#[derive(Debug)]
+pub struct OpenBundledPositionInstruction {
+    /// The index of the position in the bundle.
+    pub bundle_index: u16,
+
+    /// The public key of the bundled Position account.
+    pub position: Pubkey,
+
+    /// The computed quote for increasing liquidity, including liquidity delta, token estimates,
+    /// and maximum tokens based on slippage tolerance.
+    pub quote: IncreaseLiquidityQuote,
+
+    /// A vector of `Instruction` objects required to open the bundled position.
+    pub instructions: Vec<Instruction>,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+
+    /// The cost of initializing the tick arrays of the position, measured in lamports.
+    pub initialization_cost: u64,
+}
+
+/// Represents the instructions for deleting a position bundle.
+#[derive(Debug)]
+pub struct DeletePositionBundleInstruction {
+    /// A vector of `Instruction` objects required to delete the position bundle.
+    pub instructions: Vec<Instruction>,
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to initialize a position bundle.
+///
+/// A position bundle is an NFT that can hold up to 256 positions, which avoids creating a mint
+/// and a token account for every position. The bundle NFT is minted to the funder.
+///
+/// # Arguments
+///
+/// * `with_metadata` - Whether to create the Metaplex metadata account of the bundle NFT.
+/// * `funder` - An optional public key of the funder and owner of the bundle. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `InitializePositionBundleInstruction` on success:
+///
+/// * `position_bundle_mint` - The public key of the position bundle NFT mint.
+/// * `position_bundle` - The public key of the PositionBundle account.
+/// * `instructions` - A vector of `Instruction` objects required to initialize the position bundle.
+/// * `additional_signers` - A vector of `Keypair` objects representing additional signers required for the instructions.
+///
+/// # Errors
+///
+/// This function will return an error if the `funder` account is invalid or missing.
+///
+/// # Example
+///
+/// ```rust
+/// use fusionamm_sdk::initialize_position_bundle_instructions;
+/// use solana_keypair::Keypair;
+/// use solana_signer::Signer;
+///
+/// fn main() {
+///     let wallet = Keypair::new(); // Load your wallet here
+///
+///     let result = initialize_position_bundle_instructions(true, Some(wallet.pubkey())).unwrap();
+///
+///     println!("Position Bundle Mint: {:?}", result.position_bundle_mint);
+/// }
+/// ```
+pub fn initialize_position_bundle_instructions(
+    with_metadata: bool,
+    funder: Option<Pubkey>,
+) -> Result<InitializePositionBundleInstruction, Box<dyn Error>> {
+    let funder = funder.unwrap_or(*FUNDER.try_lock()?);
+    if funder == Pubkey::default() {
+        return Err("Funder must be provided".into());
+    }
+
+    let position_bundle_mint = Keypair::new();
+    let position_bundle = get_position_bundle_address(&position_bundle_mint.pubkey())?.0;
+    let position_bundle_token_account = get_associated_token_address_with_program_id(&funder, &position_bundle_mint.pubkey(), &spl_token::ID);
+
+    let instruction = if with_metadata {
+        let position_bundle_metadata =
+            Pubkey::find_program_address(&[b"metadata", METADATA_PROGRAM_ID.as_ref(), position_bundle_mint.pubkey().as_ref()], &METADATA_PROGRAM_ID)
+                .0;
+        InitializePositionBundleWithMetadata {
+            position_bundle,
+            position_bundle_mint: position_bundle_mint.pubkey(),
+            position_bundle_metadata,
+            position_bundle_token_account,
+            position_bundle_owner: funder,
+            funder,
+            metadata_update_auth: FP_NFT_UPDATE_AUTH,
+            token_program: spl_token::ID,
+            system_program: solana_program::system_program::id(),
+            rent: solana_program::sysvar::rent::id(),
+            associated_token_program: spl_associated_token_account::ID,
+            metadata_program: METADATA_PROGRAM_ID,
+        }
+        .instruction()
+    } else {
+        InitializePositionBundle {
+            position_bundle,
+            position_bundle_mint: position_bundle_mint.pubkey(),
+            position_bundle_token_account,
+            position_bundle_owner: funder,
+            funder,
+            token_program: spl_token::ID,
+            system_program: solana_program::system_program::id(),
+            rent: solana_program::sysvar::rent::id(),
+            associated_token_program: spl_associated_token_account::ID,
+        }
+        .instruction()
+    };
+
+    Ok(InitializePositionBundleInstruction {
+        position_bundle_mint: position_bundle_mint.pubkey(),
+        position_bundle,
+        instructions: vec![instruction],
+        additional_signers: vec![position_bundle_mint],
+    })
+}
+
+// Derives the address of a bundled position, rejecting indexes outside of the bundle.
+fn get_bundled_position_address_checked(position_bundle_mint: &Pubkey, bundle_index: u16) -> Result<Pubkey, Box<dyn Error>> {
+    if bundle_index as usize >= POSITION_BUNDLE_SIZE {
+        return Err(format!("Bundle index must be less than {}", POSITION_BUNDLE_SIZE).into());
+    }
+    Ok(get_bundled_position_address(position_bundle_mint, bundle_index as u8)?.0)
+}
+
+#[cfg(not(doctest))]
+/// Opens a position in a position bundle and adds liquidity to it.
+///
+/// The position is opened in the first unoccupied slot of the bundle.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `position_bundle_mint` - The public key of the position bundle NFT mint.
+/// * `pool_address` - The public key of the liquidity pool.
+/// * `lower_price_or_tick_index` - The lower bound of the position, as a price or a tick index.
+/// * `upper_price_or_tick_index` - The upper bound of the position, as a price or a tick index.
+/// * `param` - Parameters for increasing liquidity (`IncreaseLiquidityParam`).
+/// * `slippage_tolerance_bps` - An optional slippage tolerance in basis points. Defaults to the global slippage tolerance if not provided.
+/// * `funder` - An optional public key of the bundle owner, who also funds the position. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `OpenBundledPositionInstruction` on success:
+///
+/// * `bundle_index` - The index of the position in the bundle.
+/// * `position` - The public key of the bundled Position account.
+/// * `quote` - The computed quote for increasing liquidity.
+/// * `instructions` - A vector of `Instruction` objects required to open the bundled position.
+/// * `additional_signers` - A vector of `Keypair` objects representing additional signers required for the instructions.
+/// * `initialization_cost` - The cost of initializing the tick arrays of the position, measured in lamports.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `funder` account is invalid or missing.
+/// - The position bundle is full.
+/// - The position bundle, pool or token mint accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+///
+/// # Example
+///
+/// ```rust
+/// use fusionamm_sdk::{open_bundled_position_instructions, IncreaseLiquidityParam, PriceOrTickIndex};
+/// use solana_client::nonblocking::rpc_client::RpcClient;
+/// use solana_pubkey::pubkey;
+/// use solana_keypair::Keypair;
+/// use solana_signer::Signer;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rpc = RpcClient::new("https://api.devnet.solana.com".to_string());
+///     let wallet = Keypair::new(); // Load your wallet here
+///
+///     let position_bundle_mint = pubkey!("HqoV7Qv27REUtmd9UKSJGGmCRNx3531t33bDG1BUfo9K");
+///     let fusion_pool_pubkey = pubkey!("7VuKeevbvbQQcxz6N4SNLmuq6PYy4AcGQRDssoqo4t65");
+///
+///     let result = open_bundled_position_instructions(
+///         &rpc,
+///         position_bundle_mint,
+///         fusion_pool_pubkey,
+///         PriceOrTickIndex::Price(0.9),
+///         PriceOrTickIndex::Price(1.1),
+///         IncreaseLiquidityParam::TokenA(1_000_000),
+///         Some(100),
+///         Some(wallet.pubkey()),
+///     )
+///     .await
+///     .unwrap();
+///
+///     println!("Bundle Index: {}", result.bundle_index);
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub async fn open_bundled_position_instructions(
+    rpc: &impl AccountSource,
+    position_bundle_mint: Pubkey,
+    pool_address: Pubkey,
+    lower_price_or_tick_index: PriceOrTickIndex,
+    upper_price_or_tick_index: PriceOrTickIndex,
+    param: IncreaseLiquidityParam,
+    slippage_tolerance_bps: Option<u16>,
+    funder: Option<Pubkey>,
+) -> Result<OpenBundledPositionInstruction, Box<dyn Error>> {
+    let funder = funder.unwrap_or(*FUNDER.try_lock()?);
+    let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
+    if funder == Pubkey::default() {
+        return Err("Funder must be provided".into());
+    }
+    let rent = rpc.get_rent().await?;
+
+    let position_bundle_address = get_position_bundle_address(&position_bundle_mint)?.0;
+    let infos = rpc
+        .get_multiple_accounts(&[position_bundle_address, pool_address, position_bundle_mint])
+        .await?;
+    let position_bundle = PositionBundle::from_bytes(&infos[0].as_ref().ok_or("Position bundle info not found")?.data)?;
+    let fusion_pool = FusionPool::from_bytes(&infos[1].as_ref().ok_or("Fusion pool info not found")?.data)?;
+    let position_bundle_mint_info = infos[2].as_ref().ok_or("Position bundle mint info not found")?;
+
+    let bundle_index = first_unoccupied_position_in_bundle(&position_bundle.position_bitmap).ok_or("Position bundle is full")? as u16;
+
+    let mint_infos = rpc.get_multiple_accounts(&[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
+    let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
+    let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
+    // Use 'unpack_from_slice' instead of 'unpack' because the account length might be larger than Mint::LEN due to extensions.
+    if mint_a_info.data.len() < Mint::LEN || mint_b_info.data.len() < Mint::LEN {
+        return Err("Wrong token mint account length".into());
+    }
+    let mint_a = Mint::unpack_from_slice(&mint_a_info.data)?;
+    let mint_b = Mint::unpack_from_slice(&mint_b_info.data)?;
+
+    let lower_tick_index = match lower_price_or_tick_index {
+        PriceOrTickIndex::Tick(tick_index) => tick_index,
+        PriceOrTickIndex::Price(price) => price_to_tick_index(price, mint_a.decimals, mint_b.decimals),
+    };
+    let upper_tick_index = match upper_price_or_tick_index {
+        PriceOrTickIndex::Tick(tick_index) => tick_index,
+        PriceOrTickIndex::Price(price) => price_to_tick_index(price, mint_a.decimals, mint_b.decimals),
+    };
+
+    let tick_range = order_tick_indexes(lower_tick_index, upper_tick_index);
+    let lower_initializable_tick_index = get_initializable_tick_index(tick_range.tick_lower_index, fusion_pool.tick_spacing, Some(false));
+    let upper_initializable_tick_index = get_initializable_tick_index(tick_range.tick_upper_index, fusion_pool.tick_spacing, Some(true));
+
+    let epoch = rpc.get_epoch().await?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), epoch);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), epoch);
+
+    let quote = get_increase_liquidity_quote(
+        param,
+        slippage_tolerance_bps,
+        &fusion_pool,
+        lower_initializable_tick_index,
+        upper_initializable_tick_index,
+        transfer_fee_a,
+        transfer_fee_b,
+    )?;
+
+    let mut instructions: Vec<Instruction> = Vec::new();
+    let mut non_refundable_rent: u64 = 0;
+
+    let lower_tick_start_index = get_tick_array_start_tick_index(lower_initializable_tick_index, fusion_pool.tick_spacing);
+    let upper_tick_start_index = get_tick_array_start_tick_index(upper_initializable_tick_index, fusion_pool.tick_spacing);
+
+    let position_address = get_bundled_position_address_checked(&position_bundle_mint, bundle_index)?;
+    let position_bundle_token_account =
+        get_associated_token_address_with_program_id(&funder, &position_bundle_mint, &position_bundle_mint_info.owner);
+    let lower_tick_array_address = get_tick_array_address(&pool_address, lower_tick_start_index)?.0;
+    let upper_tick_array_address = get_tick_array_address(&pool_address, upper_tick_start_index)?.0;
+
+    let token_accounts = prepare_token_accounts_instructions(
+        rpc,
+        funder,
+        vec![
+            TokenAccountStrategy::WithBalance(fusion_pool.token_mint_a, quote.token_max_a),
+            TokenAccountStrategy::WithBalance(fusion_pool.token_mint_b, quote.token_max_b),
+        ],
+    )
+    .await?;
+
+    instructions.extend(token_accounts.create_instructions);
+
+    let tick_array_infos = rpc.get_multiple_accounts(&[lower_tick_array_address, upper_tick_array_address]).await?;
+
+    if tick_array_infos[0].is_none() {
+        instructions.push(
+            InitializeTickArray {
+                fusion_pool: pool_address,
+                funder,
+                tick_array: lower_tick_array_address,
+                system_program: solana_program::system_program::id(),
+            }
+            .instruction(InitializeTickArrayInstructionArgs {
+                start_tick_index: lower_tick_start_index,
+            }),
+        );
+        non_refundable_rent += rent.minimum_balance(TickArray::MIN_LEN);
+    }
+
+    if tick_array_infos[1].is_none() && lower_tick_start_index != upper_tick_start_index {
+        instructions.push(
+            InitializeTickArray {
+                fusion_pool: pool_address,
+                funder,
+                tick_array: upper_tick_array_address,
+                system_program: solana_program::system_program::id(),
+            }
+            .instruction(InitializeTickArrayInstructionArgs {
+                start_tick_index: upper_tick_start_index,
+            }),
+        );
+        non_refundable_rent += rent.minimum_balance(TickArray::MIN_LEN);
+    }
+
+    let token_owner_account_a = token_accounts
+        .token_account_addresses
+        .get(&fusion_pool.token_mint_a)
+        .ok_or("Token A owner account not found")?;
+    let token_owner_account_b = token_accounts
+        .token_account_addresses
+        .get(&fusion_pool.token_mint_b)
+        .ok_or("Token B owner account not found")?;
+
+    instructions.push(
+        OpenBundledPosition {
+            bundled_position: position_address,
+            position_bundle: position_bundle_address,
+            position_bundle_token_account,
+            position_bundle_authority: funder,
+            fusion_pool: pool_address,
+            funder,
+            system_program: solana_program::system_program::id(),
+            rent: solana_program::sysvar::rent::id(),
+        }
+        .instruction(OpenBundledPositionInstructionArgs {
+            bundle_index,
+            tick_lower_index: lower_initializable_tick_index,
+            tick_upper_index: upper_initializable_tick_index,
+        }),
+    );
+
+    instructions.push(
+        IncreaseLiquidity {
+            fusion_pool: pool_address,
+            token_program_a: mint_a_info.owner,
+            token_program_b: mint_b_info.owner,
+            memo_program: spl_memo::ID,
+            position_authority: funder,
+            position: position_address,
+            position_token_account: position_bundle_token_account,
+            token_mint_a: fusion_pool.token_mint_a,
+            token_mint_b: fusion_pool.token_mint_b,
+            token_owner_account_a: *token_owner_account_a,
+            token_owner_account_b: *token_owner_account_b,
+            token_vault_a: fusion_pool.token_vault_a,
+            token_vault_b: fusion_pool.token_vault_b,
+            tick_array_lower: lower_tick_array_address,
+            tick_array_upper: upper_tick_array_address,
+        }
+        .instruction(IncreaseLiquidityInstructionArgs {
+            liquidity_amount: quote.liquidity_delta,
+            token_max_a: quote.token_max_a,
+            token_max_b: quote.token_max_b,
+            remaining_accounts_info: None,
+        }),
+    );
+
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(OpenBundledPositionInstruction {
+        bundle_index,
+        position: position_address,
+        quote,
+        instructions,
+        additional_signers: token_accounts.additional_signers,
+        initialization_cost: non_refundable_rent,
+    })
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to close a position in a position bundle.
+///
+/// This function removes any remaining liquidity, collects all fees and closes the bundled
+/// position, freeing its slot in the bundle.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `position_bundle_mint` - The public key of the position bundle NFT mint.
+/// * `bundle_index` - The index of the position in the bundle.
+/// * `slippage_tolerance_bps` - An optional slippage tolerance in basis points. Defaults to the global slippage tolerance if not provided.
+/// * `authority` - An optional public key of the bundle owner. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `ClosePositionInstruction` on success:
+///
+/// * `instructions` - A vector of `Instruction` objects required to close the bundled position.
+/// * `additional_signers` - A vector of `Keypair` objects representing additional signers required for the instructions.
+/// * `quote` - The computed quote for decreasing liquidity, including liquidity delta, token estimates, and minimum tokens.
+/// * `fees_quote` - Details of the fees available to collect from the position.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` account is invalid or missing.
+/// - The `bundle_index` is not less than the bundle size.
+/// - The position bundle, position, pool or token mint accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+///
+/// # Example
+///
+/// ```rust
+/// use fusionamm_sdk::close_bundled_position_instructions;
+/// use solana_client::nonblocking::rpc_client::RpcClient;
+/// use solana_pubkey::pubkey;
+/// use solana_keypair::Keypair;
+/// use solana_signer::Signer;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rpc = RpcClient::new("https://api.devnet.solana.com".to_string());
+///     let wallet = Keypair::new(); // Load your wallet here
+///
+///     let position_bundle_mint = pubkey!("HqoV7Qv27REUtmd9UKSJGGmCRNx3531t33bDG1BUfo9K");
+///
+///     let result = close_bundled_position_instructions(&rpc, position_bundle_mint, 0, Some(100), Some(wallet.pubkey()))
+///         .await
+///         .unwrap();
+///
+///     println!("Fees Quote: {:?}", result.fees_quote);
+/// }
+/// ```
+pub async fn close_bundled_position_instructions(
+    rpc: &impl AccountSource,
+    position_bundle_mint: Pubkey,
+    bundle_index: u16,
+    slippage_tolerance_bps: Option<u16>,
+    authority: Option<Pubkey>,
+) -> Result<ClosePositionInstruction, Box<dyn Error>> {
+    let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
+    let authority = authority.unwrap_or(*FUNDER.try_lock()?);
+    if authority == Pubkey::default() {
+        return Err("Authority must be provided".into());
+    }
+
+    let position_bundle_address = get_position_bundle_address(&position_bundle_mint)?.0;
+    let position_address = get_bundled_position_address_checked(&position_bundle_mint, bundle_index)?;
+    let infos = rpc.get_multiple_accounts(&[position_address, position_bundle_mint]).await?;
+    let position = Position::from_bytes(&infos[0].as_ref().ok_or("Bundled position info not found")?.data)?;
+    let position_bundle_mint_info = infos[1].as_ref().ok_or("Position bundle mint info not found")?;
+
+    let pool_info = rpc.get_account(&position.fusion_pool).await?;
+    let pool = FusionPool::from_bytes(&pool_info.data)?;
+
+    let mint_infos = rpc.get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b]).await?;
+    let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
+    let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
+
+    let current_epoch = rpc.get_epoch().await?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
+
+    let quote = decrease_liquidity_quote(
+        position.liquidity,
+        slippage_tolerance_bps,
+        pool.sqrt_price,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        transfer_fee_a,
+        transfer_fee_b,
+    )?;
+
+    let lower_tick_array_start_index = get_tick_array_start_tick_index(position.tick_lower_index, pool.tick_spacing);
+    let upper_tick_array_start_index = get_tick_array_start_tick_index(position.tick_upper_index, pool.tick_spacing);
+
+    let position_bundle_token_account =
+        get_associated_token_address_with_program_id(&authority, &position_bundle_mint, &position_bundle_mint_info.owner);
+    let lower_tick_array_address = get_tick_array_address(&position.fusion_pool, lower_tick_array_start_index)?.0;
+    let upper_tick_array_address = get_tick_array_address(&position.fusion_pool, upper_tick_array_start_index)?.0;
+
+    let tick_array_infos = rpc.get_multiple_accounts(&[lower_tick_array_address, upper_tick_array_address]).await?;
+
+    let lower_tick_array_info = tick_array_infos[0].as_ref().ok_or("Lower tick array info not found")?;
+    let lower_tick_array = TickArray::from_bytes(&lower_tick_array_info.data)?;
+    let lower_tick =
+        &lower_tick_array.ticks[get_tick_index_in_array(position.tick_lower_index, lower_tick_array_start_index, pool.tick_spacing)? as usize];
+
+    let upper_tick_array_info = tick_array_infos[1].as_ref().ok_or("Upper tick array info not found")?;
+    let upper_tick_array = TickArray::from_bytes(&upper_tick_array_info.data)?;
+    let upper_tick =
+        &upper_tick_array.ticks[get_tick_index_in_array(position.tick_upper_index, upper_tick_array_start_index, pool.tick_spacing)? as usize];
+
+    let fees_quote = collect_fees_quote(
+        pool.clone().into(),
+        position.clone().into(),
+        lower_tick.clone().into(),
+        upper_tick.clone().into(),
+        transfer_fee_a,
+        transfer_fee_b,
+    )?;
+
+    let mut required_mints: Vec<TokenAccountStrategy> = Vec::new();
+    if quote.liquidity_delta > 0 || fees_quote.fee_owed_a > 0 || fees_quote.fee_owed_b > 0 {
+        required_mints.push(TokenAccountStrategy::WithoutBalance(pool.token_mint_a));
+        required_mints.push(TokenAccountStrategy::WithoutBalance(pool.token_mint_b));
+    }
+
+    let token_accounts = prepare_token_accounts_instructions(rpc, authority, required_mints).await?;
+
+    let mut instructions: Vec<Instruction> = Vec::new();
+    instructions.extend(token_accounts.create_instructions);
+
+    if quote.liquidity_delta > 0 || fees_quote.fee_owed_a > 0 || fees_quote.fee_owed_b > 0 {
+        let token_owner_account_a = token_accounts
+            .token_account_addresses
+            .get(&pool.token_mint_a)
+            .ok_or("Token A owner account not found")?;
+        let token_owner_account_b = token_accounts
+            .token_account_addresses
+            .get(&pool.token_mint_b)
+            .ok_or("Token B owner account not found")?;
+
+        if quote.liquidity_delta > 0 {
+            instructions.push(
+                DecreaseLiquidity {
+                    fusion_pool: position.fusion_pool,
+                    token_program_a: mint_a_info.owner,
+                    token_program_b: mint_b_info.owner,
+                    memo_program: spl_memo::ID,
+                    position_authority: authority,
+                    position: position_address,
+                    position_token_account: position_bundle_token_account,
+                    token_mint_a: pool.token_mint_a,
+                    token_mint_b: pool.token_mint_b,
+                    token_owner_account_a: *token_owner_account_a,
+                    token_owner_account_b: *token_owner_account_b,
+                    token_vault_a: pool.token_vault_a,
+                    token_vault_b: pool.token_vault_b,
+                    tick_array_lower: lower_tick_array_address,
+                    tick_array_upper: upper_tick_array_address,
+                }
+                .instruction(DecreaseLiquidityInstructionArgs {
+                    liquidity_amount: quote.liquidity_delta,
+                    token_min_a: quote.token_min_a,
+                    token_min_b: quote.token_min_b,
+                    remaining_accounts_info: None,
+                }),
+            );
+        }
+
+        if fees_quote.fee_owed_a > 0 || fees_quote.fee_owed_b > 0 {
+            instructions.push(
+                CollectFees {
+                    fusion_pool: position.fusion_pool,
+                    position_authority: authority,
+                    position: position_address,
+                    position_token_account: position_bundle_token_account,
+                    token_owner_account_a: *token_owner_account_a,
+                    token_owner_account_b: *token_owner_account_b,
+                    token_vault_a: pool.token_vault_a,
+                    token_vault_b: pool.token_vault_b,
+                    token_mint_a: pool.token_mint_a,
+                    token_mint_b: pool.token_mint_b,
+                    token_program_a: mint_a_info.owner,
+                    token_program_b: mint_b_info.owner,
+                    memo_program: spl_memo::ID,
+                }
+                .instruction(CollectFeesInstructionArgs {
+                    remaining_accounts_info: None,
+                }),
+            );
+        }
+    }
+
+    instructions.push(
+        CloseBundledPosition {
+            bundled_position: position_address,
+            position_bundle: position_bundle_address,
+            position_bundle_token_account,
+            position_bundle_authority: authority,
+            receiver: authority,
+        }
+        .instruction(CloseBundledPositionInstructionArgs { bundle_index }),
+    );
+
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(ClosePositionInstruction {
+        instructions,
+        additional_signers: token_accounts.additional_signers,
+        quote,
+        fees_quote,
+    })
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to delete an empty position bundle.
+///
+/// The position bundle NFT is burned and the rent of the bundle accounts is returned to the owner.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client.
+/// * `position_bundle_mint` - The public key of the position bundle NFT mint.
+/// * `authority` - An optional public key of the bundle owner. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `DeletePositionBundleInstruction` on success:
+///
+/// * `instructions` - A vector of `Instruction` objects required to delete the position bundle.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` account is invalid or missing.
+/// - The position bundle still contains positions.
+/// - The position bundle accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+///
+/// # Example
+///
+/// ```rust
+/// use fusionamm_sdk::delete_position_bundle_instructions;
+/// use solana_client::nonblocking::rpc_client::RpcClient;
+/// use solana_pubkey::pubkey;
+/// use solana_keypair::Keypair;
+/// use solana_signer::Signer;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rpc = RpcClient::new("https://api.devnet.solana.com".to_string());
+///     let wallet = Keypair::new(); // Load your wallet here
+///
+///     let position_bundle_mint = pubkey!("HqoV7Qv27REUtmd9UKSJGGmCRNx3531t33bDG1BUfo9K");
+///
+///     let result = delete_position_bundle_instructions(&rpc, position_bundle_mint, Some(wallet.pubkey())).await.unwrap();
+///
+///     println!("Number of Instructions: {}", result.instructions.len());
+/// }
+/// ```
+pub async fn delete_position_bundle_instructions(
+    rpc: &impl AccountSource,
+    position_bundle_mint: Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<DeletePositionBundleInstruction, Box<dyn Error>> {
+    let authority = authority.unwrap_or(*FUNDER.try_lock()?);
+    if authority == Pubkey::default() {
+        return Err("Authority must be provided".into());
+    }
+
+    let position_bundle_address = get_position_bundle_address(&position_bundle_mint)?.0;
+    let infos = rpc.get_multiple_accounts(&[position_bundle_address, position_bundle_mint]).await?;
+    let position_bundle = PositionBundle::from_bytes(&infos[0].as_ref().ok_or("Position bundle info not found")?.data)?;
+    let position_bundle_mint_info = infos[1].as_ref().ok_or("Position bundle mint info not found")?;
+
+    if !is_position_bundle_empty(&position_bundle.position_bitmap) {
+        return Err("Position bundle is not empty".into());
+    }
+
+    let position_bundle_token_account =
+        get_associated_token_address_with_program_id(&authority, &position_bundle_mint, &position_bundle_mint_info.owner);
+
+    let instruction = DeletePositionBundle {
+        position_bundle: position_bundle_address,
+        position_bundle_mint,
+        position_bundle_token_account,
+        position_bundle_owner: authority,
+        receiver: authority,
+        token_program: position_bundle_mint_info.owner,
+    }
+    .instruction();
+
+    Ok(DeletePositionBundleInstruction {
+        instructions: vec![instruction],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fusionamm_client::{get_bundled_position_address, get_position_bundle_address, Position, PositionBundle};
+    use fusionamm_core::POSITION_BUNDLE_SIZE;
+    use serial_test::serial;
+    use solana_program_test::tokio;
+    use solana_pubkey::Pubkey;
+    use solana_signer::Signer;
+
+    use crate::{
+        close_bundled_position_instructions, delete_position_bundle_instructions, initialize_position_bundle_instructions,
+        open_bundled_position_instructions,
+        tests::{setup_ata_with_amount, setup_fusion_pool, setup_mint_with_decimals, RpcContext},
+        IncreaseLiquidityParam, PriceOrTickIndex,
+    };
+
+    async fn setup_pool(ctx: &RpcContext) -> Pubkey {
+        let mint_1 = setup_mint_with_decimals(ctx, 9).await.unwrap();
+        let mint_2 = setup_mint_with_decimals(ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_1 < mint_2 { (mint_1, mint_2) } else { (mint_2, mint_1) };
+        setup_ata_with_amount(ctx, mint_a, 1_000_000_000).await.unwrap();
+        setup_ata_with_amount(ctx, mint_b, 1_000_000_000).await.unwrap();
+        setup_fusion_pool(ctx, mint_a, mint_b, 64, 300).await.unwrap()
+    }
+
+    async fn fetch_bundle(ctx: &RpcContext, position_bundle_mint: Pubkey) -> PositionBundle {
+        let address = get_position_bundle_address(&position_bundle_mint).unwrap().0;
+        let info = ctx.rpc.get_account(&address).await.unwrap();
+        PositionBundle::from_bytes(&info.data).unwrap()
+    }
+
+    async fn open_bundled_position(ctx: &RpcContext, position_bundle_mint: Pubkey, pool: Pubkey) -> u16 {
+        let ix = open_bundled_position_instructions(
+            &ctx.rpc,
+            position_bundle_mint,
+            pool,
+            PriceOrTickIndex::Tick(-192),
+            PriceOrTickIndex::Tick(192),
+            IncreaseLiquidityParam::Liquidity(1_000_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+        ctx.send_transaction_with_signers(ix.instructions, ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+        ix.bundle_index
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_position_bundle_lifecycle() {
+        let ctx = RpcContext::new().await;
+        let pool = setup_pool(&ctx).await;
+
+        let ix = initialize_position_bundle_instructions(false, Some(ctx.signer.pubkey())).unwrap();
+        let position_bundle_mint = ix.position_bundle_mint;
+        ctx.send_transaction_with_signers(ix.instructions, ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+        assert_eq!(fetch_bundle(&ctx, position_bundle_mint).await.position_bundle_mint, position_bundle_mint);
+
+        assert_eq!(open_bundled_position(&ctx, position_bundle_mint, pool).await, 0);
+        assert_eq!(open_bundled_position(&ctx, position_bundle_mint, pool).await, 1);
+
+        let position_address = get_bundled_position_address(&position_bundle_mint, 1).unwrap().0;
+        let position_info = ctx.rpc.get_account(&position_address).await.unwrap();
+        let position = Position::from_bytes(&position_info.data).unwrap();
+        assert_eq!(position.liquidity, 1_000_000);
+        assert_eq!(position.tick_lower_index, -192);
+        assert_eq!(position.tick_upper_index, 192);
+
+        let error = delete_position_bundle_instructions(&ctx.rpc, position_bundle_mint, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Position bundle is not empty");
+
+        let error = close_bundled_position_instructions(&ctx.rpc, position_bundle_mint, POSITION_BUNDLE_SIZE as u16, Some(100), None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Bundle index must be less than 256");
+
+        // Closing the first position frees its slot, which is reused by the next position.
+        let ix = close_bundled_position_instructions(&ctx.rpc, position_bundle_mint, 0, Some(100), None)
+            .await
+            .unwrap();
+        ctx.send_transaction_with_signers(ix.instructions, ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+        assert_eq!(open_bundled_position(&ctx, position_bundle_mint, pool).await, 0);
+
+        for bundle_index in [0, 1] {
+            let ix = close_bundled_position_instructions(&ctx.rpc, position_bundle_mint, bundle_index, Some(100), None)
+                .await
+                .unwrap();
+            ctx.send_transaction_with_signers(ix.instructions, ix.additional_signers.iter().collect())
+                .await
+                .unwrap();
+        }
+
+        let ix = delete_position_bundle_instructions(&ctx.rpc, position_bundle_mint, None).await.unwrap();
+        ctx.send_transaction(ix.instructions).await.unwrap();
+        assert!(ctx
+            .rpc
+            .get_account(&get_position_bundle_address(&position_bundle_mint).unwrap().0)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_initialize_position_bundle_with_metadata() {
+        let ctx = RpcContext::new().await;
+
+        let ix = initialize_position_bundle_instructions(true, Some(ctx.signer.pubkey())).unwrap();
+        assert_eq!(ix.instructions.len(), 1);
+        assert_eq!(ix.additional_signers.len(), 1);
+        assert_eq!(ix.additional_signers[0].pubkey(), ix.position_bundle_mint);
+        assert_eq!(ix.position_bundle, get_position_bundle_address(&ix.position_bundle_mint).unwrap().0);
+    }
+}
@@ -0,0 +1,888 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::{
+    lock::ensure_position_unlocked,
+    swap::{fetch_tick_arrays_in_swap_direction, get_swap_tick_array_accounts, get_swap_tick_array_addresses, tick_arrays_from_slice},
+    token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy},
+    AccountSource, PriceOrTickIndex, SwapQuote, FUNDER, SLIPPAGE_TOLERANCE_BPS,
+};
+use fusionamm_client::{
+    get_position_address, get_position_lock_address, get_tick_array_address, CollectFees, CollectFeesInstructionArgs, DecreaseLiquidity,
+    DecreaseLiquidityInstructionArgs, FusionPool, IncreaseLiquidity, IncreaseLiquidityInstructionArgs, InitializeTickArray,
+    InitializeTickArrayInstructionArgs, Position, SetPositionRange, SetPositionRangeInstructionArgs, Swap, SwapInstructionArgs, TickArray,
+};
+use fusionamm_core::{
+    collect_fees_quote, compute_swap, decrease_liquidity_quote, get_initializable_tick_index, get_liquidity_from_amount_a,
+    get_liquidity_from_amount_b, get_liquidity_from_amounts, get_tick_array_start_tick_index, get_tick_index_in_array, increase_liquidity_quote,
+    order_tick_indexes, price_to_tick_index, swap_quote_by_input_token, tick_index_to_sqrt_price, try_apply_transfer_fee, CollectFeesQuote,
+    DecreaseLiquidityQuote, ExactInSwapQuote, FusionPoolFacade, IncreaseLiquidityQuote, TickArrayFacade, TickArraySequence, TickFacade, TransferFee,
+    TICK_ARRAY_SIZE,
+};
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_program::program_pack::Pack;
+use solana_pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::state::Mint;
+use std::error::Error;
+
+// TODO: support transfer hooks
+
+/// Represents the instructions and quotes for moving a position to a new price range.
+#[derive(Debug)]
+pub struct RebalancePositionInstruction {
+    /// A vector of `Instruction` objects required to rebalance the position.
+    pub instructions: Vec<Instruction>,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+
+    /// The new lower tick index of the position.
+    pub tick_lower_index: i32,
+
+    /// The new upper tick index of the position.
+    pub tick_upper_index: i32,
+
+    /// The quote for withdrawing the liquidity from the current range.
+    pub decrease_quote: DecreaseLiquidityQuote,
+
+    /// Details of the fees collected from the position before its range is changed.
+    pub fees_quote: CollectFeesQuote,
+
+    /// The quote for the swap converting the withdrawn tokens into the ratio required by the new range, if a swap is needed.
+    pub swap_quote: Option<ExactInSwapQuote>,
+
+    /// The quote for depositing the liquidity into the new range.
+    pub increase_quote: IncreaseLiquidityQuote,
+
+    /// The cost of initializing the tick arrays of the new range, measured in lamports.
+    pub initialization_cost: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RebalanceSwap {
+    a_to_b: bool,
+    amount: u64,
+    next_sqrt_price: u128,
+}
+
+// Returns which token is held in excess relative to the ratio of the range at the given price,
+// or None if the amounts are already balanced. The transfer fees are deducted from the amounts,
+// as they are charged when the tokens are deposited.
+fn get_excess_token(
+    amount_a: u64,
+    amount_b: u64,
+    sqrt_price: u128,
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<Option<bool>, Box<dyn Error>> {
+    let amount_a = try_apply_transfer_fee(amount_a, transfer_fee_a.unwrap_or_default())?;
+    let amount_b = try_apply_transfer_fee(amount_b, transfer_fee_b.unwrap_or_default())?;
+    if sqrt_price <= sqrt_price_lower {
+        return Ok((amount_b > 0).then_some(false));
+    }
+    if sqrt_price >= sqrt_price_upper {
+        return Ok((amount_a > 0).then_some(true));
+    }
+    let liquidity_a = get_liquidity_from_amount_a(amount_a, sqrt_price, sqrt_price_upper)?;
+    let liquidity_b = get_liquidity_from_amount_b(amount_b, sqrt_price_lower, sqrt_price)?;
+    Ok(match liquidity_a.cmp(&liquidity_b) {
+        std::cmp::Ordering::Greater => Some(true),
+        std::cmp::Ordering::Less => Some(false),
+        std::cmp::Ordering::Equal => None,
+    })
+}
+
+// Finds the largest exact-in swap of the excess token that does not overshoot the ratio required by the range.
+// The swap is simulated against the pool, so price impact, swap fees and transfer fees are taken into account.
+#[allow(clippy::too_many_arguments)]
+fn get_rebalance_swap(
+    amount_a: u64,
+    amount_b: u64,
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    fusion_pool: FusionPoolFacade,
+    tick_arrays: &[TickArrayFacade],
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<Option<RebalanceSwap>, Box<dyn Error>> {
+    let a_to_b =
+        match get_excess_token(amount_a, amount_b, fusion_pool.sqrt_price, sqrt_price_lower, sqrt_price_upper, transfer_fee_a, transfer_fee_b)? {
+            Some(a_to_b) => a_to_b,
+            None => return Ok(None),
+        };
+    let (transfer_fee_in, transfer_fee_out) = if a_to_b {
+        (transfer_fee_a, transfer_fee_b)
+    } else {
+        (transfer_fee_b, transfer_fee_a)
+    };
+
+    let simulate = |amount: u64| -> Option<RebalanceSwap> {
+        let amount_after_transfer_fee = try_apply_transfer_fee(amount, transfer_fee_in.unwrap_or_default()).ok()?;
+        let tick_sequence = TickArraySequence::new(tick_arrays.to_vec(), fusion_pool.tick_spacing).ok()?;
+        let result = compute_swap(amount_after_transfer_fee, 0, fusion_pool, tick_sequence, a_to_b, true).ok()?;
+        let (amount_in, amount_out) = if a_to_b {
+            (result.token_a, result.token_b)
+        } else {
+            (result.token_b, result.token_a)
+        };
+        if amount_in != amount_after_transfer_fee {
+            return None;
+        }
+        let amount_out = try_apply_transfer_fee(amount_out, transfer_fee_out.unwrap_or_default()).ok()?;
+        let (amount_a, amount_b) = if a_to_b {
+            (amount_a - amount, amount_b.checked_add(amount_out)?)
+        } else {
+            (amount_a.checked_add(amount_out)?, amount_b - amount)
+        };
+        let excess =
+            get_excess_token(amount_a, amount_b, result.next_sqrt_price, sqrt_price_lower, sqrt_price_upper, transfer_fee_a, transfer_fee_b).ok()?;
+        if excess == Some(!a_to_b) {
+            return None;
+        }
+        Some(RebalanceSwap {
+            a_to_b,
+            amount,
+            next_sqrt_price: result.next_sqrt_price,
+        })
+    };
+
+    let mut best: Option<RebalanceSwap> = None;
+    let mut low = 1u64;
+    let mut high = if a_to_b { amount_a } else { amount_b };
+    while low <= high {
+        let mid = low + (high - low) / 2;
+        match simulate(mid) {
+            Some(swap) => {
+                best = Some(swap);
+                low = mid + 1;
+            }
+            None => high = mid - 1,
+        }
+    }
+
+    Ok(best)
+}
+
+// Removes the liquidity of a position from the pool and tick arrays, mirroring the effect
+// of the decrease liquidity instruction that precedes the swap in the same transaction.
+fn remove_position_liquidity(fusion_pool: &mut FusionPoolFacade, tick_arrays: &mut [TickArrayFacade], position: &Position) {
+    if position.liquidity == 0 {
+        return;
+    }
+
+    if position.tick_lower_index <= fusion_pool.tick_current_index && fusion_pool.tick_current_index < position.tick_upper_index {
+        fusion_pool.liquidity -= position.liquidity;
+    }
+
+    let tick_array_width = fusion_pool.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    for tick_array in tick_arrays.iter_mut() {
+        for (tick_index, liquidity_net) in [
+            (position.tick_lower_index, position.liquidity as i128),
+            (position.tick_upper_index, -(position.liquidity as i128)),
+        ] {
+            if tick_index < tick_array.start_tick_index || tick_index >= tick_array.start_tick_index + tick_array_width {
+                continue;
+            }
+            if let Ok(index) = get_tick_index_in_array(tick_index, tick_array.start_tick_index, fusion_pool.tick_spacing) {
+                let tick = &mut tick_array.ticks[index as usize];
+                tick.liquidity_net -= liquidity_net;
+                tick.liquidity_gross = tick.liquidity_gross.saturating_sub(position.liquidity);
+                tick.initialized = tick.liquidity_gross > 0 || tick.open_orders_input > 0 || tick.part_filled_orders_input > 0;
+            }
+        }
+    }
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to move a liquidity position to a new price range.
+///
+/// Instead of closing the position and opening a new one, the position is kept and its range is
+/// changed with the `set_position_range` instruction. If the position holds liquidity, the generated
+/// instructions withdraw it, collect the fees, change the range, swap the withdrawn tokens into the
+/// ratio required by the new range and deposit them back. If the position is empty, only the fees owed
+/// (if any) are collected before the range is changed. Missing tick arrays of the new range are initialized.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client for fetching accounts and pool data.
+/// * `position_mint_address` - The public key of the NFT mint address representing the position.
+/// * `lower_price_or_tick_index` - The new lower bound of the position, as a price or a tick index.
+/// * `upper_price_or_tick_index` - The new upper bound of the position, as a price or a tick index.
+/// * `slippage_tolerance_bps` - An optional slippage tolerance in basis points. Defaults to the global slippage tolerance if not provided.
+/// * `authority` - An optional public key of the account authorizing the transaction. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `RebalancePositionInstruction` on success:
+///
+/// * `instructions` - A vector of `Instruction` objects required to rebalance the position.
+/// * `additional_signers` - A vector of `Keypair` objects representing additional signers required for the instructions.
+/// * `tick_lower_index` - The new lower tick index of the position.
+/// * `tick_upper_index` - The new upper tick index of the position.
+/// * `decrease_quote` - The quote for withdrawing the liquidity from the current range.
+/// * `fees_quote` - Details of the fees collected from the position.
+/// * `swap_quote` - The quote for the swap balancing the withdrawn tokens, if a swap is needed.
+/// * `increase_quote` - The quote for depositing the liquidity into the new range.
+/// * `initialization_cost` - The cost of initializing the tick arrays of the new range, measured in lamports.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` account is invalid or missing.
+/// - The new range is the same as the current range of the position.
+/// - The position is locked (`PositionLockError::PositionLocked`).
+/// - The position, token mint, or tick array accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+///
+/// # Example
+///
+/// ```rust
+/// use fusionamm_sdk::{rebalance_position_instructions, PriceOrTickIndex};
+/// use solana_client::nonblocking::rpc_client::RpcClient;
+/// use solana_pubkey::pubkey;
+/// use solana_keypair::Keypair;
+/// use solana_signer::Signer;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rpc = RpcClient::new("https://api.mainnet.solana.com".to_string());
+///     let wallet = Keypair::new(); // Load your wallet here
+///
+///     let position_mint_address = pubkey!("HqoV7Qv27REUtmd9UKSJGGmCRNx3531t33bDG1BUfo9K");
+///
+///     let result = rebalance_position_instructions(
+///         &rpc,
+///         position_mint_address,
+///         PriceOrTickIndex::Price(0.9),
+///         PriceOrTickIndex::Price(1.1),
+///         Some(100),
+///         Some(wallet.pubkey()),
+///     )
+///     .await
+///     .unwrap();
+///
+///     println!("New range: [{}, {}]", result.tick_lower_index, result.tick_upper_index);
+///     println!("Number of Instructions: {}", result.instructions.len());
+/// }
+/// ```
+pub async fn rebalance_position_instructions(
+    rpc: &impl AccountSource,
+    position_mint_address: Pubkey,
+    lower_price_or_tick_index: PriceOrTickIndex,
+    upper_price_or_tick_index: PriceOrTickIndex,
+    slippage_tolerance_bps: Option<u16>,
+    authority: Option<Pubkey>,
+) -> Result<RebalancePositionInstruction, Box<dyn Error>> {
+    let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
+    let authority = authority.unwrap_or(*FUNDER.try_lock()?);
+    if authority == Pubkey::default() {
+        return Err("Authority must be provided".into());
+    }
+    let rent = rpc.get_rent().await?;
+
+    let position_address = get_position_address(&position_mint_address)?.0;
+    let position_info = rpc.get_account(&position_address).await?;
+    let position = Position::from_bytes(&position_info.data)?;
+
+    let pool_info = rpc.get_account(&position.fusion_pool).await?;
+    let pool = FusionPool::from_bytes(&pool_info.data)?;
+
+    let position_lock_address = get_position_lock_address(&position_mint_address)?.0;
+    let mint_infos = rpc
+        .get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b, position_mint_address, position_lock_address])
+        .await?;
+    ensure_position_unlocked(position_mint_address, mint_infos[3].as_ref())?;
+
+    // Use 'unpack_from_slice' instead of 'unpack' because the account length might be larger than Mint::LEN due to extensions.
+    let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
+    if mint_a_info.data.len() < Mint::LEN {
+        return Err("Wrong token A mint account length".into());
+    }
+    let mint_a = Mint::unpack_from_slice(&mint_a_info.data)?;
+    let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
+    if mint_b_info.data.len() < Mint::LEN {
+        return Err("Wrong token B mint account length".into());
+    }
+    let mint_b = Mint::unpack_from_slice(&mint_b_info.data)?;
+    let position_mint_info = mint_infos[2].as_ref().ok_or("Position mint info not found")?;
+
+    let lower_tick_index = match lower_price_or_tick_index {
+        PriceOrTickIndex::Tick(tick_index) => tick_index,
+        PriceOrTickIndex::Price(price) => price_to_tick_index(price, mint_a.decimals, mint_b.decimals),
+    };
+    let upper_tick_index = match upper_price_or_tick_index {
+        PriceOrTickIndex::Tick(tick_index) => tick_index,
+        PriceOrTickIndex::Price(price) => price_to_tick_index(price, mint_a.decimals, mint_b.decimals),
+    };
+    let tick_range = order_tick_indexes(lower_tick_index, upper_tick_index);
+    let tick_lower_index = get_initializable_tick_index(tick_range.tick_lower_index, pool.tick_spacing, Some(false));
+    let tick_upper_index = get_initializable_tick_index(tick_range.tick_upper_index, pool.tick_spacing, Some(true));
+    if tick_lower_index == position.tick_lower_index && tick_upper_index == position.tick_upper_index {
+        return Err("Position already has the requested range".into());
+    }
+
+    let current_epoch = rpc.get_epoch().await?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
+
+    let old_lower_tick_array_start_index = get_tick_array_start_tick_index(position.tick_lower_index, pool.tick_spacing);
+    let old_upper_tick_array_start_index = get_tick_array_start_tick_index(position.tick_upper_index, pool.tick_spacing);
+    let new_lower_tick_array_start_index = get_tick_array_start_tick_index(tick_lower_index, pool.tick_spacing);
+    let new_upper_tick_array_start_index = get_tick_array_start_tick_index(tick_upper_index, pool.tick_spacing);
+
+    let old_lower_tick_array_address = get_tick_array_address(&position.fusion_pool, old_lower_tick_array_start_index)?.0;
+    let old_upper_tick_array_address = get_tick_array_address(&position.fusion_pool, old_upper_tick_array_start_index)?.0;
+    let new_lower_tick_array_address = get_tick_array_address(&position.fusion_pool, new_lower_tick_array_start_index)?.0;
+    let new_upper_tick_array_address = get_tick_array_address(&position.fusion_pool, new_upper_tick_array_start_index)?.0;
+
+    let tick_array_infos = rpc
+        .get_multiple_accounts(&[
+            old_lower_tick_array_address,
+            old_upper_tick_array_address,
+            new_lower_tick_array_address,
+            new_upper_tick_array_address,
+        ])
+        .await?;
+
+    // The ticks of an empty position don't affect its fees, so missing tick arrays are treated as uninitialized ticks.
+    let get_tick = |tick_array_info: &Option<solana_account::Account>, tick_index: i32, start_index: i32| -> Result<TickFacade, Box<dyn Error>> {
+        match tick_array_info {
+            Some(info) => {
+                let tick_array = TickArray::from_bytes(&info.data)?;
+                Ok(tick_array.ticks[get_tick_index_in_array(tick_index, start_index, pool.tick_spacing)? as usize]
+                    .clone()
+                    .into())
+            }
+            None if position.liquidity == 0 => Ok(TickFacade::default()),
+            None => Err("Position tick array info not found".into()),
+        }
+    };
+    let lower_tick = get_tick(&tick_array_infos[0], position.tick_lower_index, old_lower_tick_array_start_index)?;
+    let upper_tick = get_tick(&tick_array_infos[1], position.tick_upper_index, old_upper_tick_array_start_index)?;
+
+    let fees_quote = collect_fees_quote(pool.clone().into(), position.clone().into(), lower_tick, upper_tick, transfer_fee_a, transfer_fee_b)?;
+
+    let decrease_quote = decrease_liquidity_quote(
+        position.liquidity,
+        slippage_tolerance_bps,
+        pool.sqrt_price,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        transfer_fee_a,
+        transfer_fee_b,
+    )?;
+
+    let mut swap: Option<(RebalanceSwap, ExactInSwapQuote, Vec<Pubkey>)> = None;
+    let mut increase_quote = IncreaseLiquidityQuote::default();
+
+    if position.liquidity > 0 {
+        let sqrt_price_lower = tick_index_to_sqrt_price(tick_lower_index);
+        let sqrt_price_upper = tick_index_to_sqrt_price(tick_upper_index);
+        let amount_a = decrease_quote.token_est_a + fees_quote.fee_owed_a;
+        let amount_b = decrease_quote.token_est_b + fees_quote.fee_owed_b;
+
+        let mut fusion_pool: FusionPoolFacade = pool.clone().into();
+        let mut next_sqrt_price = pool.sqrt_price;
+        let mut deposit_amount_a = amount_a;
+        let mut deposit_amount_b = amount_b;
+
+        if let Some(a_to_b) =
+            get_excess_token(amount_a, amount_b, pool.sqrt_price, sqrt_price_lower, sqrt_price_upper, transfer_fee_a, transfer_fee_b)?
+        {
+            let tick_arrays = fetch_tick_arrays_in_swap_direction(rpc, position.fusion_pool, &pool, a_to_b).await?;
+            let mut tick_array_facades: Vec<TickArrayFacade> = tick_arrays.iter().map(|x| x.1).collect();
+            remove_position_liquidity(&mut fusion_pool, &mut tick_array_facades, &position);
+
+            if let Some(rebalance_swap) = get_rebalance_swap(
+                amount_a,
+                amount_b,
+                sqrt_price_lower,
+                sqrt_price_upper,
+                fusion_pool,
+                &tick_array_facades,
+                transfer_fee_a,
+                transfer_fee_b,
+            )? {
+                let quote = swap_quote_by_input_token(
+                    rebalance_swap.amount,
+                    a_to_b,
+                    slippage_tolerance_bps,
+                    fusion_pool,
+                    tick_arrays_from_slice(&tick_array_facades)?,
+                    transfer_fee_a,
+                    transfer_fee_b,
+                )?;
+                let tick_array_addresses = get_swap_tick_array_addresses(position.fusion_pool, &pool, a_to_b, &SwapQuote::ExactIn(quote))?;
+
+                if a_to_b {
+                    deposit_amount_a -= quote.token_in;
+                    deposit_amount_b += quote.token_est_out;
+                } else {
+                    deposit_amount_a += quote.token_est_out;
+                    deposit_amount_b -= quote.token_in;
+                }
+                next_sqrt_price = rebalance_swap.next_sqrt_price;
+                swap = Some((rebalance_swap, quote, tick_array_addresses));
+            }
+        }
+
+        let liquidity = get_liquidity_from_amounts(
+            next_sqrt_price,
+            sqrt_price_lower,
+            sqrt_price_upper,
+            try_apply_transfer_fee(deposit_amount_a, transfer_fee_a.unwrap_or_default())?,
+            try_apply_transfer_fee(deposit_amount_b, transfer_fee_b.unwrap_or_default())?,
+        )?;
+        increase_quote = increase_liquidity_quote(
+            liquidity,
+            slippage_tolerance_bps,
+            next_sqrt_price,
+            tick_lower_index,
+            tick_upper_index,
+            transfer_fee_a,
+            transfer_fee_b,
+        )?;
+    }
+
+    let mut instructions: Vec<Instruction> = Vec::new();
+    let mut initialization_cost: u64 = 0;
+
+    let has_fees = fees_quote.fee_owed_a > 0 || fees_quote.fee_owed_b > 0;
+    let mut required_mints: Vec<TokenAccountStrategy> = Vec::new();
+    if position.liquidity > 0 || has_fees {
+        required_mints.push(TokenAccountStrategy::WithoutBalance(pool.token_mint_a));
+        required_mints.push(TokenAccountStrategy::WithoutBalance(pool.token_mint_b));
+    }
+
+    let token_accounts = prepare_token_accounts_instructions(rpc, authority, required_mints).await?;
+    instructions.extend(token_accounts.create_instructions);
+
+    if tick_array_infos[2].is_none() {
+        instructions.push(
+            InitializeTickArray {
+                fusion_pool: position.fusion_pool,
+                funder: authority,
+                tick_array: new_lower_tick_array_address,
+                system_program: solana_program::system_program::id(),
+            }
+            .instruction(InitializeTickArrayInstructionArgs {
+                start_tick_index: new_lower_tick_array_start_index,
+            }),
+        );
+        initialization_cost += rent.minimum_balance(TickArray::MIN_LEN);
+    }
+
+    if tick_array_infos[3].is_none() && new_lower_tick_array_start_index != new_upper_tick_array_start_index {
+        instructions.push(
+            InitializeTickArray {
+                fusion_pool: position.fusion_pool,
+                funder: authority,
+                tick_array: new_upper_tick_array_address,
+                system_program: solana_program::system_program::id(),
+            }
+            .instruction(InitializeTickArrayInstructionArgs {
+                start_tick_index: new_upper_tick_array_start_index,
+            }),
+        );
+        initialization_cost += rent.minimum_balance(TickArray::MIN_LEN);
+    }
+
+    let position_token_account_address = get_associated_token_address_with_program_id(&authority, &position_mint_address, &position_mint_info.owner);
+
+    if position.liquidity > 0 || has_fees {
+        let token_owner_account_a = *token_accounts
+            .token_account_addresses
+            .get(&pool.token_mint_a)
+            .ok_or("Token A owner account not found")?;
+        let token_owner_account_b = *token_accounts
+            .token_account_addresses
+            .get(&pool.token_mint_b)
+            .ok_or("Token B owner account not found")?;
+
+        if position.liquidity > 0 {
+            instructions.push(
+                DecreaseLiquidity {
+                    fusion_pool: position.fusion_pool,
+                    token_program_a: mint_a_info.owner,
+                    token_program_b: mint_b_info.owner,
+                    memo_program: spl_memo::ID,
+                    position_authority: authority,
+                    position: position_address,
+                    position_token_account: position_token_account_address,
+                    token_mint_a: pool.token_mint_a,
+                    token_mint_b: pool.token_mint_b,
+                    token_owner_account_a,
+                    token_owner_account_b,
+                    token_vault_a: pool.token_vault_a,
+                    token_vault_b: pool.token_vault_b,
+                    tick_array_lower: old_lower_tick_array_address,
+                    tick_array_upper: old_upper_tick_array_address,
+                }
+                .instruction(DecreaseLiquidityInstructionArgs {
+                    liquidity_amount: decrease_quote.liquidity_delta,
+                    token_min_a: decrease_quote.token_min_a,
+                    token_min_b: decrease_quote.token_min_b,
+                    remaining_accounts_info: None,
+                }),
+            );
+        }
+
+        if has_fees {
+            instructions.push(
+                CollectFees {
+                    fusion_pool: position.fusion_pool,
+                    position_authority: authority,
+                    position: position_address,
+                    position_token_account: position_token_account_address,
+                    token_owner_account_a,
+                    token_owner_account_b,
+                    token_vault_a: pool.token_vault_a,
+                    token_vault_b: pool.token_vault_b,
+                    token_mint_a: pool.token_mint_a,
+                    token_mint_b: pool.token_mint_b,
+                    token_program_a: mint_a_info.owner,
+                    token_program_b: mint_b_info.owner,
+                    memo_program: spl_memo::ID,
+                }
+                .instruction(CollectFeesInstructionArgs {
+                    remaining_accounts_info: None,
+                }),
+            );
+        }
+    }
+
+    instructions.push(
+        SetPositionRange {
+            position_authority: authority,
+            position: position_address,
+            position_token_account: position_token_account_address,
+            fusion_pool: position.fusion_pool,
+        }
+        .instruction(SetPositionRangeInstructionArgs {
+            tick_lower_index,
+            tick_upper_index,
+        }),
+    );
+
+    if increase_quote.liquidity_delta > 0 {
+        let token_owner_account_a = *token_accounts
+            .token_account_addresses
+            .get(&pool.token_mint_a)
+            .ok_or("Token A owner account not found")?;
+        let token_owner_account_b = *token_accounts
+            .token_account_addresses
+            .get(&pool.token_mint_b)
+            .ok_or("Token B owner account not found")?;
+
+        if let Some((rebalance_swap, quote, tick_array_addresses)) = &swap {
+            let tick_array_accounts = get_swap_tick_array_accounts(tick_array_addresses);
+            instructions.push(
+                Swap {
+                    token_program_a: mint_a_info.owner,
+                    token_program_b: mint_b_info.owner,
+                    memo_program: spl_memo::ID,
+                    token_authority: authority,
+                    fusion_pool: position.fusion_pool,
+                    token_mint_a: pool.token_mint_a,
+                    token_mint_b: pool.token_mint_b,
+                    token_owner_account_a,
+                    token_vault_a: pool.token_vault_a,
+                    token_owner_account_b,
+                    token_vault_b: pool.token_vault_b,
+                    tick_array0: tick_array_accounts.tick_arrays[0],
+                    tick_array1: tick_array_accounts.tick_arrays[1],
+                    tick_array2: tick_array_accounts.tick_arrays[2],
+                }
+                .instruction_with_remaining_accounts(
+                    SwapInstructionArgs {
+                        amount: rebalance_swap.amount,
+                        other_amount_threshold: quote.token_min_out,
+                        sqrt_price_limit: 0,
+                        amount_specified_is_input: true,
+                        a_to_b: rebalance_swap.a_to_b,
+                        remaining_accounts_info: tick_array_accounts.remaining_accounts_info,
+                    },
+                    &tick_array_accounts.supplemental_tick_arrays,
+                ),
+            );
+        }
+
+        instructions.push(
+            IncreaseLiquidity {
+                fusion_pool: position.fusion_pool,
+                token_program_a: mint_a_info.owner,
+                token_program_b: mint_b_info.owner,
+                memo_program: spl_memo::ID,
+                position_authority: authority,
+                position: position_address,
+                position_token_account: position_token_account_address,
+                token_mint_a: pool.token_mint_a,
+                token_mint_b: pool.token_mint_b,
+                token_owner_account_a,
+                token_owner_account_b,
+                token_vault_a: pool.token_vault_a,
+                token_vault_b: pool.token_vault_b,
+                tick_array_lower: new_lower_tick_array_address,
+                tick_array_upper: new_upper_tick_array_address,
+            }
+            .instruction(IncreaseLiquidityInstructionArgs {
+                liquidity_amount: increase_quote.liquidity_delta,
+                token_max_a: increase_quote.token_max_a,
+                token_max_b: increase_quote.token_max_b,
+                remaining_accounts_info: None,
+            }),
+        );
+    }
+
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(RebalancePositionInstruction {
+        instructions,
+        additional_signers: token_accounts.additional_signers,
+        tick_lower_index,
+        tick_upper_index,
+        decrease_quote,
+        fees_quote,
+        swap_quote: swap.map(|x| x.1),
+        increase_quote,
+        initialization_cost,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use fusionamm_client::{get_position_address, FusionPool, Position, SET_POSITION_RANGE_DISCRIMINATOR};
+    use fusionamm_core::{tick_index_to_sqrt_price, FusionPoolFacade, TickArrayFacade, TickFacade, TransferFee, TICK_ARRAY_SIZE};
+    use serial_test::serial;
+    use solana_program_test::tokio;
+    use solana_pubkey::Pubkey;
+    use solana_signer::Signer;
+
+    use super::{get_excess_token, get_rebalance_swap};
+    use crate::{
+        increase_liquidity_instructions, rebalance_position_instructions,
+        tests::{setup_ata_with_amount, setup_fusion_pool, setup_mint_with_decimals, setup_position, RpcContext},
+        IncreaseLiquidityParam, PriceOrTickIndex,
+    };
+
+    fn test_fusion_pool() -> FusionPoolFacade {
+        FusionPoolFacade {
+            tick_spacing: 64,
+            fee_rate: 300,
+            liquidity: 1_000_000_000_000,
+            sqrt_price: 1 << 64,
+            tick_current_index: 0,
+            ..FusionPoolFacade::default()
+        }
+    }
+
+    fn test_tick_arrays(a_to_b: bool) -> Vec<TickArrayFacade> {
+        let offset = TICK_ARRAY_SIZE as i32 * test_fusion_pool().tick_spacing as i32;
+        let step = if a_to_b { -offset } else { offset };
+        (0..3)
+            .map(|i| TickArrayFacade {
+                start_tick_index: i * step,
+                ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rebalance_swap_into_range() {
+        let sqrt_price_lower = tick_index_to_sqrt_price(-6400);
+        let sqrt_price_upper = tick_index_to_sqrt_price(6400);
+
+        let swap = get_rebalance_swap(1_000_000, 0, sqrt_price_lower, sqrt_price_upper, test_fusion_pool(), &test_tick_arrays(true), None, None)
+            .unwrap()
+            .unwrap();
+        assert!(swap.a_to_b);
+        assert!(swap.amount > 490_000 && swap.amount < 510_000, "Unexpected swap amount: {}", swap.amount);
+        assert!(swap.next_sqrt_price < 1 << 64);
+
+        let swap = get_rebalance_swap(0, 1_000_000, sqrt_price_lower, sqrt_price_upper, test_fusion_pool(), &test_tick_arrays(false), None, None)
+            .unwrap()
+            .unwrap();
+        assert!(!swap.a_to_b);
+        assert!(swap.amount > 490_000 && swap.amount < 510_000, "Unexpected swap amount: {}", swap.amount);
+        assert!(swap.next_sqrt_price > 1 << 64);
+    }
+
+    #[test]
+    fn test_rebalance_swap_out_of_range() {
+        // The new range is above the current price, so only token A can be deposited.
+        let swap = get_rebalance_swap(
+            0,
+            1_000_000,
+            tick_index_to_sqrt_price(640),
+            tick_index_to_sqrt_price(1280),
+            test_fusion_pool(),
+            &test_tick_arrays(false),
+            None,
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(!swap.a_to_b);
+        assert_eq!(swap.amount, 1_000_000);
+    }
+
+    #[test]
+    fn test_rebalance_swap_balanced() {
+        let sqrt_price_lower = tick_index_to_sqrt_price(-6400);
+        let sqrt_price_upper = tick_index_to_sqrt_price(6400);
+        assert_eq!(get_excess_token(0, 0, 1 << 64, sqrt_price_lower, sqrt_price_upper, None, None).unwrap(), None);
+        assert_eq!(
+            get_rebalance_swap(0, 0, sqrt_price_lower, sqrt_price_upper, test_fusion_pool(), &test_tick_arrays(true), None, None).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rebalance_swap_with_transfer_fee() {
+        let sqrt_price_lower = tick_index_to_sqrt_price(-6400);
+        let sqrt_price_upper = tick_index_to_sqrt_price(6400);
+
+        let swap = get_rebalance_swap(1_000_000, 0, sqrt_price_lower, sqrt_price_upper, test_fusion_pool(), &test_tick_arrays(true), None, None)
+            .unwrap()
+            .unwrap();
+        let swap_with_fee = get_rebalance_swap(
+            1_000_000,
+            0,
+            sqrt_price_lower,
+            sqrt_price_upper,
+            test_fusion_pool(),
+            &test_tick_arrays(true),
+            None,
+            Some(TransferFee::new(1000)),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(swap_with_fee.a_to_b);
+        assert!(swap_with_fee.amount > swap.amount, "Unexpected swap amount: {}", swap_with_fee.amount);
+    }
+
+    async fn setup_pool(ctx: &RpcContext) -> Result<Pubkey, Box<dyn Error>> {
+        let mint_1 = setup_mint_with_decimals(ctx, 9).await?;
+        let mint_2 = setup_mint_with_decimals(ctx, 9).await?;
+        let (mint_a, mint_b) = if mint_1 < mint_2 { (mint_1, mint_2) } else { (mint_2, mint_1) };
+        setup_ata_with_amount(ctx, mint_a, 1_000_000_000).await?;
+        setup_ata_with_amount(ctx, mint_b, 1_000_000_000).await?;
+        setup_fusion_pool(ctx, mint_a, mint_b, 64, 300).await
+    }
+
+    async fn setup_position_with_liquidity(
+        ctx: &RpcContext,
+        pool: Pubkey,
+        tick_range: (i32, i32),
+        liquidity: u128,
+    ) -> Result<Pubkey, Box<dyn Error>> {
+        let position_mint = setup_position(ctx, pool, Some(tick_range), None).await?;
+        let ix = increase_liquidity_instructions(&ctx.rpc, position_mint, IncreaseLiquidityParam::Liquidity(liquidity), Some(100), None).await?;
+        ctx.send_transaction_with_signers(ix.instructions, ix.additional_signers.iter().collect())
+            .await?;
+        Ok(position_mint)
+    }
+
+    async fn fetch_position(ctx: &RpcContext, position_mint: Pubkey) -> Result<Position, Box<dyn Error>> {
+        let position_address = get_position_address(&position_mint)?.0;
+        let account = ctx.rpc.get_account(&position_address).await?;
+        Ok(Position::from_bytes(&account.data)?)
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_rebalance_position() {
+        let ctx = RpcContext::new().await;
+        let pool = setup_pool(&ctx).await.unwrap();
+        setup_position_with_liquidity(&ctx, pool, (-5632, 5632), 1_000_000_000).await.unwrap();
+        let position_mint = setup_position_with_liquidity(&ctx, pool, (-192, 192), 1_000_000).await.unwrap();
+
+        let ix = rebalance_position_instructions(
+            &ctx.rpc,
+            position_mint,
+            PriceOrTickIndex::Tick(0),
+            PriceOrTickIndex::Tick(640),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(ix.tick_lower_index, 0);
+        assert_eq!(ix.tick_upper_index, 640);
+        assert_eq!(ix.decrease_quote.liquidity_delta, 1_000_000);
+        assert!(ix.swap_quote.is_some());
+        assert!(ix.increase_quote.liquidity_delta > 0);
+
+        ctx.send_transaction_with_signers(ix.instructions, ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+
+        let position = fetch_position(&ctx, position_mint).await.unwrap();
+        assert_eq!(position.tick_lower_index, 0);
+        assert_eq!(position.tick_upper_index, 640);
+        assert_eq!(position.liquidity, ix.increase_quote.liquidity_delta);
+
+        let pool = FusionPool::from_bytes(&ctx.rpc.get_account(&pool).await.unwrap().data).unwrap();
+        assert!(pool.tick_current_index < 0);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_rebalance_empty_position() {
+        let ctx = RpcContext::new().await;
+        let pool = setup_pool(&ctx).await.unwrap();
+        let position_mint = setup_position(&ctx, pool, Some((-192, 192)), None).await.unwrap();
+
+        let ix = rebalance_position_instructions(
+            &ctx.rpc,
+            position_mint,
+            PriceOrTickIndex::Tick(-640),
+            PriceOrTickIndex::Tick(640),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(ix.instructions.len(), 1);
+        assert_eq!(ix.instructions[0].data[..8], SET_POSITION_RANGE_DISCRIMINATOR);
+        assert!(ix.swap_quote.is_none());
+        assert_eq!(ix.increase_quote.liquidity_delta, 0);
+
+        ctx.send_transaction(ix.instructions).await.unwrap();
+
+        let position = fetch_position(&ctx, position_mint).await.unwrap();
+        assert_eq!(position.tick_lower_index, -640);
+        assert_eq!(position.tick_upper_index, 640);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_rebalance_same_range() {
+        let ctx = RpcContext::new().await;
+        let pool = setup_pool(&ctx).await.unwrap();
+        let position_mint = setup_position(&ctx, pool, Some((-192, 192)), None).await.unwrap();
+
+        let result = rebalance_position_instructions(
+            &ctx.rpc,
+            position_mint,
+            PriceOrTickIndex::Tick(-192),
+            PriceOrTickIndex::Tick(192),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+}
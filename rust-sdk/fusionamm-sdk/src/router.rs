@@ -0,0 +1,734 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::{
+    fetch_fusion_pool_addresses_by_token_pair,
+    pool::{fetch_fusion_pools, is_token_pair},
+    swap::{
+        fetch_mint_infos, fetch_swap_pool, get_transfer_fees, get_two_hop_swap_quote, swap_instruction, tick_arrays_from_slice,
+        two_hop_swap_instruction, SwapPool,
+    },
+    token::{prepare_token_accounts_instructions, TokenAccountStrategy},
+    AccountSource, SwapQuote, SwapType, TwoHopSwapQuote, FUNDER, SLIPPAGE_TOLERANCE_BPS,
+};
+use fusionamm_core::{compute_swap, swap_quote_by_input_token, swap_quote_by_output_token, FusionPoolFacade, TickArraySequence, TransferFee};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use std::{collections::HashMap, error::Error};
+
+/// The default number of equal parts an order is divided into when searching for a split.
+/// Each part is 10% of the order, which keeps the search cheap while still finding useful splits.
+pub const DEFAULT_SPLIT_PARTS: u16 = 10;
+
+/// The default maximum number of routes an order can be split across.
+/// Every route adds a swap instruction, so this keeps a routed swap small enough for a single transaction.
+pub const DEFAULT_MAX_SPLITS: usize = 3;
+
+/// Configures how the swap router searches for routes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapRouterConfig {
+    /// Mints used as intermediate tokens for two-hop routes. Leave empty to only consider direct pools.
+    pub base_mints: Vec<Pubkey>,
+
+    /// The number of equal parts the order is divided into when searching for a split.
+    /// Setting this to `1` disables order splitting.
+    pub split_parts: u16,
+
+    /// The maximum number of routes the order can be split across.
+    /// Every route adds a swap instruction, so keep this small enough to fit in a single transaction.
+    pub max_splits: usize,
+}
+
+impl Default for SwapRouterConfig {
+    fn default() -> Self {
+        Self {
+            base_mints: vec![],
+            split_parts: DEFAULT_SPLIT_PARTS,
+            max_splits: DEFAULT_MAX_SPLITS,
+        }
+    }
+}
+
+/// Represents the pools a portion of a routed swap goes through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwapRoutePath {
+    /// A swap through a single pool of the requested token pair.
+    Direct(Pubkey),
+
+    /// A swap through two pools: the input token to a base mint, then the base mint to the output token.
+    TwoHop(Pubkey, Pubkey),
+}
+
+/// Represents the quote of a single leg of a routed swap.
+#[derive(Debug, Clone)]
+pub enum SwapRouteQuote {
+    /// The quote for a leg executed through a single pool.
+    Direct(SwapQuote),
+
+    /// The quote for a leg executed through two pools.
+    TwoHop(TwoHopSwapQuote),
+}
+
+impl SwapRouteQuote {
+    /// Returns the estimated amount of the input token spent by this leg.
+    pub fn token_est_in(&self) -> u64 {
+        match self {
+            SwapRouteQuote::Direct(SwapQuote::ExactIn(quote)) => quote.token_in,
+            SwapRouteQuote::Direct(SwapQuote::ExactOut(quote)) => quote.token_est_in,
+            SwapRouteQuote::TwoHop(quote) => quote.token_est_in,
+        }
+    }
+
+    /// Returns the estimated amount of the output token received by this leg.
+    pub fn token_est_out(&self) -> u64 {
+        match self {
+            SwapRouteQuote::Direct(SwapQuote::ExactIn(quote)) => quote.token_est_out,
+            SwapRouteQuote::Direct(SwapQuote::ExactOut(quote)) => quote.token_out,
+            SwapRouteQuote::TwoHop(quote) => quote.token_est_out,
+        }
+    }
+
+    /// Returns the minimum output amount for exact input swaps or the maximum input amount for exact output swaps.
+    pub fn other_amount_threshold(&self) -> u64 {
+        match self {
+            SwapRouteQuote::Direct(SwapQuote::ExactIn(quote)) => quote.token_min_out,
+            SwapRouteQuote::Direct(SwapQuote::ExactOut(quote)) => quote.token_max_in,
+            SwapRouteQuote::TwoHop(quote) => quote.other_amount_threshold,
+        }
+    }
+}
+
+/// Represents a portion of a routed swap executed through a single path.
+#[derive(Debug, Clone)]
+pub struct SwapRouteLeg {
+    /// The pools this leg is executed through.
+    pub path: SwapRoutePath,
+
+    /// The specified amount routed through this leg: the input amount for `SwapType::ExactIn`
+    /// or the output amount for `SwapType::ExactOut`.
+    pub amount: u64,
+
+    /// The quote for this leg.
+    pub quote: SwapRouteQuote,
+}
+
+/// Represents a complete route plan for a swap, possibly split across several paths.
+#[derive(Debug, Clone)]
+pub struct SwapRoute {
+    /// The legs of the route. Each leg is executed by its own swap instruction.
+    pub legs: Vec<SwapRouteLeg>,
+
+    /// The estimated total amount of the input token spent, including transfer fees.
+    pub token_est_in: u64,
+
+    /// The estimated total amount of the output token received, after transfer fees.
+    pub token_est_out: u64,
+}
+
+/// Represents the instructions for executing the best route of a swap, along with all evaluated routes.
+#[derive(Debug)]
+pub struct SwapRouteInstructions {
+    /// A vector of Solana `Instruction` objects executing the best route.
+    pub instructions: Vec<Instruction>,
+
+    /// All evaluated routes ranked from best to worst. The instructions execute the first route.
+    pub routes: Vec<SwapRoute>,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+}
+
+struct SwapRouteHop {
+    fusion_pool: FusionPoolFacade,
+    tick_sequence: TickArraySequence,
+    a_to_b: bool,
+}
+
+struct SwapRouteCandidate<'a> {
+    path: SwapRoutePath,
+    pools: Vec<&'a SwapPool>,
+    hops: Vec<SwapRouteHop>,
+}
+
+impl<'a> SwapRouteCandidate<'a> {
+    fn new(path: SwapRoutePath, pools: Vec<&'a SwapPool>, input_mint: Pubkey) -> Result<Self, Box<dyn Error>> {
+        let mut hops = Vec::with_capacity(pools.len());
+        let mut hop_input_mint = input_mint;
+        for pool in pools.iter() {
+            let a_to_b = pool.data.token_mint_a == hop_input_mint;
+            hop_input_mint = if a_to_b { pool.data.token_mint_b } else { pool.data.token_mint_a };
+            hops.push(SwapRouteHop {
+                fusion_pool: pool.data.clone().into(),
+                tick_sequence: TickArraySequence::new(pool.tick_array_facades(), pool.data.tick_spacing)?,
+                a_to_b,
+            });
+        }
+        Ok(Self { path, pools, hops })
+    }
+
+    /// Returns the amount on the other side of the path for the specified amount, ignoring
+    /// transfer fees and slippage. Returns `None` if the path cannot fill the specified amount.
+    fn other_amount(&self, amount: u64, swap_type: &SwapType) -> Option<u64> {
+        if amount == 0 {
+            return Some(0);
+        }
+
+        let specified_input = *swap_type == SwapType::ExactIn;
+        let mut amount = amount;
+
+        let hops: Box<dyn Iterator<Item = &SwapRouteHop>> = if specified_input {
+            Box::new(self.hops.iter())
+        } else {
+            Box::new(self.hops.iter().rev())
+        };
+
+        for hop in hops {
+            let result = compute_swap(amount, 0, hop.fusion_pool, hop.tick_sequence.clone(), hop.a_to_b, specified_input).ok()?;
+            let (token_in, token_out) = if hop.a_to_b {
+                (result.token_a, result.token_b)
+            } else {
+                (result.token_b, result.token_a)
+            };
+            let (filled, other) = if specified_input { (token_in, token_out) } else { (token_out, token_in) };
+            if filled != amount || other == 0 {
+                return None;
+            }
+            amount = other;
+        }
+
+        Some(amount)
+    }
+
+    fn quote(
+        &self,
+        amount: u64,
+        swap_type: &SwapType,
+        slippage_tolerance_bps: u16,
+        input_mint: Pubkey,
+        transfer_fees: &HashMap<Pubkey, Option<TransferFee>>,
+    ) -> Result<SwapRouteQuote, Box<dyn Error>> {
+        match self.pools.as_slice() {
+            [pool] => {
+                let transfer_fee_a = transfer_fees.get(&pool.data.token_mint_a).copied().flatten();
+                let transfer_fee_b = transfer_fees.get(&pool.data.token_mint_b).copied().flatten();
+                let a_to_b = pool.data.token_mint_a == input_mint;
+                let tick_arrays = tick_arrays_from_slice(&pool.tick_array_facades())?;
+                let quote = match swap_type {
+                    SwapType::ExactIn => SwapQuote::ExactIn(swap_quote_by_input_token(
+                        amount,
+                        a_to_b,
+                        slippage_tolerance_bps,
+                        pool.data.clone().into(),
+                        tick_arrays,
+                        transfer_fee_a,
+                        transfer_fee_b,
+                    )?),
+                    SwapType::ExactOut => SwapQuote::ExactOut(swap_quote_by_output_token(
+                        amount,
+                        !a_to_b,
+                        slippage_tolerance_bps,
+                        pool.data.clone().into(),
+                        tick_arrays,
+                        transfer_fee_a,
+                        transfer_fee_b,
+                    )?),
+                };
+                Ok(SwapRouteQuote::Direct(quote))
+            }
+            [pool_one, pool_two] => Ok(SwapRouteQuote::TwoHop(get_two_hop_swap_quote(
+                amount,
+                swap_type,
+                slippage_tolerance_bps,
+                pool_one,
+                pool_two,
+                transfer_fees,
+            )?)),
+            _ => Err("Unsupported route path".into()),
+        }
+    }
+}
+
+/// Returns `true` if `a` is a better other amount than `b` for the given swap type.
+fn is_better(a: u64, b: u64, swap_type: &SwapType) -> bool {
+    match swap_type {
+        SwapType::ExactIn => a > b,
+        SwapType::ExactOut => a < b,
+    }
+}
+
+/// Greedily assigns equal parts of the order to the candidate with the best marginal amount.
+/// Returns the specified amount allocated to each candidate.
+fn split_order(candidates: &[SwapRouteCandidate], amount: u64, swap_type: &SwapType, config: &SwapRouterConfig) -> Option<Vec<u64>> {
+    let parts = config.split_parts.max(1) as u64;
+    let max_splits = config.max_splits.max(1);
+
+    let mut allocations = vec![0u64; candidates.len()];
+    let mut other_amounts = vec![0u64; candidates.len()];
+
+    for part in 0..parts {
+        let part_amount = (amount as u128 * (part + 1) as u128 / parts as u128 - amount as u128 * part as u128 / parts as u128) as u64;
+        if part_amount == 0 {
+            continue;
+        }
+
+        let used_splits = allocations.iter().filter(|&&x| x > 0).count();
+
+        let mut best: Option<(usize, u64, u64)> = None;
+        for (i, candidate) in candidates.iter().enumerate() {
+            if allocations[i] == 0 && used_splits >= max_splits {
+                continue;
+            }
+            let Some(other_amount) = candidate.other_amount(allocations[i] + part_amount, swap_type) else {
+                continue;
+            };
+            let marginal = other_amount.saturating_sub(other_amounts[i]);
+            if best.is_none_or(|(_, best_marginal, _)| is_better(marginal, best_marginal, swap_type)) {
+                best = Some((i, marginal, other_amount));
+            }
+        }
+
+        let (i, _, other_amount) = best?;
+        allocations[i] += part_amount;
+        other_amounts[i] = other_amount;
+    }
+
+    Some(allocations)
+}
+
+fn quote_route(
+    candidates: &[SwapRouteCandidate],
+    allocations: &[u64],
+    swap_type: &SwapType,
+    slippage_tolerance_bps: u16,
+    input_mint: Pubkey,
+    transfer_fees: &HashMap<Pubkey, Option<TransferFee>>,
+) -> Result<SwapRoute, Box<dyn Error>> {
+    let mut legs = Vec::new();
+    for (candidate, &amount) in candidates.iter().zip(allocations) {
+        if amount == 0 {
+            continue;
+        }
+        let quote = candidate.quote(amount, swap_type, slippage_tolerance_bps, input_mint, transfer_fees)?;
+        legs.push(SwapRouteLeg {
+            path: candidate.path,
+            amount,
+            quote,
+        });
+    }
+
+    Ok(SwapRoute {
+        token_est_in: legs.iter().map(|x| x.quote.token_est_in()).sum(),
+        token_est_out: legs.iter().map(|x| x.quote.token_est_out()).sum(),
+        legs,
+    })
+}
+
+#[cfg(not(doctest))]
+/// Finds the addresses of the fusion pools a swap between two token mints can be routed through.
+///
+/// This is the discovery step of `route_swap_instructions`: it returns the pools of the token pair and the pools
+/// between each base mint and the input or output mint. It relies on `getProgramAccounts`, so it needs an RPC client.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `input_mint` - The public key of the token mint being sold.
+/// * `output_mint` - The public key of the token mint being bought.
+/// * `base_mints` - The mints used as intermediate tokens for two-hop routes, e.g. `SwapRouterConfig::base_mints`.
+///
+/// # Returns
+///
+/// A `Result` containing the addresses of the candidate fusion pools.
+///
+/// # Errors
+///
+/// This function will return an error if any RPC request fails.
+pub async fn fetch_route_fusion_pool_addresses(
+    rpc: &RpcClient,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    base_mints: &[Pubkey],
+) -> Result<Vec<Pubkey>, Box<dyn Error>> {
+    let mut fusion_pools = fetch_fusion_pool_addresses_by_token_pair(rpc, input_mint, output_mint).await?;
+    for base_mint in base_mints.iter().filter(|&&x| x != input_mint && x != output_mint) {
+        fusion_pools.extend(fetch_fusion_pool_addresses_by_token_pair(rpc, input_mint, *base_mint).await?);
+        fusion_pools.extend(fetch_fusion_pool_addresses_by_token_pair(rpc, *base_mint, output_mint).await?);
+    }
+    fusion_pools.sort();
+    fusion_pools.dedup();
+    Ok(fusion_pools)
+}
+
+#[cfg(not(doctest))]
+/// Finds the best route for a token swap across FusionAMM pools and generates the instructions to execute it.
+///
+/// Every candidate pool of the token pair is evaluated, as well as two-hop paths through the configured base mints
+/// (for each base mint, the best pair of pools is used). The order is then split across up to
+/// `config.max_splits` paths when that improves execution. Path amounts are computed with `compute_swap`
+/// over a `TickArraySequence` of each pool, and the final legs are quoted with transfer fees and slippage
+/// the same way as `swap_instructions` and `two_hop_swap_instructions`.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to an account source, e.g. the Solana RPC client.
+/// * `fusion_pools` - The addresses of the candidate fusion pools, e.g. from `fetch_route_fusion_pool_addresses`.
+///   Candidates that don't trade any of the routed token pairs are ignored.
+/// * `input_mint` - The public key of the token mint being sold.
+/// * `output_mint` - The public key of the token mint being bought.
+/// * `amount` - The token amount specified for the swap. For `SwapType::ExactIn`, this is the input token amount.
+///   For `SwapType::ExactOut`, this is the output token amount.
+/// * `swap_type` - The type of swap (`SwapType::ExactIn` or `SwapType::ExactOut`).
+/// * `config` - An optional `SwapRouterConfig`. Defaults to direct routes split across up to `DEFAULT_MAX_SPLITS` pools.
+/// * `slippage_tolerance_bps` - An optional slippage tolerance, in basis points (BPS). Defaults to the global setting if not provided.
+/// * `signer` - An optional public key of the wallet or account executing the swap. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `SwapRouteInstructions` on success:
+/// * `instructions` - A vector of `Instruction` objects executing the best route.
+/// * `routes` - All evaluated routes ranked from best to worst.
+/// * `additional_signers` - A vector of `Keypair` objects representing any additional signers required for the instructions.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The signer is invalid or missing.
+/// - No route can fill the requested amount.
+/// - The token mint accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+///
+/// # Example
+///
+/// ```rust
+/// use fusionamm_sdk::{fetch_route_fusion_pool_addresses, route_swap_instructions, SwapRouterConfig, SwapType};
+/// use solana_client::nonblocking::rpc_client::RpcClient;
+/// use solana_pubkey::pubkey;
+/// use solana_keypair::Keypair;
+/// use solana_signer::Signer;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rpc = RpcClient::new("https://api.mainnet.solana.com".to_string());
+///     let wallet = Keypair::new(); // Load your wallet here
+///
+///     let sol_mint = pubkey!("So11111111111111111111111111111111111111112");
+///     let usdc_mint = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+///     let config = SwapRouterConfig::default();
+///
+///     let fusion_pools = fetch_route_fusion_pool_addresses(&rpc, sol_mint, usdc_mint, &config.base_mints)
+///         .await
+///         .unwrap();
+///
+///     let result = route_swap_instructions(
+///         &rpc,
+///         &fusion_pools,
+///         sol_mint,
+///         usdc_mint,
+///         1_000_000_000,
+///         SwapType::ExactIn,
+///         Some(config),
+///         Some(100),
+///         Some(wallet.pubkey()),
+///     )
+///     .await
+///     .unwrap();
+///
+///     println!("Best route estimated token out: {}", result.routes[0].token_est_out);
+///     println!("Number of Instructions: {}", result.instructions.len());
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub async fn route_swap_instructions(
+    rpc: &impl AccountSource,
+    fusion_pools: &[Pubkey],
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount: u64,
+    swap_type: SwapType,
+    config: Option<SwapRouterConfig>,
+    slippage_tolerance_bps: Option<u16>,
+    signer: Option<Pubkey>,
+) -> Result<SwapRouteInstructions, Box<dyn Error>> {
+    let config = config.unwrap_or_default();
+    let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
+    let signer = signer.unwrap_or(*FUNDER.try_lock()?);
+    if signer == Pubkey::default() {
+        return Err("Signer must be provided".into());
+    }
+    if input_mint == output_mint {
+        return Err("Input and output mints must be different".into());
+    }
+    if amount == 0 {
+        return Err("Swap amount must be greater than zero".into());
+    }
+
+    let base_mints: Vec<Pubkey> = config
+        .base_mints
+        .iter()
+        .filter(|&&x| x != input_mint && x != output_mint)
+        .copied()
+        .collect();
+
+    let mut mint_addresses = vec![input_mint, output_mint];
+    mint_addresses.extend(base_mints.iter());
+    let mint_infos = fetch_mint_infos(rpc, &mint_addresses).await?;
+    let current_epoch = rpc.get_epoch().await?;
+    let transfer_fees = get_transfer_fees(&mint_infos, current_epoch);
+
+    let fusion_pools = fetch_fusion_pools(rpc, fusion_pools).await?;
+
+    let mut direct_pools: Vec<SwapPool> = Vec::new();
+    for pool in fusion_pools.iter().filter(|x| is_token_pair(&x.data, input_mint, output_mint)) {
+        direct_pools.push(fetch_swap_pool(rpc, pool.address, pool.data.clone(), input_mint).await?);
+    }
+
+    let mut two_hop_pools: Vec<(Vec<SwapPool>, Vec<SwapPool>)> = Vec::new();
+    for base_mint in base_mints.iter() {
+        let mut pools_one: Vec<SwapPool> = Vec::new();
+        for pool in fusion_pools.iter().filter(|x| is_token_pair(&x.data, input_mint, *base_mint)) {
+            pools_one.push(fetch_swap_pool(rpc, pool.address, pool.data.clone(), input_mint).await?);
+        }
+        let mut pools_two: Vec<SwapPool> = Vec::new();
+        for pool in fusion_pools.iter().filter(|x| is_token_pair(&x.data, *base_mint, output_mint)) {
+            pools_two.push(fetch_swap_pool(rpc, pool.address, pool.data.clone(), *base_mint).await?);
+        }
+        two_hop_pools.push((pools_one, pools_two));
+    }
+
+    let mut candidates: Vec<SwapRouteCandidate> = Vec::new();
+    for pool in direct_pools.iter() {
+        candidates.push(SwapRouteCandidate::new(SwapRoutePath::Direct(pool.address), vec![pool], input_mint)?);
+    }
+
+    // Only the best pool pair of each base mint is considered, so that no pool is shared between
+    // candidates and their amounts can be computed independently.
+    for (pools_one, pools_two) in two_hop_pools.iter() {
+        let mut best: Option<(SwapRouteCandidate, u64)> = None;
+        for pool_one in pools_one.iter() {
+            for pool_two in pools_two.iter() {
+                let path = SwapRoutePath::TwoHop(pool_one.address, pool_two.address);
+                let candidate = SwapRouteCandidate::new(path, vec![pool_one, pool_two], input_mint)?;
+                let Some(other_amount) = candidate.other_amount(amount, &swap_type) else {
+                    continue;
+                };
+                if best
+                    .as_ref()
+                    .is_none_or(|(_, best_amount)| is_better(other_amount, *best_amount, &swap_type))
+                {
+                    best = Some((candidate, other_amount));
+                }
+            }
+        }
+        if let Some((candidate, _)) = best {
+            candidates.push(candidate);
+        }
+    }
+
+    let mut routes: Vec<SwapRoute> = Vec::new();
+
+    for (i, candidate) in candidates.iter().enumerate() {
+        if candidate.other_amount(amount, &swap_type).is_none() {
+            continue;
+        }
+        let mut allocations = vec![0u64; candidates.len()];
+        allocations[i] = amount;
+        if let Ok(route) = quote_route(&candidates, &allocations, &swap_type, slippage_tolerance_bps, input_mint, &transfer_fees) {
+            routes.push(route);
+        }
+    }
+
+    if let Some(allocations) = split_order(&candidates, amount, &swap_type, &config) {
+        if allocations.iter().filter(|&&x| x > 0).count() > 1 {
+            if let Ok(route) = quote_route(&candidates, &allocations, &swap_type, slippage_tolerance_bps, input_mint, &transfer_fees) {
+                routes.push(route);
+            }
+        }
+    }
+
+    routes.sort_by(|a, b| match swap_type {
+        SwapType::ExactIn => b.token_est_out.cmp(&a.token_est_out),
+        SwapType::ExactOut => a.token_est_in.cmp(&b.token_est_in),
+    });
+
+    let best_route = routes.first().ok_or("No route found for the given mints and amount")?;
+
+    let specified_input = swap_type == SwapType::ExactIn;
+    let max_in_amount: u64 = match swap_type {
+        SwapType::ExactIn => best_route.token_est_in,
+        SwapType::ExactOut => best_route.legs.iter().map(|x| x.quote.other_amount_threshold()).sum(),
+    };
+
+    let mut instructions: Vec<Instruction> = Vec::new();
+
+    let token_accounts = prepare_token_accounts_instructions(
+        rpc,
+        signer,
+        vec![
+            TokenAccountStrategy::WithBalance(input_mint, max_in_amount),
+            TokenAccountStrategy::WithoutBalance(output_mint),
+        ],
+    )
+    .await?;
+
+    instructions.extend(token_accounts.create_instructions);
+
+    for leg in best_route.legs.iter() {
+        let candidate = candidates.iter().find(|x| x.path == leg.path).ok_or("Route leg pool not found")?;
+        let other_amount_threshold = leg.quote.other_amount_threshold();
+        let instruction = match (candidate.pools.as_slice(), &leg.quote) {
+            ([pool], SwapRouteQuote::Direct(quote)) => swap_instruction(
+                pool,
+                quote,
+                &mint_infos,
+                &token_accounts.token_account_addresses,
+                signer,
+                leg.amount,
+                other_amount_threshold,
+                specified_input,
+            )?,
+            ([pool_one, pool_two], SwapRouteQuote::TwoHop(quote)) => two_hop_swap_instruction(
+                pool_one,
+                pool_two,
+                quote,
+                &mint_infos,
+                &token_accounts.token_account_addresses,
+                signer,
+                leg.amount,
+                other_amount_threshold,
+                specified_input,
+            )?,
+            _ => return Err("Unsupported route path".into()),
+        };
+        instructions.push(instruction);
+    }
+
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(SwapRouteInstructions {
+        instructions,
+        routes,
+        additional_signers: token_accounts.additional_signers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        increase_liquidity_instructions,
+        tests::{setup_ata_with_amount, setup_fusion_pool, setup_mint_with_decimals, setup_position, RpcContext},
+        IncreaseLiquidityParam,
+    };
+    use rstest::rstest;
+    use serial_test::serial;
+    use solana_program::program_pack::Pack;
+    use solana_program_test::tokio;
+    use solana_signer::Signer;
+    use spl_token::state::Account as TokenAccount;
+
+    async fn get_token_balance(rpc: &impl AccountSource, address: Pubkey) -> Result<u64, Box<dyn Error>> {
+        let account_data = rpc.get_account(&address).await?;
+        Ok(TokenAccount::unpack(&account_data.data)?.amount)
+    }
+
+    async fn setup_pool_with_liquidity(
+        ctx: &RpcContext,
+        mint_1: Pubkey,
+        mint_2: Pubkey,
+        tick_spacing: u16,
+        liquidity: u128,
+    ) -> Result<Pubkey, Box<dyn Error>> {
+        let [mint_a, mint_b] = crate::order_mints(mint_1, mint_2);
+        let pool_pubkey = setup_fusion_pool(ctx, mint_a, mint_b, tick_spacing, 300).await?;
+        let position_mint = setup_position(ctx, pool_pubkey, Some((-1024, 1024)), None).await?;
+
+        let liq_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            position_mint,
+            IncreaseLiquidityParam::Liquidity(liquidity),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await?;
+        ctx.send_transaction_with_signers(liq_ix.instructions, liq_ix.additional_signers.iter().collect())
+            .await?;
+
+        Ok(pool_pubkey)
+    }
+
+    #[rstest]
+    #[case(SwapType::ExactIn, 50_000)]
+    #[case(SwapType::ExactOut, 50_000)]
+    #[serial]
+    fn test_route_swap_instructions(#[case] swap_type: SwapType, #[case] amount: u64) {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let ctx = RpcContext::new().await;
+
+            let mint_in = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+            let mint_out = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+            let mint_base = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+            let ata_in = setup_ata_with_amount(&ctx, mint_in, 1_000_000_000).await.unwrap();
+            let ata_out = setup_ata_with_amount(&ctx, mint_out, 1_000_000_000).await.unwrap();
+            setup_ata_with_amount(&ctx, mint_base, 1_000_000_000).await.unwrap();
+
+            let fusion_pools = vec![
+                setup_pool_with_liquidity(&ctx, mint_in, mint_out, 64, 10_000_000).await.unwrap(),
+                setup_pool_with_liquidity(&ctx, mint_in, mint_out, 128, 10_000_000).await.unwrap(),
+                setup_pool_with_liquidity(&ctx, mint_in, mint_base, 64, 100_000_000).await.unwrap(),
+                setup_pool_with_liquidity(&ctx, mint_base, mint_out, 64, 100_000_000).await.unwrap(),
+            ];
+
+            let config = SwapRouterConfig {
+                base_mints: vec![mint_base],
+                ..SwapRouterConfig::default()
+            };
+
+            let result = route_swap_instructions(
+                &ctx.rpc,
+                &fusion_pools,
+                mint_in,
+                mint_out,
+                amount,
+                swap_type.clone(),
+                Some(config.clone()),
+                Some(100),
+                Some(ctx.signer.pubkey()),
+            )
+            .await
+            .unwrap();
+
+            // Two direct pools and one two-hop path, plus a split route if splitting improves execution.
+            assert!(result.routes.len() >= 3);
+            for pair in result.routes.windows(2) {
+                match swap_type {
+                    SwapType::ExactIn => assert!(pair[0].token_est_out >= pair[1].token_est_out),
+                    SwapType::ExactOut => assert!(pair[0].token_est_in <= pair[1].token_est_in),
+                }
+            }
+
+            let best_route = result.routes[0].clone();
+            assert!(!best_route.legs.is_empty() && best_route.legs.len() <= config.max_splits);
+            assert_eq!(best_route.legs.iter().map(|x| x.amount).sum::<u64>(), amount);
+
+            let before_in = get_token_balance(&ctx.rpc, ata_in).await.unwrap();
+            let before_out = get_token_balance(&ctx.rpc, ata_out).await.unwrap();
+
+            ctx.send_transaction_with_signers(result.instructions, result.additional_signers.iter().collect())
+                .await
+                .unwrap();
+
+            let after_in = get_token_balance(&ctx.rpc, ata_in).await.unwrap();
+            let after_out = get_token_balance(&ctx.rpc, ata_out).await.unwrap();
+
+            assert_eq!(before_in - after_in, best_route.token_est_in);
+            assert_eq!(after_out - before_out, best_route.token_est_out);
+        });
+    }
+}
@@ -9,21 +9,26 @@
 //
 
 use crate::{
+    pool::{fetch_fusion_pools, is_token_pair},
     token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy},
-    FUNDER, SLIPPAGE_TOLERANCE_BPS,
+    AccountSource, FUNDER, SLIPPAGE_TOLERANCE_BPS,
 };
 use fusionamm_client::{
     get_tick_array_address, AccountsType, FusionPool, RemainingAccountsInfo, RemainingAccountsSlice, Swap, SwapInstructionArgs, TickArray,
+    TwoHopSwap, TwoHopSwapInstructionArgs,
 };
 use fusionamm_core::{
-    get_tick_array_start_tick_index, swap_quote_by_input_token, swap_quote_by_output_token, ExactInSwapQuote, ExactOutSwapQuote, TickArrayFacade,
-    TickFacade, TICK_ARRAY_SIZE,
+    get_tick_array_start_tick_index, price_to_sqrt_price, swap_quote_by_input_token, swap_quote_by_input_token_with_sqrt_price_limit,
+    swap_quote_by_output_token, swap_quote_by_output_token_with_sqrt_price_limit, tick_index_to_sqrt_price, ExactInSwapQuote, ExactOutSwapQuote,
+    TickArrayFacade, TickArrays, TickFacade, TransferFee, INVALID_TICK_ARRAY_SEQUENCE, MAX_TICK_INDEX, MIN_TICK_INDEX, TICK_ARRAY_SIZE,
 };
-use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_account::Account as SolanaAccount;
 use solana_instruction::{AccountMeta, Instruction};
 use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
-use std::{error::Error, iter::zip};
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Mint;
+use std::{collections::HashMap, error::Error, iter::zip};
 // TODO: transfer hooks
 
 /// Represents the type of a swap operation.
@@ -50,6 +55,32 @@ pub enum SwapQuote {
     ExactOut(ExactOutSwapQuote),
 }
 
+impl SwapQuote {
+    /// Returns the deviation of the average execution price, including fees, from the spot price in basis points.
+    pub fn price_impact_bps(&self) -> u32 {
+        match self {
+            SwapQuote::ExactIn(quote) => quote.price_impact_bps,
+            SwapQuote::ExactOut(quote) => quote.price_impact_bps,
+        }
+    }
+
+    /// Returns the start tick indexes of the tick arrays touched by the swap, in the swap direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick_spacing` - The tick spacing of the pool the quote was computed for.
+    pub fn tick_array_start_indexes(&self, tick_spacing: u16) -> Vec<i32> {
+        let (first, last) = match self {
+            SwapQuote::ExactIn(quote) => (quote.first_tick_array_start_index, quote.last_tick_array_start_index),
+            SwapQuote::ExactOut(quote) => (quote.first_tick_array_start_index, quote.last_tick_array_start_index),
+        };
+        let offset = tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+        let count = (last - first).abs() / offset + 1;
+        let step = if last < first { -offset } else { offset };
+        (0..count).map(|i| first + i * step).collect()
+    }
+}
+
 /// Represents the instructions and quote for executing a token swap.
 ///
 /// This struct contains the instructions required to perform the swap, along with the computed
@@ -66,6 +97,51 @@ pub struct SwapInstructions {
     pub additional_signers: Vec<Keypair>,
 }
 
+/// Represents the price at which a swap stops filling.
+///
+/// Prices are expressed as the price of token A in terms of token B of the pool.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SwapPriceLimit {
+    /// The price limit represented as a square root in Q64.64 format.
+    SqrtPrice(u128),
+
+    /// The price limit as a decimal price, adjusted for the token decimals.
+    Price(f64),
+
+    /// The price limit as a tick index.
+    TickIndex(i32),
+}
+
+/// Represents the instructions and quote for executing a token swap bounded by a price limit.
+#[derive(Debug)]
+pub struct SwapWithPriceLimitInstructions {
+    /// A vector of Solana `Instruction` objects required to execute the swap.
+    pub instructions: Vec<Instruction>,
+
+    /// A `SwapQuote` representing the details of the swap up to the price limit.
+    pub quote: SwapQuote,
+
+    /// The price limit set on the swap instruction, represented as a square root.
+    pub sqrt_price_limit: u128,
+
+    /// The token amount requested for the swap.
+    pub requested_amount: u64,
+
+    /// The part of the requested amount filled before the price limit is reached. For `SwapType::ExactIn`,
+    /// this is the input token amount; for `SwapType::ExactOut`, this is the output token amount.
+    pub filled_amount: u64,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+}
+
+impl SwapWithPriceLimitInstructions {
+    /// Returns `true` if the price limit is reached before the requested amount is filled.
+    pub fn is_partial_fill(&self) -> bool {
+        self.filled_amount < self.requested_amount
+    }
+}
+
 fn uninitialized_tick_array(start_tick_index: i32) -> TickArrayFacade {
     TickArrayFacade {
         start_tick_index,
@@ -73,22 +149,11 @@ fn uninitialized_tick_array(start_tick_index: i32) -> TickArrayFacade {
     }
 }
 
-async fn fetch_tick_arrays_or_default(
-    rpc: &RpcClient,
+async fn fetch_tick_arrays_by_start_index(
+    rpc: &impl AccountSource,
     fusion_pool_address: Pubkey,
-    fusion_pool: &FusionPool,
-) -> Result<[(Pubkey, TickArrayFacade); 5], Box<dyn Error>> {
-    let tick_array_start_index = get_tick_array_start_tick_index(fusion_pool.tick_current_index, fusion_pool.tick_spacing);
-    let offset = fusion_pool.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
-
-    let tick_array_indexes = [
-        tick_array_start_index,
-        tick_array_start_index + offset,
-        tick_array_start_index + offset * 2,
-        tick_array_start_index - offset,
-        tick_array_start_index - offset * 2,
-    ];
-
+    tick_array_indexes: &[i32],
+) -> Result<Vec<(Pubkey, TickArrayFacade)>, Box<dyn Error>> {
     let tick_array_addresses: Vec<Pubkey> = tick_array_indexes
         .iter()
         .map(|&x| get_tick_array_address(&fusion_pool_address, x).map(|y| y.0))
@@ -108,12 +173,7 @@ async fn fetch_tick_arrays_or_default(
         .map(|(i, x)| x.unwrap_or(uninitialized_tick_array(tick_array_indexes[i])))
         .collect::<Vec<TickArrayFacade>>();
 
-    let result: [(Pubkey, TickArrayFacade); 5] = zip(tick_array_addresses, tick_arrays)
-        .collect::<Vec<(Pubkey, TickArrayFacade)>>()
-        .try_into()
-        .map_err(|_| "Failed to convert tick arrays to array".to_string())?;
-
-    Ok(result)
+    Ok(zip(tick_array_addresses, tick_arrays).collect())
 }
 
 #[cfg(not(doctest))]
@@ -183,7 +243,7 @@ async fn fetch_tick_arrays_or_default(
 /// }
 /// ```
 pub async fn swap_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     fusion_pool_address: Pubkey,
     amount: u64,
     specified_mint: Pubkey,
@@ -191,6 +251,212 @@ pub async fn swap_instructions(
     slippage_tolerance_bps: Option<u16>,
     signer: Option<Pubkey>,
 ) -> Result<SwapInstructions, Box<dyn Error>> {
+    let (swap_instructions, _) =
+        internal_swap_instructions(rpc, fusion_pool_address, amount, specified_mint, swap_type, None, slippage_tolerance_bps, signer).await?;
+    Ok(swap_instructions)
+}
+
+#[cfg(not(doctest))]
+/// Generates the instructions necessary to execute a token swap that stops at a price limit.
+///
+/// The swap is filled until either the specified amount is reached or the pool price reaches the limit.
+/// The same limit is set on the swap instruction, so the on-chain execution stops at the quoted price.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client for fetching accounts and interacting with the blockchain.
+/// * `fusion_pool_address` - The public key of the FusionPool against which the swap will be executed.
+/// * `amount` - The token amount specified for the swap. For `SwapType::ExactIn`, this is the input token amount.
+///   For `SwapType::ExactOut`, this is the output token amount.
+/// * `specified_mint` - The public key of the token mint being swapped.
+/// * `swap_type` - The type of swap (`SwapType::ExactIn` or `SwapType::ExactOut`).
+/// * `price_limit` - The price at which the swap stops, as a sqrt price, a price or a tick index.
+/// * `slippage_tolerance_bps` - An optional slippage tolerance, in basis points (BPS). Defaults to the global setting if not provided.
+/// * `signer` - An optional public key of the wallet or account executing the swap. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `SwapWithPriceLimitInstructions` on success:
+/// * `instructions` - A vector of `Instruction` objects required to execute the swap.
+/// * `quote` - A `SwapQuote` providing the computed details of the swap up to the price limit.
+/// * `sqrt_price_limit` - The price limit set on the swap instruction.
+/// * `requested_amount` - The token amount requested for the swap.
+/// * `filled_amount` - The part of the requested amount filled before the price limit is reached.
+/// * `additional_signers` - A vector of `Keypair` objects representing any additional signers required for the instructions.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The signer is invalid or missing.
+/// - The price limit is out of bounds or on the wrong side of the current pool price.
+/// - The FusionPool or token mint accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+///
+/// # Example
+///
+/// ```rust
+/// use fusionamm_sdk::{swap_with_price_limit_instructions, SwapPriceLimit, SwapType};
+/// use solana_client::nonblocking::rpc_client::RpcClient;
+/// use solana_pubkey::pubkey;
+/// use solana_keypair::Keypair;
+/// use solana_signer::Signer;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rpc = RpcClient::new("https://api.mainnet.solana.com".to_string());
+///     let wallet = Keypair::new(); // Load your wallet here
+///
+///     let fusion_pool_address = pubkey!("7VuKeevbvbQQcxz6N4SNLmuq6PYy4AcGQRDssoqo4t65");
+///     let mint_address = pubkey!("So11111111111111111111111111111111111111112");
+///     let input_amount = 1_000_000;
+///
+///     let result = swap_with_price_limit_instructions(
+///         &rpc,
+///         fusion_pool_address,
+///         input_amount,
+///         mint_address,
+///         SwapType::ExactIn,
+///         SwapPriceLimit::Price(150.0),
+///         Some(100),
+///         Some(wallet.pubkey()),
+///     )
+///     .await
+///     .unwrap();
+///
+///     println!("Filled {} of {}", result.filled_amount, result.requested_amount);
+///     println!("Number of Instructions: {}", result.instructions.len());
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub async fn swap_with_price_limit_instructions(
+    rpc: &impl AccountSource,
+    fusion_pool_address: Pubkey,
+    amount: u64,
+    specified_mint: Pubkey,
+    swap_type: SwapType,
+    price_limit: SwapPriceLimit,
+    slippage_tolerance_bps: Option<u16>,
+    signer: Option<Pubkey>,
+) -> Result<SwapWithPriceLimitInstructions, Box<dyn Error>> {
+    let (swap_instructions, sqrt_price_limit) =
+        internal_swap_instructions(rpc, fusion_pool_address, amount, specified_mint, swap_type, Some(price_limit), slippage_tolerance_bps, signer)
+            .await?;
+
+    let filled_amount = match swap_instructions.quote {
+        SwapQuote::ExactIn(quote) => quote.token_in,
+        SwapQuote::ExactOut(quote) => quote.token_out,
+    };
+
+    Ok(SwapWithPriceLimitInstructions {
+        instructions: swap_instructions.instructions,
+        quote: swap_instructions.quote,
+        sqrt_price_limit,
+        requested_amount: amount,
+        filled_amount,
+        additional_signers: swap_instructions.additional_signers,
+    })
+}
+
+// A swap instruction takes three tick array accounts and up to three supplemental tick arrays.
+const MAX_SWAP_TICK_ARRAYS: usize = 6;
+
+// Returns the start indexes of the tick arrays a single swap can traverse in the swap direction,
+// starting with the tick array containing the current tick.
+fn get_tick_array_start_indexes_in_swap_direction(fusion_pool: &FusionPool, a_to_b: bool) -> Vec<i32> {
+    let tick_array_start_index = get_tick_array_start_tick_index(fusion_pool.tick_current_index, fusion_pool.tick_spacing);
+    let offset = fusion_pool.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    let step = if a_to_b { -offset } else { offset };
+
+    (0..MAX_SWAP_TICK_ARRAYS as i32)
+        .map(|i| tick_array_start_index + step * i)
+        .take_while(|&x| x <= MAX_TICK_INDEX && x + offset > MIN_TICK_INDEX)
+        .collect()
+}
+
+pub(crate) async fn fetch_tick_arrays_in_swap_direction(
+    rpc: &impl AccountSource,
+    fusion_pool_address: Pubkey,
+    fusion_pool: &FusionPool,
+    a_to_b: bool,
+) -> Result<Vec<(Pubkey, TickArrayFacade)>, Box<dyn Error>> {
+    let tick_array_indexes = get_tick_array_start_indexes_in_swap_direction(fusion_pool, a_to_b);
+    fetch_tick_arrays_by_start_index(rpc, fusion_pool_address, &tick_array_indexes).await
+}
+
+pub(crate) fn tick_arrays_from_slice(tick_arrays: &[TickArrayFacade]) -> Result<TickArrays, Box<dyn Error>> {
+    match tick_arrays {
+        [a] => Ok([*a].into()),
+        [a, b] => Ok([*a, *b].into()),
+        [a, b, c] => Ok([*a, *b, *c].into()),
+        [a, b, c, d] => Ok([*a, *b, *c, *d].into()),
+        [a, b, c, d, e] => Ok([*a, *b, *c, *d, *e].into()),
+        [a, b, c, d, e, f] => Ok([*a, *b, *c, *d, *e, *f].into()),
+        _ => Err(format!("Unsupported number of tick arrays: {}", tick_arrays.len()).into()),
+    }
+}
+
+// Selects the tick arrays crossed by the quoted swap plus one more in the swap direction (if available),
+// so that a swap ending exactly on a tick array boundary can still move into the next array.
+// At least three tick arrays are selected to fill the tick array accounts of the swap instruction.
+pub(crate) fn get_swap_tick_array_addresses(
+    fusion_pool_address: Pubkey,
+    fusion_pool: &FusionPool,
+    a_to_b: bool,
+    quote: &SwapQuote,
+) -> Result<Vec<Pubkey>, Box<dyn Error>> {
+    let tick_array_indexes = get_tick_array_start_indexes_in_swap_direction(fusion_pool, a_to_b);
+    let crossed = quote.tick_array_start_indexes(fusion_pool.tick_spacing).len();
+    if crossed > tick_array_indexes.len() {
+        return Err("Swap quote crosses more tick arrays than a single swap can traverse".into());
+    }
+    let count = (crossed + 1).max(3).min(tick_array_indexes.len());
+    let tick_array_addresses = tick_array_indexes[..count]
+        .iter()
+        .map(|&x| get_tick_array_address(&fusion_pool_address, x).map(|y| y.0))
+        .collect::<Result<Vec<Pubkey>, _>>()?;
+    Ok(tick_array_addresses)
+}
+
+pub(crate) struct SwapTickArrayAccounts {
+    pub tick_arrays: [Pubkey; 3],
+    pub remaining_accounts_info: Option<RemainingAccountsInfo>,
+    pub supplemental_tick_arrays: Vec<AccountMeta>,
+}
+
+// Splits the tick arrays of a swap into the three tick array accounts and the supplemental tick arrays.
+// Fewer than three tick arrays only exist next to the price bounds, where the last one fills the remaining accounts.
+pub(crate) fn get_swap_tick_array_accounts(tick_array_addresses: &[Pubkey]) -> SwapTickArrayAccounts {
+    let tick_array_account = |i: usize| tick_array_addresses[i.min(tick_array_addresses.len() - 1)];
+    let supplemental_tick_arrays: Vec<AccountMeta> = tick_array_addresses.iter().skip(3).map(|&x| AccountMeta::new(x, false)).collect();
+    let remaining_accounts_info = if supplemental_tick_arrays.is_empty() {
+        None
+    } else {
+        Some(RemainingAccountsInfo {
+            slices: vec![RemainingAccountsSlice {
+                accounts_type: AccountsType::SupplementalTickArrays,
+                length: supplemental_tick_arrays.len() as u8,
+            }],
+        })
+    };
+
+    SwapTickArrayAccounts {
+        tick_arrays: [tick_array_account(0), tick_array_account(1), tick_array_account(2)],
+        remaining_accounts_info,
+        supplemental_tick_arrays,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn internal_swap_instructions(
+    rpc: &impl AccountSource,
+    fusion_pool_address: Pubkey,
+    amount: u64,
+    specified_mint: Pubkey,
+    swap_type: SwapType,
+    price_limit: Option<SwapPriceLimit>,
+    slippage_tolerance_bps: Option<u16>,
+    signer: Option<Pubkey>,
+) -> Result<(SwapInstructions, u128), Box<dyn Error>> {
     let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
     let signer = signer.unwrap_or(*FUNDER.try_lock()?);
     if signer == Pubkey::default() {
@@ -203,39 +469,77 @@ pub async fn swap_instructions(
     let specified_token_a = specified_mint == fusion_pool.token_mint_a;
     let a_to_b = specified_token_a == specified_input;
 
-    let tick_arrays = fetch_tick_arrays_or_default(rpc, fusion_pool_address, &fusion_pool).await?;
-
     let mint_infos = rpc.get_multiple_accounts(&[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
 
     let mint_a_info = mint_infos[0].as_ref().ok_or(format!("Mint a not found: {}", fusion_pool.token_mint_a))?;
 
     let mint_b_info = mint_infos[1].as_ref().ok_or(format!("Mint b not found: {}", fusion_pool.token_mint_b))?;
 
-    let current_epoch = rpc.get_epoch_info().await?.epoch;
+    let current_epoch = rpc.get_epoch().await?;
     let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
     let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
 
-    let quote = match swap_type {
-        SwapType::ExactIn => SwapQuote::ExactIn(swap_quote_by_input_token(
-            amount,
-            specified_token_a,
-            slippage_tolerance_bps,
-            fusion_pool.clone().into(),
-            tick_arrays.map(|x| x.1).into(),
-            transfer_fee_a,
-            transfer_fee_b,
-        )?),
-        SwapType::ExactOut => SwapQuote::ExactOut(swap_quote_by_output_token(
-            amount,
-            specified_token_a,
-            slippage_tolerance_bps,
-            fusion_pool.clone().into(),
-            tick_arrays.map(|x| x.1).into(),
-            transfer_fee_a,
-            transfer_fee_b,
-        )?),
+    let sqrt_price_limit = match price_limit {
+        None => 0,
+        Some(SwapPriceLimit::SqrtPrice(sqrt_price)) => sqrt_price,
+        Some(SwapPriceLimit::TickIndex(tick_index)) => tick_index_to_sqrt_price(tick_index),
+        Some(SwapPriceLimit::Price(price)) => {
+            let mint_a = StateWithExtensions::<Mint>::unpack(&mint_a_info.data)?;
+            let mint_b = StateWithExtensions::<Mint>::unpack(&mint_b_info.data)?;
+            price_to_sqrt_price(price, mint_a.base.decimals, mint_b.base.decimals)
+        }
     };
 
+    // The tick arrays are fetched one at a time in the swap direction until the quote no longer runs past them,
+    // so that only the tick arrays crossed by the swap are fetched.
+    let tick_array_indexes = get_tick_array_start_indexes_in_swap_direction(&fusion_pool, a_to_b);
+    let mut tick_arrays: Vec<TickArrayFacade> = Vec::new();
+    let quote = loop {
+        let next_tick_array_index = tick_array_indexes[tick_arrays.len()];
+        let next_tick_arrays = fetch_tick_arrays_by_start_index(rpc, fusion_pool_address, &[next_tick_array_index]).await?;
+        tick_arrays.extend(next_tick_arrays.iter().map(|x| x.1));
+
+        let quote = match swap_type {
+            SwapType::ExactIn => swap_quote_by_input_token_with_sqrt_price_limit(
+                amount,
+                specified_token_a,
+                slippage_tolerance_bps,
+                sqrt_price_limit,
+                fusion_pool.clone().into(),
+                tick_arrays_from_slice(&tick_arrays)?,
+                transfer_fee_a,
+                transfer_fee_b,
+            )
+            .map(SwapQuote::ExactIn),
+            SwapType::ExactOut => swap_quote_by_output_token_with_sqrt_price_limit(
+                amount,
+                specified_token_a,
+                slippage_tolerance_bps,
+                sqrt_price_limit,
+                fusion_pool.clone().into(),
+                tick_arrays_from_slice(&tick_arrays)?,
+                transfer_fee_a,
+                transfer_fee_b,
+            )
+            .map(SwapQuote::ExactOut),
+        };
+
+        match quote {
+            Err(INVALID_TICK_ARRAY_SEQUENCE) if tick_arrays.len() < tick_array_indexes.len() => continue,
+            Err(INVALID_TICK_ARRAY_SEQUENCE) => {
+                return Err(format!(
+                    "Insufficient liquidity: the swap would run past the {} tick arrays a single swap can traverse",
+                    tick_arrays.len()
+                )
+                .into())
+            }
+            result => break result?,
+        }
+    };
+
+    let tick_array_addresses = get_swap_tick_array_addresses(fusion_pool_address, &fusion_pool, a_to_b, &quote)?;
+    let tick_array_accounts = get_swap_tick_array_accounts(&tick_array_addresses);
+
     let max_in_amount = match quote {
         SwapQuote::ExactIn(quote) => quote.token_in,
         SwapQuote::ExactOut(quote) => quote.token_max_in,
@@ -283,37 +587,660 @@ pub async fn swap_instructions(
         token_vault_a: fusion_pool.token_vault_a,
         token_owner_account_b: *token_owner_account_b,
         token_vault_b: fusion_pool.token_vault_b,
-        tick_array0: tick_arrays[0].0,
-        tick_array1: tick_arrays[1].0,
-        tick_array2: tick_arrays[2].0,
+        tick_array0: tick_array_accounts.tick_arrays[0],
+        tick_array1: tick_array_accounts.tick_arrays[1],
+        tick_array2: tick_array_accounts.tick_arrays[2],
     }
     .instruction_with_remaining_accounts(
         SwapInstructionArgs {
             amount,
             other_amount_threshold,
-            sqrt_price_limit: 0,
+            sqrt_price_limit,
             amount_specified_is_input: specified_input,
             a_to_b,
-            remaining_accounts_info: Some(RemainingAccountsInfo {
-                slices: vec![RemainingAccountsSlice {
-                    accounts_type: AccountsType::SupplementalTickArrays,
-                    length: 2,
-                }],
-            }),
+            remaining_accounts_info: tick_array_accounts.remaining_accounts_info,
         },
-        &[AccountMeta::new(tick_arrays[3].0, false), AccountMeta::new(tick_arrays[4].0, false)],
+        &tick_array_accounts.supplemental_tick_arrays,
     );
 
     instructions.push(swap_instruction);
     instructions.extend(token_accounts.cleanup_instructions);
 
-    Ok(SwapInstructions {
+    Ok((
+        SwapInstructions {
+            instructions,
+            quote,
+            additional_signers: token_accounts.additional_signers,
+        },
+        sqrt_price_limit,
+    ))
+}
+
+/// Represents the combined quote for a two-hop swap operation.
+///
+/// The first hop swaps the input token into the intermediate token, and the second hop swaps the
+/// intermediate token into the output token. The intermediate token is moved directly between the
+/// pool vaults, so its transfer fee (if any) is charged exactly once, between the two hops.
+#[derive(Debug, Clone)]
+pub struct TwoHopSwapQuote {
+    /// The quote for the first hop (input token to intermediate token).
+    pub quote_one: SwapQuote,
+
+    /// The quote for the second hop (intermediate token to output token).
+    pub quote_two: SwapQuote,
+
+    /// The estimated amount of the input token taken from the signer, including transfer fees.
+    pub token_est_in: u64,
+
+    /// The estimated amount of the intermediate token received by the second pool.
+    pub token_intermediate: u64,
+
+    /// The estimated amount of the output token received by the signer, after transfer fees.
+    pub token_est_out: u64,
+
+    /// The slippage bound passed to the instruction: the minimum output amount for exact input swaps
+    /// or the maximum input amount for exact output swaps.
+    pub other_amount_threshold: u64,
+}
+
+/// Represents the instructions and quote for executing a two-hop token swap.
+#[derive(Debug)]
+pub struct TwoHopSwapInstructions {
+    /// A vector of Solana `Instruction` objects required to execute the swap.
+    pub instructions: Vec<Instruction>,
+
+    /// A `TwoHopSwapQuote` representing the details of both hops.
+    pub quote: TwoHopSwapQuote,
+
+    /// The address of the pool used for the first hop.
+    pub fusion_pool_one: Pubkey,
+
+    /// The address of the pool used for the second hop.
+    pub fusion_pool_two: Pubkey,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+}
+
+pub(crate) struct SwapPool {
+    pub(crate) address: Pubkey,
+    pub(crate) data: FusionPool,
+    pub(crate) a_to_b: bool,
+    // The tick arrays in the swap direction, starting with the one containing the current tick.
+    pub(crate) tick_arrays: Vec<(Pubkey, TickArrayFacade)>,
+}
+
+impl SwapPool {
+    pub(crate) fn tick_array_facades(&self) -> Vec<TickArrayFacade> {
+        self.tick_arrays.iter().map(|x| x.1).collect()
+    }
+
+    fn tick_array_accounts(&self, quote: &SwapQuote) -> Result<SwapTickArrayAccounts, Box<dyn Error>> {
+        let tick_array_addresses = get_swap_tick_array_addresses(self.address, &self.data, self.a_to_b, quote)?;
+        Ok(get_swap_tick_array_accounts(&tick_array_addresses))
+    }
+}
+
+pub(crate) struct TwoHopMints {
+    pub(crate) input: Pubkey,
+    pub(crate) intermediate: Pubkey,
+    pub(crate) output: Pubkey,
+}
+
+pub(crate) fn get_two_hop_mints(fusion_pool_one: &FusionPool, fusion_pool_two: &FusionPool) -> Result<TwoHopMints, Box<dyn Error>> {
+    let mints_one = [fusion_pool_one.token_mint_a, fusion_pool_one.token_mint_b];
+    let mints_two = [fusion_pool_two.token_mint_a, fusion_pool_two.token_mint_b];
+    let shared: Vec<Pubkey> = mints_one.iter().filter(|x| mints_two.contains(x)).copied().collect();
+    if shared.len() != 1 {
+        return Err("Two-hop pools must share exactly one token mint".into());
+    }
+
+    let intermediate = shared[0];
+    let input = if mints_one[0] == intermediate { mints_one[1] } else { mints_one[0] };
+    let output = if mints_two[0] == intermediate { mints_two[1] } else { mints_two[0] };
+
+    Ok(TwoHopMints { input, intermediate, output })
+}
+
+pub(crate) fn get_two_hop_swap_quote(
+    amount: u64,
+    swap_type: &SwapType,
+    slippage_tolerance_bps: u16,
+    pool_one: &SwapPool,
+    pool_two: &SwapPool,
+    transfer_fees: &HashMap<Pubkey, Option<TransferFee>>,
+) -> Result<TwoHopSwapQuote, Box<dyn Error>> {
+    let mints = get_two_hop_mints(&pool_one.data, &pool_two.data)?;
+    let a_to_b_one = pool_one.data.token_mint_a == mints.input;
+    let a_to_b_two = pool_two.data.token_mint_a == mints.intermediate;
+
+    let transfer_fee_input = transfer_fees.get(&mints.input).copied().flatten();
+    let transfer_fee_intermediate = transfer_fees.get(&mints.intermediate).copied().flatten();
+    let transfer_fee_output = transfer_fees.get(&mints.output).copied().flatten();
+
+    // The intermediate transfer fee is charged when the tokens leave the first pool,
+    // so the second pool receives the intermediate amount as is.
+    let (transfer_fee_one_a, transfer_fee_one_b) = if a_to_b_one {
+        (transfer_fee_input, transfer_fee_intermediate)
+    } else {
+        (transfer_fee_intermediate, transfer_fee_input)
+    };
+    let (transfer_fee_two_a, transfer_fee_two_b) = if a_to_b_two {
+        (None, transfer_fee_output)
+    } else {
+        (transfer_fee_output, None)
+    };
+
+    let tick_arrays_one = || tick_arrays_from_slice(&pool_one.tick_array_facades());
+    let tick_arrays_two = || tick_arrays_from_slice(&pool_two.tick_array_facades());
+
+    match swap_type {
+        SwapType::ExactIn => {
+            let quote_one = swap_quote_by_input_token(
+                amount,
+                a_to_b_one,
+                0,
+                pool_one.data.clone().into(),
+                tick_arrays_one()?,
+                transfer_fee_one_a,
+                transfer_fee_one_b,
+            )?;
+            let quote_two = swap_quote_by_input_token(
+                quote_one.token_est_out,
+                a_to_b_two,
+                slippage_tolerance_bps,
+                pool_two.data.clone().into(),
+                tick_arrays_two()?,
+                transfer_fee_two_a,
+                transfer_fee_two_b,
+            )?;
+
+            Ok(TwoHopSwapQuote {
+                token_est_in: quote_one.token_in,
+                token_intermediate: quote_one.token_est_out,
+                token_est_out: quote_two.token_est_out,
+                other_amount_threshold: quote_two.token_min_out,
+                quote_one: SwapQuote::ExactIn(quote_one),
+                quote_two: SwapQuote::ExactIn(quote_two),
+            })
+        }
+        SwapType::ExactOut => {
+            let quote_two = swap_quote_by_output_token(
+                amount,
+                !a_to_b_two,
+                0,
+                pool_two.data.clone().into(),
+                tick_arrays_two()?,
+                transfer_fee_two_a,
+                transfer_fee_two_b,
+            )?;
+            let quote_one = swap_quote_by_output_token(
+                quote_two.token_est_in,
+                !a_to_b_one,
+                slippage_tolerance_bps,
+                pool_one.data.clone().into(),
+                tick_arrays_one()?,
+                transfer_fee_one_a,
+                transfer_fee_one_b,
+            )?;
+
+            Ok(TwoHopSwapQuote {
+                token_est_in: quote_one.token_est_in,
+                token_intermediate: quote_two.token_est_in,
+                token_est_out: quote_two.token_out,
+                other_amount_threshold: quote_one.token_max_in,
+                quote_one: SwapQuote::ExactOut(quote_one),
+                quote_two: SwapQuote::ExactOut(quote_two),
+            })
+        }
+    }
+}
+
+pub(crate) async fn fetch_swap_pool(
+    rpc: &impl AccountSource,
+    address: Pubkey,
+    data: FusionPool,
+    input_mint: Pubkey,
+) -> Result<SwapPool, Box<dyn Error>> {
+    let a_to_b = data.token_mint_a == input_mint;
+    let tick_arrays = fetch_tick_arrays_in_swap_direction(rpc, address, &data, a_to_b).await?;
+    Ok(SwapPool {
+        address,
+        data,
+        a_to_b,
+        tick_arrays,
+    })
+}
+
+pub(crate) async fn fetch_mint_infos(rpc: &impl AccountSource, mint_addresses: &[Pubkey]) -> Result<HashMap<Pubkey, SolanaAccount>, Box<dyn Error>> {
+    let mint_infos = rpc.get_multiple_accounts(mint_addresses).await?;
+
+    let mut result = HashMap::new();
+    for (&address, info) in zip(mint_addresses, mint_infos) {
+        result.insert(address, info.ok_or(format!("Mint not found: {}", address))?);
+    }
+    Ok(result)
+}
+
+pub(crate) fn get_transfer_fees(mint_infos: &HashMap<Pubkey, SolanaAccount>, current_epoch: u64) -> HashMap<Pubkey, Option<TransferFee>> {
+    mint_infos
+        .iter()
+        .map(|(address, info)| (*address, get_current_transfer_fee(Some(info), current_epoch)))
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn swap_instruction(
+    pool: &SwapPool,
+    quote: &SwapQuote,
+    mint_infos: &HashMap<Pubkey, SolanaAccount>,
+    token_account_addresses: &HashMap<Pubkey, Pubkey>,
+    signer: Pubkey,
+    amount: u64,
+    other_amount_threshold: u64,
+    specified_input: bool,
+) -> Result<Instruction, Box<dyn Error>> {
+    let tick_array_accounts = pool.tick_array_accounts(quote)?;
+    let token_mint_a = pool.data.token_mint_a;
+    let token_mint_b = pool.data.token_mint_b;
+    let mint_a_info = mint_infos.get(&token_mint_a).ok_or(format!("Mint a not found: {}", token_mint_a))?;
+    let mint_b_info = mint_infos.get(&token_mint_b).ok_or(format!("Mint b not found: {}", token_mint_b))?;
+    let token_owner_account_a = token_account_addresses.get(&token_mint_a).ok_or("Token A owner account not found")?;
+    let token_owner_account_b = token_account_addresses.get(&token_mint_b).ok_or("Token B owner account not found")?;
+
+    Ok(Swap {
+        token_program_a: mint_a_info.owner,
+        token_program_b: mint_b_info.owner,
+        memo_program: spl_memo::ID,
+        token_authority: signer,
+        fusion_pool: pool.address,
+        token_mint_a,
+        token_mint_b,
+        token_owner_account_a: *token_owner_account_a,
+        token_vault_a: pool.data.token_vault_a,
+        token_owner_account_b: *token_owner_account_b,
+        token_vault_b: pool.data.token_vault_b,
+        tick_array0: tick_array_accounts.tick_arrays[0],
+        tick_array1: tick_array_accounts.tick_arrays[1],
+        tick_array2: tick_array_accounts.tick_arrays[2],
+    }
+    .instruction_with_remaining_accounts(
+        SwapInstructionArgs {
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit: 0,
+            amount_specified_is_input: specified_input,
+            a_to_b: pool.a_to_b,
+            remaining_accounts_info: tick_array_accounts.remaining_accounts_info,
+        },
+        &tick_array_accounts.supplemental_tick_arrays,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn two_hop_swap_instruction(
+    pool_one: &SwapPool,
+    pool_two: &SwapPool,
+    quote: &TwoHopSwapQuote,
+    mint_infos: &HashMap<Pubkey, SolanaAccount>,
+    token_account_addresses: &HashMap<Pubkey, Pubkey>,
+    signer: Pubkey,
+    amount: u64,
+    other_amount_threshold: u64,
+    specified_input: bool,
+) -> Result<Instruction, Box<dyn Error>> {
+    let mints = get_two_hop_mints(&pool_one.data, &pool_two.data)?;
+    let a_to_b_one = pool_one.data.token_mint_a == mints.input;
+    let a_to_b_two = pool_two.data.token_mint_a == mints.intermediate;
+
+    let tick_array_accounts_one = pool_one.tick_array_accounts(&quote.quote_one)?;
+    let tick_array_accounts_two = pool_two.tick_array_accounts(&quote.quote_two)?;
+
+    let mut remaining_accounts_slices = Vec::new();
+    if !tick_array_accounts_one.supplemental_tick_arrays.is_empty() {
+        remaining_accounts_slices.push(RemainingAccountsSlice {
+            accounts_type: AccountsType::SupplementalTickArraysOne,
+            length: tick_array_accounts_one.supplemental_tick_arrays.len() as u8,
+        });
+    }
+    if !tick_array_accounts_two.supplemental_tick_arrays.is_empty() {
+        remaining_accounts_slices.push(RemainingAccountsSlice {
+            accounts_type: AccountsType::SupplementalTickArraysTwo,
+            length: tick_array_accounts_two.supplemental_tick_arrays.len() as u8,
+        });
+    }
+    let remaining_accounts_info = if remaining_accounts_slices.is_empty() {
+        None
+    } else {
+        Some(RemainingAccountsInfo {
+            slices: remaining_accounts_slices,
+        })
+    };
+
+    let token_program =
+        |mint: &Pubkey| -> Result<Pubkey, Box<dyn Error>> { Ok(mint_infos.get(mint).ok_or(format!("Mint not found: {}", mint))?.owner) };
+
+    let token_owner_account_input = token_account_addresses.get(&mints.input).ok_or("Input token owner account not found")?;
+    let token_owner_account_output = token_account_addresses.get(&mints.output).ok_or("Output token owner account not found")?;
+
+    let (token_vault_one_input, token_vault_one_intermediate) = if a_to_b_one {
+        (pool_one.data.token_vault_a, pool_one.data.token_vault_b)
+    } else {
+        (pool_one.data.token_vault_b, pool_one.data.token_vault_a)
+    };
+    let (token_vault_two_intermediate, token_vault_two_output) = if a_to_b_two {
+        (pool_two.data.token_vault_a, pool_two.data.token_vault_b)
+    } else {
+        (pool_two.data.token_vault_b, pool_two.data.token_vault_a)
+    };
+
+    Ok(TwoHopSwap {
+        fusion_pool_one: pool_one.address,
+        fusion_pool_two: pool_two.address,
+        token_mint_input: mints.input,
+        token_mint_intermediate: mints.intermediate,
+        token_mint_output: mints.output,
+        token_program_input: token_program(&mints.input)?,
+        token_program_intermediate: token_program(&mints.intermediate)?,
+        token_program_output: token_program(&mints.output)?,
+        token_owner_account_input: *token_owner_account_input,
+        token_vault_one_input,
+        token_vault_one_intermediate,
+        token_vault_two_intermediate,
+        token_vault_two_output,
+        token_owner_account_output: *token_owner_account_output,
+        token_authority: signer,
+        tick_array_one0: tick_array_accounts_one.tick_arrays[0],
+        tick_array_one1: tick_array_accounts_one.tick_arrays[1],
+        tick_array_one2: tick_array_accounts_one.tick_arrays[2],
+        tick_array_two0: tick_array_accounts_two.tick_arrays[0],
+        tick_array_two1: tick_array_accounts_two.tick_arrays[1],
+        tick_array_two2: tick_array_accounts_two.tick_arrays[2],
+        memo_program: spl_memo::ID,
+    }
+    .instruction_with_remaining_accounts(
+        TwoHopSwapInstructionArgs {
+            amount,
+            other_amount_threshold,
+            amount_specified_is_input: specified_input,
+            a_to_b_one,
+            a_to_b_two,
+            sqrt_price_limit_one: 0,
+            sqrt_price_limit_two: 0,
+            remaining_accounts_info,
+        },
+        &[
+            tick_array_accounts_one.supplemental_tick_arrays,
+            tick_array_accounts_two.supplemental_tick_arrays,
+        ]
+        .concat(),
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn build_two_hop_swap_instructions(
+    rpc: &impl AccountSource,
+    pool_one: &SwapPool,
+    pool_two: &SwapPool,
+    mint_infos: &HashMap<Pubkey, SolanaAccount>,
+    amount: u64,
+    swap_type: SwapType,
+    quote: TwoHopSwapQuote,
+    signer: Pubkey,
+) -> Result<TwoHopSwapInstructions, Box<dyn Error>> {
+    let mints = get_two_hop_mints(&pool_one.data, &pool_two.data)?;
+    let specified_input = swap_type == SwapType::ExactIn;
+
+    let max_in_amount = match swap_type {
+        SwapType::ExactIn => quote.token_est_in,
+        SwapType::ExactOut => quote.other_amount_threshold,
+    };
+
+    let mut instructions: Vec<Instruction> = Vec::new();
+
+    let token_accounts = prepare_token_accounts_instructions(
+        rpc,
+        signer,
+        vec![
+            TokenAccountStrategy::WithBalance(mints.input, max_in_amount),
+            TokenAccountStrategy::WithoutBalance(mints.output),
+        ],
+    )
+    .await?;
+
+    instructions.extend(token_accounts.create_instructions);
+
+    instructions.push(two_hop_swap_instruction(
+        pool_one,
+        pool_two,
+        &quote,
+        mint_infos,
+        &token_accounts.token_account_addresses,
+        signer,
+        amount,
+        quote.other_amount_threshold,
+        specified_input,
+    )?);
+
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(TwoHopSwapInstructions {
         instructions,
         quote,
+        fusion_pool_one: pool_one.address,
+        fusion_pool_two: pool_two.address,
         additional_signers: token_accounts.additional_signers,
     })
 }
 
+#[cfg(not(doctest))]
+/// Generates the instructions necessary to execute a token swap through two pools.
+///
+/// The first pool swaps the input token into the intermediate token (the mint shared by both pools),
+/// and the second pool swaps the intermediate token into the output token. Both hops are executed
+/// by a single `TwoHopSwap` instruction, and the quotes are chained so that the intermediate amount
+/// matches the on-chain computation, including Token-2022 transfer fees on the intermediate token.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client for fetching accounts and interacting with the blockchain.
+/// * `fusion_pool_one_address` - The public key of the FusionPool used for the first hop. Its mint that is not
+///   shared with the second pool is the input token.
+/// * `fusion_pool_two_address` - The public key of the FusionPool used for the second hop. Its mint that is not
+///   shared with the first pool is the output token.
+/// * `amount` - The token amount specified for the swap. For `SwapType::ExactIn`, this is the input token amount.
+///   For `SwapType::ExactOut`, this is the output token amount.
+/// * `swap_type` - The type of swap (`SwapType::ExactIn` or `SwapType::ExactOut`).
+/// * `slippage_tolerance_bps` - An optional slippage tolerance, in basis points (BPS). Defaults to the global setting if not provided.
+/// * `signer` - An optional public key of the wallet or account executing the swap. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `TwoHopSwapInstructions` on success:
+/// * `instructions` - A vector of `Instruction` objects required to execute the swap.
+/// * `quote` - A `TwoHopSwapQuote` providing the computed details of both hops.
+/// * `fusion_pool_one` - The address of the pool used for the first hop.
+/// * `fusion_pool_two` - The address of the pool used for the second hop.
+/// * `additional_signers` - A vector of `Keypair` objects representing any additional signers required for the instructions.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The signer is invalid or missing.
+/// - The pools do not share exactly one token mint.
+/// - The FusionPool or token mint accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+///
+/// # Example
+///
+/// ```rust
+/// use fusionamm_sdk::{two_hop_swap_instructions, SwapType};
+/// use solana_client::nonblocking::rpc_client::RpcClient;
+/// use solana_pubkey::pubkey;
+/// use solana_keypair::Keypair;
+/// use solana_signer::Signer;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let rpc = RpcClient::new("https://api.mainnet.solana.com".to_string());
+///     let wallet = Keypair::new(); // Load your wallet here
+///
+///     let fusion_pool_one = pubkey!("7VuKeevbvbQQcxz6N4SNLmuq6PYy4AcGQRDssoqo4t65");
+///     let fusion_pool_two = pubkey!("Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE");
+///     let input_amount = 1_000_000;
+///
+///     let result = two_hop_swap_instructions(
+///         &rpc,
+///         fusion_pool_one,
+///         fusion_pool_two,
+///         input_amount,
+///         SwapType::ExactIn,
+///         Some(100),
+///         Some(wallet.pubkey()),
+///     )
+///     .await
+///     .unwrap();
+///
+///     println!("Quote estimated token out: {}", result.quote.token_est_out);
+///     println!("Number of Instructions: {}", result.instructions.len());
+/// }
+/// ```
+pub async fn two_hop_swap_instructions(
+    rpc: &impl AccountSource,
+    fusion_pool_one_address: Pubkey,
+    fusion_pool_two_address: Pubkey,
+    amount: u64,
+    swap_type: SwapType,
+    slippage_tolerance_bps: Option<u16>,
+    signer: Option<Pubkey>,
+) -> Result<TwoHopSwapInstructions, Box<dyn Error>> {
+    let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
+    let signer = signer.unwrap_or(*FUNDER.try_lock()?);
+    if signer == Pubkey::default() {
+        return Err("Signer must be provided".into());
+    }
+
+    let fusion_pool_infos = rpc.get_multiple_accounts(&[fusion_pool_one_address, fusion_pool_two_address]).await?;
+    let fusion_pool_one_info = fusion_pool_infos[0]
+        .as_ref()
+        .ok_or(format!("Fusion pool not found: {}", fusion_pool_one_address))?;
+    let fusion_pool_two_info = fusion_pool_infos[1]
+        .as_ref()
+        .ok_or(format!("Fusion pool not found: {}", fusion_pool_two_address))?;
+
+    let fusion_pool_one = FusionPool::from_bytes(&fusion_pool_one_info.data)?;
+    let fusion_pool_two = FusionPool::from_bytes(&fusion_pool_two_info.data)?;
+
+    let mints = get_two_hop_mints(&fusion_pool_one, &fusion_pool_two)?;
+    let pool_one = fetch_swap_pool(rpc, fusion_pool_one_address, fusion_pool_one, mints.input).await?;
+    let pool_two = fetch_swap_pool(rpc, fusion_pool_two_address, fusion_pool_two, mints.intermediate).await?;
+    let mint_infos = fetch_mint_infos(rpc, &[mints.input, mints.intermediate, mints.output]).await?;
+    let current_epoch = rpc.get_epoch().await?;
+    let transfer_fees = get_transfer_fees(&mint_infos, current_epoch);
+
+    let quote = get_two_hop_swap_quote(amount, &swap_type, slippage_tolerance_bps, &pool_one, &pool_two, &transfer_fees)?;
+
+    build_two_hop_swap_instructions(rpc, &pool_one, &pool_two, &mint_infos, amount, swap_type, quote, signer).await
+}
+
+#[cfg(not(doctest))]
+/// Generates the instructions necessary to execute a token swap from the input mint to the output mint
+/// through the intermediate mint.
+///
+/// All candidate pools of the input/intermediate and intermediate/output token pairs are evaluated, and the pair
+/// of pools yielding the best quote (the largest output for `SwapType::ExactIn`, the smallest input for
+/// `SwapType::ExactOut`) is used to build a single `TwoHopSwap` instruction.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to an account source, e.g. the Solana RPC client.
+/// * `fusion_pools` - The addresses of the candidate fusion pools, e.g. from `fetch_route_fusion_pool_addresses` with
+///   the intermediate mint as the only base mint. Candidates of other token pairs are ignored.
+/// * `input_mint` - The public key of the token mint being sold.
+/// * `intermediate_mint` - The public key of the token mint the swap is routed through.
+/// * `output_mint` - The public key of the token mint being bought.
+/// * `amount` - The token amount specified for the swap. For `SwapType::ExactIn`, this is the input token amount.
+///   For `SwapType::ExactOut`, this is the output token amount.
+/// * `swap_type` - The type of swap (`SwapType::ExactIn` or `SwapType::ExactOut`).
+/// * `slippage_tolerance_bps` - An optional slippage tolerance, in basis points (BPS). Defaults to the global setting if not provided.
+/// * `signer` - An optional public key of the wallet or account executing the swap. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `TwoHopSwapInstructions` on success. See [`two_hop_swap_instructions`] for details.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The signer is invalid or missing.
+/// - No pool pair can quote the requested swap.
+/// - The token mint accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+#[allow(clippy::too_many_arguments)]
+pub async fn two_hop_swap_instructions_by_mints(
+    rpc: &impl AccountSource,
+    fusion_pools: &[Pubkey],
+    input_mint: Pubkey,
+    intermediate_mint: Pubkey,
+    output_mint: Pubkey,
+    amount: u64,
+    swap_type: SwapType,
+    slippage_tolerance_bps: Option<u16>,
+    signer: Option<Pubkey>,
+) -> Result<TwoHopSwapInstructions, Box<dyn Error>> {
+    let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
+    let signer = signer.unwrap_or(*FUNDER.try_lock()?);
+    if signer == Pubkey::default() {
+        return Err("Signer must be provided".into());
+    }
+    if input_mint == output_mint || input_mint == intermediate_mint || intermediate_mint == output_mint {
+        return Err("Input, intermediate and output mints must be distinct".into());
+    }
+
+    let mints = TwoHopMints {
+        input: input_mint,
+        intermediate: intermediate_mint,
+        output: output_mint,
+    };
+    let mint_infos = fetch_mint_infos(rpc, &[mints.input, mints.intermediate, mints.output]).await?;
+    let current_epoch = rpc.get_epoch().await?;
+    let transfer_fees = get_transfer_fees(&mint_infos, current_epoch);
+
+    let fusion_pools = fetch_fusion_pools(rpc, fusion_pools).await?;
+
+    let mut pools_one: Vec<SwapPool> = Vec::new();
+    for pool in fusion_pools.iter().filter(|x| is_token_pair(&x.data, input_mint, intermediate_mint)) {
+        pools_one.push(fetch_swap_pool(rpc, pool.address, pool.data.clone(), input_mint).await?);
+    }
+    let mut pools_two: Vec<SwapPool> = Vec::new();
+    for pool in fusion_pools.iter().filter(|x| is_token_pair(&x.data, intermediate_mint, output_mint)) {
+        pools_two.push(fetch_swap_pool(rpc, pool.address, pool.data.clone(), intermediate_mint).await?);
+    }
+
+    let mut best: Option<(&SwapPool, &SwapPool, TwoHopSwapQuote)> = None;
+    for pool_one in pools_one.iter().filter(|x| x.data.liquidity > 0) {
+        for pool_two in pools_two.iter().filter(|x| x.data.liquidity > 0) {
+            let Ok(quote) = get_two_hop_swap_quote(amount, &swap_type, slippage_tolerance_bps, pool_one, pool_two, &transfer_fees) else {
+                continue;
+            };
+            let is_better = match &best {
+                None => true,
+                Some((_, _, best_quote)) => match swap_type {
+                    SwapType::ExactIn => quote.token_est_out > best_quote.token_est_out,
+                    SwapType::ExactOut => quote.token_est_in < best_quote.token_est_in,
+                },
+            };
+            if is_better {
+                best = Some((pool_one, pool_two, quote));
+            }
+        }
+    }
+
+    let (pool_one, pool_two, quote) = best.ok_or("No two-hop route found for the given mints")?;
+
+    build_two_hop_swap_instructions(rpc, pool_one, pool_two, &mint_infos, amount, swap_type, quote, signer).await
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -321,7 +1248,6 @@ mod tests {
 
     use rstest::rstest;
     use serial_test::serial;
-    use solana_client::nonblocking::rpc_client::RpcClient;
     use solana_keypair::Keypair;
     use solana_program::program_pack::Pack;
     use solana_program_test::tokio;
@@ -331,15 +1257,17 @@ mod tests {
     use spl_token_2022::{extension::StateWithExtensionsOwned, state::Account as TokenAccount2022, ID as TOKEN_2022_PROGRAM_ID};
 
     use crate::{
-        increase_liquidity_instructions, swap_instructions,
+        increase_liquidity_instructions, swap_instructions, swap_with_price_limit_instructions,
         tests::{
             setup_ata_te, setup_ata_with_amount, setup_fusion_pool, setup_mint_te, setup_mint_te_fee, setup_mint_with_decimals, setup_position,
             RpcContext, SetupAtaConfig,
         },
-        IncreaseLiquidityParam, SwapInstructions, SwapQuote, SwapType,
+        two_hop_swap_instructions, AccountSource, IncreaseLiquidityParam, SwapInstructions, SwapPriceLimit, SwapQuote, SwapType,
     };
+    use fusionamm_client::{get_tick_array_address, FusionPool, FUSIONAMM_ID};
+    use fusionamm_core::{tick_index_to_sqrt_price, ExactInSwapQuote, ExactOutSwapQuote};
 
-    async fn get_token_balance(rpc: &RpcClient, address: Pubkey) -> Result<u64, Box<dyn Error>> {
+    async fn get_token_balance(rpc: &impl AccountSource, address: Pubkey) -> Result<u64, Box<dyn Error>> {
         let account_data = rpc.get_account(&address).await?;
         if account_data.owner == TOKEN_2022_PROGRAM_ID {
             let parsed = StateWithExtensionsOwned::<TokenAccount2022>::unpack(account_data.data)?;
@@ -539,4 +1467,224 @@ mod tests {
                 .unwrap();
         });
     }
+
+    async fn setup_pool_with_liquidity(ctx: &RpcContext, mint_1: Pubkey, mint_2: Pubkey) -> Result<Pubkey, Box<dyn Error>> {
+        let (mint_a, mint_b) = if mint_1 < mint_2 { (mint_1, mint_2) } else { (mint_2, mint_1) };
+        let pool_pubkey = setup_fusion_pool(ctx, mint_a, mint_b, 64, 300).await?;
+        let position_mint = setup_position(ctx, pool_pubkey, Some((-192, 192)), None).await?;
+
+        let liq_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            position_mint,
+            IncreaseLiquidityParam::Liquidity(1_000_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await?;
+        ctx.send_transaction_with_signers(liq_ix.instructions, liq_ix.additional_signers.iter().collect())
+            .await?;
+
+        Ok(pool_pubkey)
+    }
+
+    #[rstest]
+    #[case("A", "B", "TEA", SwapType::ExactIn, 1000)]
+    #[case("A", "B", "TEA", SwapType::ExactOut, 500)]
+    #[case("TEA", "B", "A", SwapType::ExactIn, 200)]
+    #[case("TEA", "B", "A", SwapType::ExactOut, 100)]
+    #[case("A", "TEFee", "B", SwapType::ExactIn, 1000)]
+    #[case("A", "TEFee", "B", SwapType::ExactOut, 500)]
+    #[case("TEB", "TEFee", "A", SwapType::ExactIn, 200)]
+    #[case("TEB", "TEFee", "A", SwapType::ExactOut, 100)]
+    #[serial]
+    fn test_two_hop_swap_scenarios(
+        #[case] input: &str,
+        #[case] intermediate: &str,
+        #[case] output: &str,
+        #[case] swap_type: SwapType,
+        #[case] amount: u64,
+    ) {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let ctx = RpcContext::new().await;
+
+            let minted = setup_all_mints(&ctx).await.unwrap();
+            let user_atas = setup_all_atas(&ctx, &minted).await.unwrap();
+
+            let pool_one = setup_pool_with_liquidity(&ctx, minted[input], minted[intermediate]).await.unwrap();
+            let pool_two = setup_pool_with_liquidity(&ctx, minted[intermediate], minted[output]).await.unwrap();
+
+            let swap_ix = two_hop_swap_instructions(&ctx.rpc, pool_one, pool_two, amount, swap_type.clone(), Some(100), Some(ctx.signer.pubkey()))
+                .await
+                .unwrap();
+
+            let before_input = get_token_balance(&ctx.rpc, user_atas[input]).await.unwrap();
+            let before_intermediate = get_token_balance(&ctx.rpc, user_atas[intermediate]).await.unwrap();
+            let before_output = get_token_balance(&ctx.rpc, user_atas[output]).await.unwrap();
+
+            let signers: Vec<&Keypair> = swap_ix.additional_signers.iter().collect();
+            ctx.send_transaction_with_signers(swap_ix.instructions.clone(), signers).await.unwrap();
+
+            let after_input = get_token_balance(&ctx.rpc, user_atas[input]).await.unwrap();
+            let after_intermediate = get_token_balance(&ctx.rpc, user_atas[intermediate]).await.unwrap();
+            let after_output = get_token_balance(&ctx.rpc, user_atas[output]).await.unwrap();
+
+            assert_eq!(before_input - after_input, swap_ix.quote.token_est_in, "Used input mismatch");
+            assert_eq!(after_output - before_output, swap_ix.quote.token_est_out, "Gained output mismatch");
+            assert_eq!(before_intermediate, after_intermediate, "Intermediate balance changed");
+            if swap_type == SwapType::ExactOut {
+                assert_eq!(swap_ix.quote.token_est_out, amount);
+            } else {
+                assert_eq!(swap_ix.quote.token_est_in, amount);
+            }
+        });
+    }
+
+    #[rstest]
+    #[case(true, SwapType::ExactIn)]
+    #[case(true, SwapType::ExactOut)]
+    #[case(false, SwapType::ExactIn)]
+    #[case(false, SwapType::ExactOut)]
+    #[serial]
+    fn test_swap_with_price_limit(#[case] a_to_b: bool, #[case] swap_type: SwapType) {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let ctx = RpcContext::new().await;
+
+            let minted = setup_all_mints(&ctx).await.unwrap();
+            let user_atas = setup_all_atas(&ctx, &minted).await.unwrap();
+            let pool_pubkey = setup_pool_with_liquidity(&ctx, minted["A"], minted["B"]).await.unwrap();
+
+            let pool = FusionPool::from_bytes(&ctx.rpc.get_account(&pool_pubkey).await.unwrap().data).unwrap();
+            let (ata_a, ata_b) = if pool.token_mint_a == minted["A"] {
+                (user_atas["A"], user_atas["B"])
+            } else {
+                (user_atas["B"], user_atas["A"])
+            };
+
+            let limit_tick_index = if a_to_b { -64 } else { 64 };
+            let specified_mint = if a_to_b == (swap_type == SwapType::ExactIn) {
+                pool.token_mint_a
+            } else {
+                pool.token_mint_b
+            };
+
+            let requested_amount = 1_000_000;
+            let swap_ix = swap_with_price_limit_instructions(
+                &ctx.rpc,
+                pool_pubkey,
+                requested_amount,
+                specified_mint,
+                swap_type.clone(),
+                SwapPriceLimit::TickIndex(limit_tick_index),
+                Some(100),
+                Some(ctx.signer.pubkey()),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(swap_ix.sqrt_price_limit, tick_index_to_sqrt_price(limit_tick_index));
+            assert_eq!(swap_ix.requested_amount, requested_amount);
+            assert!(swap_ix.is_partial_fill());
+            assert!(swap_ix.filled_amount > 0);
+
+            let swap_instructions = SwapInstructions {
+                instructions: swap_ix.instructions,
+                quote: swap_ix.quote,
+                additional_signers: swap_ix.additional_signers,
+            };
+            verify_swap(&ctx, &swap_instructions, ata_a, ata_b, a_to_b).await.unwrap();
+
+            let pool_after = FusionPool::from_bytes(&ctx.rpc.get_account(&pool_pubkey).await.unwrap().data).unwrap();
+            assert_eq!(pool_after.sqrt_price, swap_ix.sqrt_price_limit);
+        });
+    }
+
+    #[rstest]
+    #[case(true, SwapType::ExactIn)]
+    #[case(true, SwapType::ExactOut)]
+    #[case(false, SwapType::ExactIn)]
+    #[case(false, SwapType::ExactOut)]
+    #[serial]
+    fn test_swap_uses_crossed_tick_arrays(#[case] a_to_b: bool, #[case] swap_type: SwapType) {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let ctx = RpcContext::new().await;
+
+            let minted = setup_all_mints(&ctx).await.unwrap();
+            let user_atas = setup_all_atas(&ctx, &minted).await.unwrap();
+            let pool_pubkey = setup_pool_with_liquidity(&ctx, minted["A"], minted["B"]).await.unwrap();
+
+            let pool = FusionPool::from_bytes(&ctx.rpc.get_account(&pool_pubkey).await.unwrap().data).unwrap();
+            let (ata_a, ata_b) = if pool.token_mint_a == minted["A"] {
+                (user_atas["A"], user_atas["B"])
+            } else {
+                (user_atas["B"], user_atas["A"])
+            };
+            let specified_mint = if a_to_b == (swap_type == SwapType::ExactIn) {
+                pool.token_mint_a
+            } else {
+                pool.token_mint_b
+            };
+
+            let swap_ix = swap_instructions(&ctx.rpc, pool_pubkey, 100, specified_mint, swap_type.clone(), Some(100), Some(ctx.signer.pubkey()))
+                .await
+                .unwrap();
+
+            let start_indexes = swap_ix.quote.tick_array_start_indexes(pool.tick_spacing);
+            let offset = TICK_ARRAY_SIZE as i32 * pool.tick_spacing as i32;
+            let step = if a_to_b { -offset } else { offset };
+            let expected: Vec<Pubkey> = (0..3).map(|i| get_tick_array_address(&pool_pubkey, i * step).unwrap().0).collect();
+
+            let swap = swap_ix.instructions.iter().find(|x| x.program_id == FUSIONAMM_ID).unwrap();
+            let tick_array_accounts: Vec<Pubkey> = swap.accounts[11..].iter().map(|x| x.pubkey).collect();
+            assert_eq!(start_indexes[0], 0);
+            assert_eq!(tick_array_accounts, expected);
+
+            verify_swap(&ctx, &swap_ix, ata_a, ata_b, a_to_b).await.unwrap();
+        });
+    }
+
+    #[rstest]
+    #[case(true)]
+    #[case(false)]
+    #[serial]
+    fn test_swap_past_available_liquidity(#[case] a_to_b: bool) {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let ctx = RpcContext::new().await;
+
+            let minted = setup_all_mints(&ctx).await.unwrap();
+            let pool_pubkey = setup_pool_with_liquidity(&ctx, minted["A"], minted["B"]).await.unwrap();
+
+            let pool = FusionPool::from_bytes(&ctx.rpc.get_account(&pool_pubkey).await.unwrap().data).unwrap();
+            let specified_mint = if a_to_b { pool.token_mint_a } else { pool.token_mint_b };
+
+            let result =
+                swap_instructions(&ctx.rpc, pool_pubkey, 1_000_000_000, specified_mint, SwapType::ExactIn, Some(100), Some(ctx.signer.pubkey()))
+                    .await;
+
+            let error = result.err().unwrap().to_string();
+            assert!(error.starts_with("Insufficient liquidity"), "Unexpected error: {}", error);
+        });
+    }
+
+    #[test]
+    fn test_tick_array_start_indexes() {
+        let offset = TICK_ARRAY_SIZE as i32 * 64;
+
+        let a_to_b = SwapQuote::ExactIn(ExactInSwapQuote {
+            first_tick_array_start_index: 0,
+            last_tick_array_start_index: -2 * offset,
+            ..ExactInSwapQuote::default()
+        });
+        assert_eq!(a_to_b.tick_array_start_indexes(64), vec![0, -offset, -2 * offset]);
+
+        let b_to_a = SwapQuote::ExactOut(ExactOutSwapQuote {
+            first_tick_array_start_index: -offset,
+            last_tick_array_start_index: -offset,
+            ..ExactOutSwapQuote::default()
+        });
+        assert_eq!(b_to_a.tick_array_start_indexes(64), vec![-offset]);
+    }
 }
@@ -144,22 +144,7 @@ impl OrderBook {
         (decimals_a, decimals_b): (u8, u8),
         tick_arrays: &[&TickArray],
     ) -> Result<Self, fusionamm_core::CoreError> {
-        let fusion_pool_facade = FusionPoolFacade {
-            tick_spacing: pool.tick_spacing,
-            fee_rate: pool.fee_rate,
-            protocol_fee_rate: pool.protocol_fee_rate,
-            liquidity: pool.liquidity,
-            sqrt_price: pool.sqrt_price,
-            tick_current_index: pool.tick_current_index,
-            fee_growth_global_a: pool.fee_growth_global_a,
-            fee_growth_global_b: pool.fee_growth_global_b,
-            orders_total_amount_a: pool.orders_total_amount_a,
-            orders_total_amount_b: pool.orders_total_amount_b,
-            orders_filled_amount_a: pool.orders_filled_amount_a,
-            orders_filled_amount_b: pool.orders_filled_amount_b,
-            olp_fee_owed_a: pool.olp_fee_owed_a,
-            olp_fee_owed_b: pool.olp_fee_owed_b,
-        };
+        let fusion_pool_facade: FusionPoolFacade = pool.clone().into();
         let price_shift = price_step * entries as f64;
         let pool_price = if inverted {
             sqrt_price_to_price(invert_sqrt_price(fusion_pool_facade.sqrt_price), decimals_b, decimals_a)
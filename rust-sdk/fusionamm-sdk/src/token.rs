@@ -8,10 +8,9 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use crate::{NativeMintWrappingStrategy, NATIVE_MINT_WRAPPING_STRATEGY};
+use crate::{AccountSource, NativeMintWrappingStrategy, NATIVE_MINT_WRAPPING_STRATEGY};
 use fusionamm_core::TransferFee;
 use solana_account::Account as SolanaAccount;
-use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_instruction::Instruction;
 use solana_keypair::Keypair;
 use solana_program::hash::hashv;
@@ -44,7 +43,7 @@ pub(crate) struct TokenAccountInstructions {
 }
 
 pub(crate) async fn prepare_token_accounts_instructions(
-    rpc: &RpcClient,
+    rpc: &impl AccountSource,
     owner: Pubkey,
     spec: Vec<TokenAccountStrategy>,
 ) -> Result<TokenAccountInstructions, Box<dyn Error>> {
@@ -127,7 +126,7 @@ pub(crate) async fn prepare_token_accounts_instructions(
 
     if has_native_mint && native_mint_wrapping_strategy == NativeMintWrappingStrategy::Keypair {
         let keypair = Keypair::new();
-        let mut lamports = rpc.get_minimum_balance_for_rent_exemption(Account::LEN).await?;
+        let mut lamports = rpc.get_rent().await?.minimum_balance(Account::LEN);
 
         if let TokenAccountStrategy::WithBalance(_, balance) = spec[native_mint_index.unwrap_or(0)] {
             lamports += balance;
@@ -144,7 +143,7 @@ pub(crate) async fn prepare_token_accounts_instructions(
     }
 
     if has_native_mint && native_mint_wrapping_strategy == NativeMintWrappingStrategy::Seed {
-        let mut lamports = rpc.get_minimum_balance_for_rent_exemption(Account::LEN).await?;
+        let mut lamports = rpc.get_rent().await?.minimum_balance(Account::LEN);
 
         if let TokenAccountStrategy::WithBalance(_, balance) = spec[native_mint_index.unwrap_or(0)] {
             lamports += balance;
@@ -0,0 +1,570 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use fusionamm_client::{fetch_all_tick_array_with_filter, FusionPool, TickArray, TickArrayFilter, FUSIONAMM_ID, TICK_ARRAY_DISCRIMINATOR};
+use fusionamm_core::{FusionPoolFacade, TickArrayFacade, TickArraySequence};
+use futures::stream::{BoxStream, SelectAll, StreamExt};
+use solana_account::Account;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::{PubsubClient, PubsubClientError};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_commitment_config::CommitmentConfig;
+use solana_pubkey::Pubkey;
+use std::{collections::HashMap, error::Error, future::Future, iter::zip, str::FromStr};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+use crate::AccountSource;
+
+/// A change of an on-chain account observed at a slot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountUpdate {
+    /// The address of the account.
+    pub address: Pubkey,
+
+    /// The slot at which the account had this state.
+    pub slot: u64,
+
+    /// The new state of the account, or `None` if the account was closed.
+    pub account: Option<Account>,
+}
+
+/// A stream of account updates consumed by the `PoolStateTracker`.
+pub trait AccountUpdateStream {
+    /// Waits for the next account update. Returns `None` once the stream has ended.
+    fn next_update(&mut self) -> impl Future<Output = Option<AccountUpdate>> + Send;
+}
+
+/// An account update stream fed through a channel, e.g. by a custom indexer or by tests.
+#[derive(Debug)]
+pub struct ChannelAccountUpdateStream {
+    receiver: UnboundedReceiver<AccountUpdate>,
+}
+
+impl ChannelAccountUpdateStream {
+    /// Creates a stream together with the sender to push account updates into it.
+    /// The stream ends once all senders are dropped.
+    pub fn channel() -> (UnboundedSender<AccountUpdate>, Self) {
+        let (sender, receiver) = unbounded_channel();
+        (sender, Self { receiver })
+    }
+}
+
+impl AccountUpdateStream for ChannelAccountUpdateStream {
+    async fn next_update(&mut self) -> Option<AccountUpdate> {
+        self.receiver.recv().await
+    }
+}
+
+/// An account update stream backed by websocket subscriptions to a Solana RPC node.
+///
+/// Each fusion pool is watched with an `accountSubscribe` subscription and its tick arrays,
+/// including the ones created later, with a `programSubscribe` subscription.
+/// The stream ends when the connection is lost. In that case, reconnect and reload the tracker.
+#[derive(Debug)]
+pub struct WebsocketAccountUpdateStream {
+    updates: ChannelAccountUpdateStream,
+    task: JoinHandle<()>,
+}
+
+impl WebsocketAccountUpdateStream {
+    /// Connects to the websocket endpoint of an RPC node and subscribes to the given fusion pools and their tick arrays.
+    ///
+    /// The subscriptions are active once this function returns, so a `PoolStateTracker` loaded afterwards does not miss updates.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The websocket URL of the RPC node.
+    /// * `fusion_pools` - The addresses of the fusion pools to watch.
+    /// * `commitment` - The commitment level of the updates.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the connected stream.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the connection or any of the subscriptions fails.
+    pub async fn connect(url: &str, fusion_pools: &[Pubkey], commitment: CommitmentConfig) -> Result<Self, Box<dyn Error>> {
+        let (sender, updates) = ChannelAccountUpdateStream::channel();
+        let (ready_sender, ready_receiver) = oneshot::channel();
+        let task = tokio::spawn(forward_account_updates(url.to_string(), fusion_pools.to_vec(), commitment, sender, ready_sender));
+        ready_receiver.await??;
+        Ok(Self { updates, task })
+    }
+}
+
+impl AccountUpdateStream for WebsocketAccountUpdateStream {
+    async fn next_update(&mut self) -> Option<AccountUpdate> {
+        self.updates.next_update().await
+    }
+}
+
+impl Drop for WebsocketAccountUpdateStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn forward_account_updates(
+    url: String,
+    fusion_pools: Vec<Pubkey>,
+    commitment: CommitmentConfig,
+    sender: UnboundedSender<AccountUpdate>,
+    ready: oneshot::Sender<Result<(), String>>,
+) {
+    let client = match PubsubClient::new(&url).await {
+        Ok(client) => client,
+        Err(err) => {
+            let _ = ready.send(Err(err.to_string()));
+            return;
+        }
+    };
+
+    let mut updates = SelectAll::new();
+    for fusion_pool in fusion_pools {
+        match subscribe_fusion_pool(&client, fusion_pool, commitment).await {
+            Ok(subscriptions) => updates.extend(subscriptions),
+            Err(err) => {
+                let _ = ready.send(Err(err.to_string()));
+                return;
+            }
+        }
+    }
+
+    if ready.send(Ok(())).is_err() {
+        return;
+    }
+
+    while let Some(update) = updates.next().await {
+        if sender.send(update).is_err() {
+            break;
+        }
+    }
+}
+
+async fn subscribe_fusion_pool(
+    client: &PubsubClient,
+    fusion_pool: Pubkey,
+    commitment: CommitmentConfig,
+) -> Result<[BoxStream<'_, AccountUpdate>; 2], PubsubClientError> {
+    let account_config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(commitment),
+        ..RpcAccountInfoConfig::default()
+    };
+
+    let (fusion_pool_updates, _) = client.account_subscribe(&fusion_pool, Some(account_config.clone())).await?;
+    let fusion_pool_updates = fusion_pool_updates
+        .map(move |response| AccountUpdate {
+            address: fusion_pool,
+            slot: response.context.slot,
+            account: response.value.decode(),
+        })
+        .boxed();
+
+    let program_config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, TICK_ARRAY_DISCRIMINATOR.to_vec())),
+            TickArrayFilter::FusionPool(fusion_pool).into(),
+        ]),
+        account_config,
+        with_context: Some(true),
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let (tick_array_updates, _) = client.program_subscribe(&FUSIONAMM_ID, Some(program_config)).await?;
+    let tick_array_updates = tick_array_updates
+        .filter_map(|response| async move {
+            Some(AccountUpdate {
+                address: Pubkey::from_str(&response.value.pubkey).ok()?,
+                slot: response.context.slot,
+                account: response.value.account.decode(),
+            })
+        })
+        .boxed();
+
+    Ok([fusion_pool_updates, tick_array_updates])
+}
+
+/// Finds the addresses of all tick arrays of the given fusion pools.
+///
+/// This is the discovery step of `PoolStateTracker::load`. It relies on `getProgramAccounts`,
+/// so it needs an RPC client; the tracker itself can then be loaded from any `AccountSource`.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `fusion_pools` - The addresses of the fusion pools.
+///
+/// # Returns
+///
+/// A `Result` containing the addresses of the tick arrays.
+///
+/// # Errors
+///
+/// This function will return an error if the RPC request fails.
+pub async fn fetch_tick_array_addresses(rpc: &RpcClient, fusion_pools: &[Pubkey]) -> Result<Vec<Pubkey>, Box<dyn Error>> {
+    let mut addresses: Vec<Pubkey> = Vec::new();
+    for fusion_pool in fusion_pools {
+        let tick_arrays = fetch_all_tick_array_with_filter(rpc, vec![TickArrayFilter::FusionPool(*fusion_pool)]).await?;
+        addresses.extend(tick_arrays.into_iter().map(|x| x.address));
+    }
+    Ok(addresses)
+}
+
+#[derive(Debug)]
+struct TrackedFusionPool {
+    slot: u64,
+    fusion_pool: Option<FusionPool>,
+}
+
+#[derive(Debug)]
+struct TrackedTickArray {
+    fusion_pool: Pubkey,
+    slot: u64,
+    tick_array: Option<TickArrayFacade>,
+}
+
+/// An in-memory mirror of fusion pools and their tick arrays, kept up to date by account update streams.
+///
+/// Updates older than the state already tracked for an account are ignored, so updates can be
+/// applied from several sources and in any order. Tick arrays are picked up when they are created
+/// and dropped when they are closed.
+///
+/// # Example
+///
+/// ```ignore
+/// let tick_arrays = fetch_tick_array_addresses(&rpc, &[fusion_pool]).await?;
+/// let mut stream = WebsocketAccountUpdateStream::connect(ws_url, &[fusion_pool], CommitmentConfig::confirmed()).await?;
+/// let mut tracker = PoolStateTracker::load(&rpc, &[fusion_pool], &tick_arrays).await?;
+/// while let Some(changed) = tracker.next_change(&mut stream).await {
+///     let changed = changed?;
+///     let pool = tracker.fusion_pool_facade(&changed).unwrap();
+///     let tick_sequence = tracker.tick_array_sequence(&changed)?;
+///     // Quote against the latest state
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct PoolStateTracker {
+    fusion_pools: HashMap<Pubkey, TrackedFusionPool>,
+    tick_arrays: HashMap<Pubkey, TrackedTickArray>,
+}
+
+impl PoolStateTracker {
+    /// Creates a tracker without any fusion pools.
+    pub fn new() -> Self {
+        Self {
+            fusion_pools: HashMap::new(),
+            tick_arrays: HashMap::new(),
+        }
+    }
+
+    /// Creates a tracker for the given fusion pools, loaded with the current state of the pools and the given tick arrays.
+    ///
+    /// The tick arrays of the pools can be found with `fetch_tick_array_addresses` or come from an indexer.
+    /// Tick arrays that don't exist are skipped, and the ones created later are picked up from the account updates.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc` - A reference to an account source, e.g. the Solana RPC client.
+    /// * `fusion_pools` - The addresses of the fusion pools to track.
+    /// * `tick_arrays` - The addresses of the tick arrays of the fusion pools.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the loaded `PoolStateTracker`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - A fusion pool does not exist or has invalid data.
+    /// - A tick array has invalid data.
+    /// - Any RPC request to the blockchain fails.
+    pub async fn load(rpc: &impl AccountSource, fusion_pools: &[Pubkey], tick_arrays: &[Pubkey]) -> Result<Self, Box<dyn Error>> {
+        // The slot is fetched first, so the loaded state is never considered newer than it is.
+        let slot = rpc.get_slot().await?;
+        let accounts = rpc.get_multiple_accounts(fusion_pools).await?;
+
+        let mut tracker = Self::new();
+        for (address, account) in zip(fusion_pools, accounts) {
+            let account = account.ok_or(format!("Fusion pool {} not found", address))?;
+            tracker.track_fusion_pool(*address);
+            tracker.apply(AccountUpdate {
+                address: *address,
+                slot,
+                account: Some(account),
+            })?;
+        }
+
+        let accounts = rpc.get_multiple_accounts(tick_arrays).await?;
+        for (address, account) in zip(tick_arrays, accounts) {
+            if account.is_some() {
+                tracker.apply(AccountUpdate {
+                    address: *address,
+                    slot,
+                    account,
+                })?;
+            }
+        }
+
+        Ok(tracker)
+    }
+
+    /// Starts tracking a fusion pool. Its state is filled in by the next updates of the pool and its tick arrays.
+    pub fn track_fusion_pool(&mut self, address: Pubkey) {
+        self.fusion_pools
+            .entry(address)
+            .or_insert(TrackedFusionPool { slot: 0, fusion_pool: None });
+    }
+
+    /// Stops tracking a fusion pool and drops its tick arrays.
+    pub fn untrack_fusion_pool(&mut self, address: &Pubkey) {
+        self.fusion_pools.remove(address);
+        self.tick_arrays.retain(|_, tick_array| tick_array.fusion_pool != *address);
+    }
+
+    /// Applies an account update.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the address of the fusion pool whose state changed,
+    /// or `None` if the update is stale or unrelated to the tracked fusion pools.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the account data of a tracked fusion pool or of a tick array is invalid.
+    pub fn apply(&mut self, update: AccountUpdate) -> Result<Option<Pubkey>, Box<dyn Error>> {
+        // Closed accounts are reported either without an account or as an empty account owned by the system program.
+        let account = update.account.filter(|account| account.lamports > 0 && account.owner == FUSIONAMM_ID);
+
+        if let Some(tracked) = self.fusion_pools.get_mut(&update.address) {
+            if update.slot < tracked.slot {
+                return Ok(None);
+            }
+            tracked.fusion_pool = account.map(|account| FusionPool::from_bytes(&account.data)).transpose()?;
+            tracked.slot = update.slot;
+            return Ok(Some(update.address));
+        }
+
+        let tick_array = match account {
+            Some(account) if account.data.starts_with(&TICK_ARRAY_DISCRIMINATOR) => Some(TickArray::from_bytes(&account.data)?),
+            Some(_) => return Ok(None),
+            None => None,
+        };
+
+        let fusion_pool = match (&tick_array, self.tick_arrays.get(&update.address)) {
+            (Some(tick_array), _) => tick_array.fusion_pool,
+            (None, Some(tracked)) => tracked.fusion_pool,
+            (None, None) => return Ok(None),
+        };
+        if !self.fusion_pools.contains_key(&fusion_pool) {
+            return Ok(None);
+        }
+
+        let tracked = self.tick_arrays.entry(update.address).or_insert(TrackedTickArray {
+            fusion_pool,
+            slot: update.slot,
+            tick_array: None,
+        });
+        if update.slot < tracked.slot {
+            return Ok(None);
+        }
+        tracked.slot = update.slot;
+        tracked.tick_array = tick_array.map(|tick_array| tick_array.into());
+
+        Ok(Some(fusion_pool))
+    }
+
+    /// Waits for account updates from a stream and applies them until the state of a tracked fusion pool changes.
+    ///
+    /// # Returns
+    ///
+    /// The address of the fusion pool whose state changed, or `None` once the stream has ended.
+    pub async fn next_change(&mut self, stream: &mut impl AccountUpdateStream) -> Option<Result<Pubkey, Box<dyn Error>>> {
+        while let Some(update) = stream.next_update().await {
+            if let Some(result) = self.apply(update).transpose() {
+                return Some(result);
+            }
+        }
+        None
+    }
+
+    /// Returns the latest state of a tracked fusion pool.
+    pub fn fusion_pool(&self, address: &Pubkey) -> Option<&FusionPool> {
+        self.fusion_pools.get(address)?.fusion_pool.as_ref()
+    }
+
+    /// Returns the latest state of a tracked fusion pool as a facade for quoting.
+    pub fn fusion_pool_facade(&self, address: &Pubkey) -> Option<FusionPoolFacade> {
+        self.fusion_pool(address).map(|fusion_pool| fusion_pool.clone().into())
+    }
+
+    /// Returns the slot of the latest update of a tracked fusion pool.
+    pub fn slot(&self, address: &Pubkey) -> Option<u64> {
+        Some(self.fusion_pools.get(address)?.slot)
+    }
+
+    /// Returns the latest state of the tick arrays of a tracked fusion pool, ordered by start tick index.
+    pub fn tick_arrays(&self, address: &Pubkey) -> Vec<TickArrayFacade> {
+        let mut tick_arrays: Vec<TickArrayFacade> = self
+            .tick_arrays
+            .values()
+            .filter(|tracked| tracked.fusion_pool == *address)
+            .filter_map(|tracked| tracked.tick_array)
+            .collect();
+        tick_arrays.sort_by_key(|tick_array| tick_array.start_tick_index);
+        tick_arrays
+    }
+
+    /// Returns the latest state of the tick arrays of a tracked fusion pool as a tick sequence for quoting.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the fusion pool is not tracked or has no tick arrays.
+    pub fn tick_array_sequence(&self, address: &Pubkey) -> Result<TickArraySequence, Box<dyn Error>> {
+        let fusion_pool = self.fusion_pool(address).ok_or(format!("Fusion pool {} is not tracked", address))?;
+        Ok(TickArraySequence::new(self.tick_arrays(address), fusion_pool.tick_spacing)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{setup_fusion_pool, setup_mint_with_decimals, setup_position, RpcContext};
+    use crate::AccountSnapshot;
+    use borsh::BorshSerialize;
+    use fusionamm_client::get_tick_array_address;
+    use fusionamm_core::get_tick_array_start_tick_index;
+    use serial_test::serial;
+    use solana_program::rent::Rent;
+    use solana_program_test::tokio;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_pool_state_tracker() {
+        let ctx = RpcContext::new().await;
+        let mint_1 = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_2 = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_1 < mint_2 { (mint_1, mint_2) } else { (mint_2, mint_1) };
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await.unwrap();
+        setup_position(&ctx, pool_address, Some((-192, 192)), None).await.unwrap();
+
+        let tick_array_addresses: Vec<Pubkey> = [-192, 192]
+            .iter()
+            .map(|&tick_index| {
+                get_tick_array_address(&pool_address, get_tick_array_start_tick_index(tick_index, 64))
+                    .unwrap()
+                    .0
+            })
+            .collect();
+        let pool_account = ctx.rpc.get_account(&pool_address).await.unwrap();
+        let tick_array_accounts: Vec<Account> = ctx
+            .rpc
+            .get_multiple_accounts(&tick_array_addresses)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|account| account.unwrap())
+            .collect();
+
+        // The tracker loads from any account source and skips missing tick arrays.
+        let mut snapshot = AccountSnapshot::new(0, 5, Rent::default());
+        snapshot.insert(pool_address, pool_account.clone());
+        for (address, account) in zip(&tick_array_addresses, &tick_array_accounts) {
+            snapshot.insert(*address, account.clone());
+        }
+        let tick_array_candidates = [tick_array_addresses.clone(), vec![Pubkey::new_unique()]].concat();
+        let loaded = PoolStateTracker::load(&snapshot, &[pool_address], &tick_array_candidates).await.unwrap();
+        assert_eq!(loaded.slot(&pool_address), Some(5));
+        assert_eq!(loaded.tick_arrays(&pool_address).len(), 2);
+        assert!(PoolStateTracker::load(&snapshot, &[Pubkey::new_unique()], &[]).await.is_err());
+
+        let (sender, mut stream) = ChannelAccountUpdateStream::channel();
+        let mut tracker = PoolStateTracker::new();
+        tracker.track_fusion_pool(pool_address);
+
+        sender
+            .send(AccountUpdate {
+                address: Pubkey::new_unique(),
+                slot: 10,
+                account: Some(pool_account.clone()),
+            })
+            .unwrap();
+        sender
+            .send(AccountUpdate {
+                address: pool_address,
+                slot: 10,
+                account: Some(pool_account.clone()),
+            })
+            .unwrap();
+        for (address, account) in zip(&tick_array_addresses, &tick_array_accounts) {
+            sender
+                .send(AccountUpdate {
+                    address: *address,
+                    slot: 10,
+                    account: Some(account.clone()),
+                })
+                .unwrap();
+        }
+        drop(sender);
+
+        for _ in 0..3 {
+            assert_eq!(tracker.next_change(&mut stream).await.unwrap().unwrap(), pool_address);
+        }
+        assert!(tracker.next_change(&mut stream).await.is_none());
+
+        let pool = FusionPool::from_bytes(&pool_account.data).unwrap();
+        let tick_arrays: Vec<TickArrayFacade> = tick_array_accounts
+            .iter()
+            .map(|account| TickArray::from_bytes(&account.data).unwrap().into())
+            .collect();
+        assert_eq!(tracker.slot(&pool_address), Some(10));
+        assert_eq!(tracker.fusion_pool_facade(&pool_address), Some(pool.clone().into()));
+        assert_eq!(tracker.tick_array_sequence(&pool_address).unwrap(), TickArraySequence::new(tick_arrays.clone(), 64).unwrap());
+
+        // A stale update is ignored, a newer one is applied.
+        let mut updated_pool = pool.clone();
+        updated_pool.liquidity += 1;
+        let updated_pool_account = Account {
+            data: updated_pool.try_to_vec().unwrap(),
+            ..pool_account.clone()
+        };
+        let stale_update = AccountUpdate {
+            address: pool_address,
+            slot: 9,
+            account: Some(updated_pool_account.clone()),
+        };
+        assert_eq!(tracker.apply(stale_update.clone()).unwrap(), None);
+        assert_eq!(tracker.fusion_pool(&pool_address), Some(&pool));
+        assert_eq!(tracker.apply(AccountUpdate { slot: 11, ..stale_update }).unwrap(), Some(pool_address));
+        assert_eq!(tracker.fusion_pool(&pool_address), Some(&updated_pool));
+
+        // A closed tick array is dropped and is not brought back by a stale update.
+        let closed_tick_array = AccountUpdate {
+            address: tick_array_addresses[0],
+            slot: 12,
+            account: None,
+        };
+        assert_eq!(tracker.apply(closed_tick_array).unwrap(), Some(pool_address));
+        assert_eq!(tracker.tick_arrays(&pool_address), tick_arrays[1..]);
+        let stale_tick_array = AccountUpdate {
+            address: tick_array_addresses[0],
+            slot: 10,
+            account: Some(tick_array_accounts[0].clone()),
+        };
+        assert_eq!(tracker.apply(stale_tick_array).unwrap(), None);
+        assert_eq!(tracker.tick_arrays(&pool_address), tick_arrays[1..]);
+
+        tracker.untrack_fusion_pool(&pool_address);
+        assert!(tracker.fusion_pool(&pool_address).is_none());
+        assert!(tracker.tick_arrays(&pool_address).is_empty());
+    }
+}
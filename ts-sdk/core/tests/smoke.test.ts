@@ -45,6 +45,8 @@ function testFusionPool(): FusionPoolFacade {
     ordersFilledAmountB: 30000n,
     olpFeeOwedA: 50n,
     olpFeeOwedB: 60n,
+    maSqrtPrice: 1n << 64n,
+    lastSwapTimestamp: 0n,
   };
 }
 